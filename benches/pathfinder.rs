@@ -0,0 +1,28 @@
+//! Paths across a 64x64 room with walls in the way, each has to take less than 1ms.
+//! `cargo bench --bench pathfinder`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use sulove::habbohotel::rooms::RoomLayout;
+use sulove::util::pathfinding::{Pathfinder, PathfinderOptions, WalkOptions};
+
+/// Every eighth column is a wall with a gap at the top or the bottom, alternating
+fn large_room() -> RoomLayout {
+    let rows: Vec<String> = (0..64)
+        .map(|y| (0..64).map(|x| if x % 8 == 4 && y % 32 != (x / 8) % 2 * 31 { 'x' } else { '0' }).collect())
+        .collect();
+
+    RoomLayout::parse("model_bench", 0, 0, 2, &rows.join("\r\n")).unwrap()
+}
+
+fn bench_large_room(c: &mut Criterion) {
+    let room = large_room();
+    let pathfinder = Pathfinder::new(PathfinderOptions::default());
+
+    c.bench_function("find_path 64x64", |b| {
+        b.iter(|| pathfinder.find_path(&room, (0, 0), (63, 63), WalkOptions::default(), |_, _| false).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_large_room);
+criterion_main!(benches);
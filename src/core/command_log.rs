@@ -0,0 +1,43 @@
+use crate::core::database_loggable::{DatabaseLoggable, LogValue};
+
+/// A command someone used, written to `commandlogs` by the `DatabaseLogger`
+pub struct CommandLog {
+    user_id: i32,
+    timestamp: i64,
+    command: String,
+    params: String,
+    success: bool,
+}
+
+impl CommandLog {
+    pub fn new(user_id: i32, command: &str, params: &str, success: bool) -> Self {
+        // Cut to the size of the columns
+        CommandLog {
+            user_id,
+            timestamp: crate::get_unix_timestamp() as i64,
+            command: command.chars().take(256).collect(),
+            params: params.chars().take(100).collect(),
+            success,
+        }
+    }
+}
+
+impl DatabaseLoggable for CommandLog {
+    fn get_table(&self) -> &'static str {
+        "commandlogs"
+    }
+
+    fn get_columns(&self) -> &'static [&'static str] {
+        &["user_id", "timestamp", "command", "params", "succes"]
+    }
+
+    fn get_values(&self) -> Vec<LogValue> {
+        vec![
+            self.user_id.into(),
+            self.timestamp.into(),
+            self.command.as_str().into(),
+            self.params.as_str().into(),
+            if self.success { "yes" } else { "no" }.into(),
+        ]
+    }
+}
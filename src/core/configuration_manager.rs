@@ -1,115 +1,454 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::error::Error;
+use std::fs;
+use std::io;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-use log::{error, info};
-use sqlx::MySqlPool;
+use log::{info, warn};
 
-#[derive(Debug)]
+use crate::database::repositories::SettingsRepository;
+
+/// Prefix of the environment variables that override settings, `SULOVE_DB_HOST` sets `db.host`
+const ENVIRONMENT_PREFIX: &str = "SULOVE_";
+
+/// Where a setting came from, later sources override earlier ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    File,
+    Environment,
+    Database,
+    Runtime,
+}
+
+/// The type a setting is expected to have, see `validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    String,
+    Int,
+    Bool,
+    Duration,
+    List,
+}
+
+/// Every setting the emulator reads from the file or the environment.
+/// The `emulator_settings` table holds far more, those aren't checked.
+const KNOWN_SETTINGS: &[(&str, SettingKind)] = &[
+    ("console.mode", SettingKind::Bool),
+    ("db.driver", SettingKind::String),
+    ("db.host", SettingKind::String),
+    ("db.migrations.auto", SettingKind::Bool),
+    ("db.name", SettingKind::String),
+    ("db.password", SettingKind::String),
+    ("db.port", SettingKind::Int),
+    ("db.sqlite.path", SettingKind::String),
+    ("db.username", SettingKind::String),
+    ("debug.mode", SettingKind::Bool),
+    ("enc.d", SettingKind::String),
+    ("enc.dh.bits", SettingKind::Int),
+    ("enc.e", SettingKind::String),
+    ("enc.enabled", SettingKind::Bool),
+    ("enc.n", SettingKind::String),
+    ("game.host", SettingKind::String),
+    ("game.port", SettingKind::Int),
+    ("imager.internal.enabled", SettingKind::Bool),
+    ("imager.location.badgeparts", SettingKind::String),
+    ("imager.location.output.badges", SettingKind::String),
+    ("io.bossgroup.threads", SettingKind::Int),
+    ("io.client.queue.overflow", SettingKind::String),
+    ("io.client.queue.size", SettingKind::Int),
+    ("io.idle.ping_interval", SettingKind::Duration),
+    ("io.idle.pong_timeout", SettingKind::Duration),
+    ("io.ratelimit.ban_after", SettingKind::Int),
+    ("io.ratelimit.ban_minutes", SettingKind::Int),
+    ("io.ratelimit.disconnect_after", SettingKind::Int),
+    ("io.ratelimit.enabled", SettingKind::Bool),
+    ("io.ratelimit.max", SettingKind::Int),
+    ("io.ratelimit.overrides", SettingKind::List),
+    ("io.ratelimit.reset_ms", SettingKind::Int),
+    ("io.ratelimit.warn_after", SettingKind::Int),
+    ("io.workergroup.threads", SettingKind::Int),
+    ("logging.database.batch_size", SettingKind::Int),
+    ("logging.database.interval", SettingKind::Duration),
+    ("logging.errors.database", SettingKind::Bool),
+    ("logging.errors.file", SettingKind::String),
+    ("logging.errors.file.max_files", SettingKind::Int),
+    ("logging.errors.file.max_size", SettingKind::Int),
+    ("logging.errors.sql_window", SettingKind::Duration),
+    ("packet_handling.multi_threaded", SettingKind::Bool),
+    ("packets.revision", SettingKind::String),
+    ("rcon.allowed", SettingKind::List),
+    ("rcon.host", SettingKind::String),
+    ("rcon.port", SettingKind::Int),
+    ("rooms.unload_after", SettingKind::Duration),
+    ("runtime.threads", SettingKind::Int),
+    ("seasonal.primary.type", SettingKind::Int),
+    ("shutdown.countdown", SettingKind::Int),
+    ("shutdown.timeout", SettingKind::Duration),
+    ("texts.file", SettingKind::String),
+    ("texts.language", SettingKind::String),
+];
+
+#[derive(Debug, Clone)]
+struct ConfigValue {
+    value: String,
+    source: ConfigSource,
+}
+
+type Subscriber = Arc<dyn Fn(&ConfigurationManager) + Send + Sync>;
+
+/// Problems found by `ConfigurationManager::validate`
+#[derive(Debug, Default)]
+pub struct ConfigReport {
+    pub unknown_keys: Vec<String>,
+    pub malformed: Vec<String>,
+}
+
+impl ConfigReport {
+    pub fn is_ok(&self) -> bool {
+        self.unknown_keys.is_empty() && self.malformed.is_empty()
+    }
+
+    pub fn log(&self) {
+        for key in &self.unknown_keys {
+            warn!("Unknown setting {}, is it misspelled?", key);
+        }
+        for problem in &self.malformed {
+            warn!("{}", problem);
+        }
+    }
+}
+
+/// The emulator's settings, layered: the config file first, then `SULOVE_*` environment
+/// variables, then the `emulator_settings` table. A later layer overrides an earlier one.
 pub struct ConfigurationManager {
     config_path: String,
-    values: RwLock<HashMap<String, String>>,
-    pub loaded: bool,
+    values: RwLock<HashMap<String, ConfigValue>>,
+    subscribers: RwLock<Vec<Subscriber>>,
 }
 
 impl ConfigurationManager {
+    /// Loads the file and the environment, the database is loaded once it is connected
     pub fn new(config_path: &str) -> Result<Self, io::Error> {
-        let mut manager = ConfigurationManager {
+        let manager = ConfigurationManager {
             config_path: config_path.to_string(),
             values: RwLock::new(HashMap::new()),
-            loaded: false,
+            subscribers: RwLock::new(Vec::new()),
         };
-        
-        manager.load_from_file()?;
-        
+
+        let mut values = Self::read_file(config_path)?;
+        Self::apply_environment(&mut values, std::env::vars());
+        *manager.values.write().unwrap() = values;
+
         Ok(manager)
     }
-    
-    fn load_from_file(&self) -> Result<(), io::Error> {
-        let file = File::open(&self.config_path)?;
-        let reader = BufReader::new(file);
-        
-        let mut values = self.values.write().unwrap();
-        
-        for line in reader.lines() {
-            let line = line?;
+
+    fn read_file(config_path: &str) -> Result<HashMap<String, ConfigValue>, io::Error> {
+        let content = fs::read_to_string(config_path)?;
+        let values = Self::parse(&content);
+
+        info!("Loaded {} settings from {}", values.len(), config_path);
+
+        Ok(values)
+    }
+
+    /// Parses `key=value` lines. Keys below a `[section]` header are prefixed with `section.`
+    fn parse(content: &str) -> HashMap<String, ConfigValue> {
+        let mut values = HashMap::new();
+        let mut section = String::new();
+
+        for line in content.lines() {
             let line = line.trim();
-            
+
             // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                section = name.trim().to_string();
                 continue;
             }
-            
+
             // Parse key=value pairs
-            if let Some(pos) = line.find('=') {
-                let key = line[..pos].trim().to_string();
-                let value = line[pos + 1..].trim().to_string();
-                values.insert(key, value);
+            if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim();
+                let key = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+                values.insert(key, ConfigValue { value: value.trim().to_string(), source: ConfigSource::File });
             }
         }
-        
-        info!("Loaded {} settings from {}", values.len(), self.config_path);
-        
-        Ok(())
+
+        values
     }
-    
-    pub fn load_from_database(&self) -> Result<(), Box<dyn std::error::Error>> {
-        if !self.loaded {
-            return Ok(());
+
+    /// Applies `SULOVE_*` variables. `SULOVE_DB_HOST` matches `db.host`, and since keys can contain
+    /// underscores themselves, a variable is matched against the keys that are already known first.
+    fn apply_environment(values: &mut HashMap<String, ConfigValue>, vars: impl Iterator<Item = (String, String)>) {
+        let known: HashMap<String, String> = values.keys().cloned()
+            .chain(KNOWN_SETTINGS.iter().map(|(key, _)| key.to_string()))
+            .map(|key| (key.replace('.', "_"), key))
+            .collect();
+
+        for (name, value) in vars {
+            let Some(name) = name.strip_prefix(ENVIRONMENT_PREFIX) else {
+                continue;
+            };
+
+            let name = name.to_lowercase();
+            let key = known.get(&name).cloned().unwrap_or_else(|| name.replace('_', "."));
+            values.insert(key, ConfigValue { value, source: ConfigSource::Environment });
         }
-        
-        // Get database connection from the main database pool
-        let pool = crate::get_database().get_pool();
-        
-        // Load settings from database
-        let rows = sqlx::query!("SELECT `key`, `value` FROM emulator_settings")
-            .fetch_all(pool)
-            .map_err(|e| {
-                error!("Failed to load settings from database: {}", e);
-                e
-            })?;
-        
+    }
+
+    /// Loads the `emulator_settings` table on top of the file and the environment
+    pub async fn load_from_database(&self, settings: &dyn SettingsRepository) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let rows = settings.get_settings().await?;
+
+        let count = rows.len();
         let mut values = self.values.write().unwrap();
-        
-        for row in rows {
-            values.insert(row.key, row.value);
+
+        for (key, value) in rows {
+            values.insert(key, ConfigValue { value, source: ConfigSource::Database });
         }
-        
-        info!("Loaded {} settings from database", rows.len());
-        
+
+        info!("Loaded {} settings from database", count);
+
         Ok(())
     }
-    
+
+    /// Reads every layer again and tells the subscribers. Settings set at runtime are dropped.
+    pub async fn reload(&self, settings: Option<&dyn SettingsRepository>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut values = Self::read_file(&self.config_path)?;
+        Self::apply_environment(&mut values, std::env::vars());
+        *self.values.write().unwrap() = values;
+
+        if let Some(settings) = settings {
+            self.load_from_database(settings).await?;
+        }
+
+        self.notify_subscribers();
+        Ok(())
+    }
+
+    /// Calls `subscriber` after every reload, for anything that keeps settings around
+    pub fn subscribe(&self, subscriber: impl Fn(&ConfigurationManager) + Send + Sync + 'static) {
+        self.subscribers.write().unwrap().push(Arc::new(subscriber));
+    }
+
+    fn notify_subscribers(&self) {
+        // Cloned so a subscriber can subscribe or read settings without deadlocking
+        let subscribers = self.subscribers.read().unwrap().clone();
+        for subscriber in subscribers {
+            subscriber(self);
+        }
+    }
+
+    /// Reports settings from the file or the environment nobody reads, and known settings
+    /// that don't parse as their type
+    pub fn validate(&self) -> ConfigReport {
+        let values = self.values.read().unwrap();
+        let mut report = ConfigReport::default();
+
+        for (key, value) in values.iter() {
+            let kind = KNOWN_SETTINGS.iter().find(|(known, _)| known == key).map(|(_, kind)| *kind);
+
+            let kind = match kind {
+                Some(kind) => kind,
+                None => {
+                    if matches!(value.source, ConfigSource::File | ConfigSource::Environment) {
+                        report.unknown_keys.push(key.clone());
+                    }
+                    continue;
+                }
+            };
+
+            let valid = match kind {
+                SettingKind::String | SettingKind::List => true,
+                SettingKind::Int => value.value.parse::<i64>().is_ok(),
+                SettingKind::Bool => Self::parse_bool(&value.value).is_ok(),
+                SettingKind::Duration => Self::parse_duration(&value.value).is_ok(),
+            };
+
+            if !valid {
+                report.malformed.push(format!("Setting {} = '{}' is not a valid {:?}", key, value.value, kind));
+            }
+        }
+
+        report.unknown_keys.sort();
+        report.malformed.sort();
+        report
+    }
+
     pub fn get_string(&self, key: &str) -> Result<String, String> {
         let values = self.values.read().unwrap();
-        
+
         values.get(key)
-            .cloned()
+            .map(|value| value.value.clone())
             .ok_or_else(|| format!("Configuration key not found: {}", key))
     }
-    
+
     pub fn get_int(&self, key: &str) -> Result<i32, String> {
         let value = self.get_string(key)?;
-        
+
         value.parse::<i32>()
             .map_err(|e| format!("Failed to parse '{}' as integer: {}", value, e))
     }
-    
+
+    pub fn get_i64(&self, key: &str) -> Result<i64, String> {
+        let value = self.get_string(key)?;
+
+        value.parse::<i64>()
+            .map_err(|e| format!("Failed to parse '{}' as integer: {}", value, e))
+    }
+
+    pub fn get_f64(&self, key: &str) -> Result<f64, String> {
+        let value = self.get_string(key)?;
+
+        value.parse::<f64>()
+            .map_err(|e| format!("Failed to parse '{}' as number: {}", value, e))
+    }
+
     pub fn get_bool(&self, key: &str) -> Result<bool, String> {
+        Self::parse_bool(&self.get_string(key)?)
+    }
+
+    /// A duration like `500ms`, `30s`, `5m`, `2h` or `1d`, a plain number is in seconds
+    pub fn get_duration(&self, key: &str) -> Result<Duration, String> {
+        Self::parse_duration(&self.get_string(key)?)
+    }
+
+    /// A list separated by `,` or `;`, empty entries are skipped
+    pub fn get_list(&self, key: &str) -> Result<Vec<String>, String> {
         let value = self.get_string(key)?;
-        
+
+        Ok(value.split([',', ';'])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    pub fn get_source(&self, key: &str) -> Option<ConfigSource> {
+        self.values.read().unwrap().get(key).map(|value| value.source)
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        let mut values = self.values.write().unwrap();
+        values.insert(key.to_string(), ConfigValue { value: value.to_string(), source: ConfigSource::Runtime });
+        Ok(())
+    }
+
+    fn parse_bool(value: &str) -> Result<bool, String> {
         match value.to_lowercase().as_str() {
             "true" | "1" | "yes" | "on" => Ok(true),
             "false" | "0" | "no" | "off" => Ok(false),
             _ => Err(format!("Failed to parse '{}' as boolean", value)),
         }
     }
-    
-    pub fn set(&self, key: &str, value: &str) -> Result<(), String> {
-        let mut values = self.values.write().unwrap();
-        values.insert(key.to_string(), value.to_string());
-        Ok(())
+
+    fn parse_duration(value: &str) -> Result<Duration, String> {
+        let value = value.trim();
+        let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+
+        let number: u64 = number.parse()
+            .map_err(|_| format!("Failed to parse '{}' as duration", value))?;
+
+        match unit.trim() {
+            "ms" => Ok(Duration::from_millis(number)),
+            "" | "s" => Ok(Duration::from_secs(number)),
+            "m" => Ok(Duration::from_secs(number * 60)),
+            "h" => Ok(Duration::from_secs(number * 3600)),
+            "d" => Ok(Duration::from_secs(number * 86400)),
+            _ => Err(format!("Failed to parse '{}' as duration, unknown unit", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn manager(content: &str, vars: &[(&str, &str)]) -> ConfigurationManager {
+        let mut values = ConfigurationManager::parse(content);
+        ConfigurationManager::apply_environment(
+            &mut values,
+            vars.iter().map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+
+        ConfigurationManager {
+            config_path: String::new(),
+            values: RwLock::new(values),
+            subscribers: RwLock::new(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_layers() {
+        let config = manager(
+            "# comment\ngame.port=30000\n[db]\nhost = localhost\nport=3306\n",
+            &[("SULOVE_DB_HOST", "mysql"), ("SULOVE_PACKET_HANDLING_MULTI_THREADED", "true"), ("PATH", "/bin")],
+        );
+
+        assert_eq!(config.get_int("game.port"), Ok(30000));
+        assert_eq!(config.get_int("db.port"), Ok(3306));
+        assert_eq!(config.get_string("db.host").unwrap(), "mysql");
+        assert_eq!(config.get_source("db.host"), Some(ConfigSource::Environment));
+        assert_eq!(config.get_bool("packet_handling.multi_threaded"), Ok(true));
+        assert!(config.get_string("path").is_err());
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let config = manager(
+            "big=8589934592\nratio=0.75\nfast=250ms\nslow=2m\nplain=30\nbad=5 weeks\nlist=a; b,,c\n",
+            &[],
+        );
+
+        assert_eq!(config.get_i64("big"), Ok(8589934592));
+        assert!(config.get_int("big").is_err());
+        assert_eq!(config.get_f64("ratio"), Ok(0.75));
+        assert_eq!(config.get_duration("fast"), Ok(Duration::from_millis(250)));
+        assert_eq!(config.get_duration("slow"), Ok(Duration::from_secs(120)));
+        assert_eq!(config.get_duration("plain"), Ok(Duration::from_secs(30)));
+        assert!(config.get_duration("bad").is_err());
+        assert_eq!(config.get_list("list").unwrap(), ["a", "b", "c"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_validate() {
+        let config = manager("game.port=thirty\ndb.hots=localhost\nshutdown.timeout=10s\n", &[("SULOVE_DEBUG_MODE", "maybe")]);
+
+        // Settings from the database aren't expected to be known
+        config.values.write().unwrap().insert(
+            String::from("hotel.catalog.discount"),
+            ConfigValue { value: String::from("1"), source: ConfigSource::Database },
+        );
+
+        let report = config.validate();
+        assert!(!report.is_ok());
+        assert_eq!(report.unknown_keys, ["db.hots"]);
+        assert_eq!(report.malformed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_notifies_subscribers() {
+        let path = std::env::temp_dir().join(format!("sulove-config-{}.ini", std::process::id()));
+        fs::write(&path, "game.port=30000\n").unwrap();
+
+        let config = ConfigurationManager::new(path.to_str().unwrap()).unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        config.subscribe(move |config| {
+            assert_eq!(config.get_int("game.port"), Ok(30001));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        fs::write(&path, "game.port=30001\n").unwrap();
+        config.reload(None).await.unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::Arc;
+use log::error;
+
+use crate::core::consolecommands::{
+    ConsoleInfoCommand, ConsoleReconnectCameraCommand, ConsoleReloadConfigCommand,
+    ConsoleShutdownCommand, ConsoleTestCommand,
+    ShowInteractionsCommand, ShowRconCommands, UpdateTextsCommand,
+};
+use crate::habbohotel::gameclients::GameClientManager;
+use crate::messages::rcon::handlers::RconMessageHandler;
+
+/// What console commands get to work with
+pub struct ConsoleContext {
+    game_client_manager: Arc<GameClientManager>,
+    rcon_message_handler: Arc<RconMessageHandler>,
+}
+
+impl ConsoleContext {
+    pub fn new(game_client_manager: Arc<GameClientManager>, rcon_message_handler: Arc<RconMessageHandler>) -> Self {
+        Self {
+            game_client_manager,
+            rcon_message_handler,
+        }
+    }
+
+    pub fn get_game_client_manager(&self) -> &Arc<GameClientManager> {
+        &self.game_client_manager
+    }
+
+    pub fn get_rcon_message_handler(&self) -> &Arc<RconMessageHandler> {
+        &self.rcon_message_handler
+    }
+}
+
+/// A command typed into the emulator's terminal
+pub trait ConsoleCommand: Send + Sync {
+    /// The word that runs the command
+    fn get_key(&self) -> &'static str;
+
+    /// Shown by `help`, the arguments come after the key separated by tabs
+    fn get_usage(&self) -> &'static str;
+
+    fn handle(&self, context: &ConsoleContext, args: &[&str]) -> Result<(), Box<dyn Error>>;
+}
+
+/// Every console command by key. `help` is answered by the manager itself since it lists the others.
+pub struct ConsoleCommandManager {
+    commands: BTreeMap<&'static str, Box<dyn ConsoleCommand>>,
+    context: ConsoleContext,
+}
+
+impl ConsoleCommandManager {
+    pub fn new(context: ConsoleContext) -> Self {
+        let mut manager = Self {
+            commands: BTreeMap::new(),
+            context,
+        };
+
+        manager.register(ConsoleInfoCommand);
+        manager.register(ConsoleShutdownCommand);
+        manager.register(ConsoleReconnectCameraCommand);
+        manager.register(ConsoleReloadConfigCommand);
+        manager.register(ShowInteractionsCommand);
+        manager.register(ShowRconCommands);
+        manager.register(ConsoleTestCommand);
+        manager.register(UpdateTextsCommand);
+
+        manager
+    }
+
+    pub fn register(&mut self, command: impl ConsoleCommand + 'static) {
+        self.commands.insert(command.get_key(), Box::new(command));
+    }
+
+    /// Runs a line from the terminal, returns false if there is no such command
+    pub fn handle(&self, line: &str) -> bool {
+        let (key, args) = match Self::parse_line(line) {
+            Some(parsed) => parsed,
+            None => return true,
+        };
+
+        if key == "help" {
+            self.print_help();
+            return true;
+        }
+
+        match self.commands.get(key) {
+            Some(command) => {
+                if let Err(e) = command.handle(&self.context, &args) {
+                    error!("Console command {} failed: {}", key, e);
+                }
+                true
+            }
+            None => {
+                println!("Unknown command '{}', type help for a list of commands", key);
+                false
+            }
+        }
+    }
+
+    /// Splits a line into the command key and its tab separated arguments
+    pub fn parse_line(line: &str) -> Option<(&str, Vec<&str>)> {
+        let mut parts = line.trim_matches(['\r', '\n']).split('\t');
+
+        let key = parts.next()?.trim();
+        if key.is_empty() {
+            return None;
+        }
+
+        Some((key, parts.map(str::trim).collect()))
+    }
+
+    fn print_help(&self) {
+        println!("Available commands, arguments are separated by tabs:");
+        println!("  help - Shows this list");
+        for command in self.commands.values() {
+            println!("  {} - {}", command.get_key(), command.get_usage());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::database::repositories::Repositories;
+    use crate::habbohotel::game_enviroment::GameEnvironment;
+    use crate::habbohotel::gameclients::QueueOverflowPolicy;
+    use crate::messages::rcon::commands::RconContext;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountArgs;
+
+    impl ConsoleCommand for CountArgs {
+        fn get_key(&self) -> &'static str {
+            "count"
+        }
+
+        fn get_usage(&self) -> &'static str {
+            "Counts its arguments"
+        }
+
+        fn handle(&self, _context: &ConsoleContext, args: &[&str]) -> Result<(), Box<dyn Error>> {
+            assert_eq!(args, ["first argument", "second"]);
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(ConsoleCommandManager::parse_line("info\n"), Some(("info", vec![])));
+        assert_eq!(ConsoleCommandManager::parse_line("alert\tuser one\t hi \r\n"), Some(("alert", vec!["user one", "hi"])));
+        assert_eq!(ConsoleCommandManager::parse_line("  \n"), None);
+    }
+
+    #[test]
+    fn test_handle() {
+        let game_client_manager = Arc::new(GameClientManager::new(16, QueueOverflowPolicy::Drop));
+        let rcon_message_handler = Arc::new(RconMessageHandler::new(RconContext::new(Arc::clone(&game_client_manager), Arc::new(GameEnvironment::new(Repositories::in_memory())))));
+        let mut manager = ConsoleCommandManager::new(ConsoleContext::new(game_client_manager, rcon_message_handler));
+        manager.register(CountArgs);
+
+        assert!(manager.handle("count\tfirst argument\tsecond"));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        assert!(manager.handle("help"));
+        assert!(manager.handle("test"));
+        assert!(!manager.handle("does_not_exist"));
+    }
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::fs;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Prints the uptime, the amount of users online and the memory in use
+pub struct ConsoleInfoCommand;
+
+impl ConsoleCommand for ConsoleInfoCommand {
+    fn get_key(&self) -> &'static str {
+        "info"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Shows the uptime, online users and memory usage"
+    }
+
+    fn handle(&self, context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let uptime = crate::get_time_started()
+            .map(|started| format_duration(crate::get_unix_timestamp().saturating_sub(started)))
+            .unwrap_or_else(|| String::from("still starting"));
+
+        println!("{}", crate::VERSION);
+        println!("Uptime: {}", uptime);
+        println!("Users online: {}", context.get_game_client_manager().get_online_count());
+        println!("Memory in use: {}", memory_usage().unwrap_or_else(|| String::from("unknown")));
+        println!("CPU cores: {}", num_cpus::get());
+
+        Ok(())
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{} days, {} hours, {} minutes, {} seconds",
+        seconds / 86400,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+// Resident memory of the process, only known on Linux
+fn memory_usage() -> Option<String> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(format!("{} MB", kilobytes / 1024))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(90061), "1 days, 1 hours, 1 minutes, 1 seconds");
+    }
+}
@@ -0,0 +1,29 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+use crate::networking::camera::CameraClient;
+
+/// Makes the camera client connect to the camera server again after it lost the connection
+pub struct ConsoleReconnectCameraCommand;
+
+impl ConsoleCommand for ConsoleReconnectCameraCommand {
+    fn get_key(&self) -> &'static str {
+        "reconnect_camera"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Reconnects to the camera server"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        // Nothing starts a camera client yet, so there is nothing to reconnect
+        if !CameraClient::is_running() {
+            println!("No camera client is connected.");
+            return Ok(());
+        }
+
+        CameraClient::request_reconnect();
+        println!("Attempting to reconnect to the camera server.");
+        Ok(())
+    }
+}
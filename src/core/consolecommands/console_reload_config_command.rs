@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Reads the config file, the environment and the `emulator_settings` table again
+pub struct ConsoleReloadConfigCommand;
+
+impl ConsoleCommand for ConsoleReloadConfigCommand {
+    fn get_key(&self) -> &'static str {
+        "reload_config"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Reloads the settings from the config file, the environment and the database"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let runtime = crate::get_runtime().ok_or("The thread pool isn't running")?;
+        let database = crate::get_database();
+
+        runtime.block_on(crate::get_config().reload(Some(database.get_repositories().get_settings().as_ref())))
+            .map_err(|e| e.to_string())?;
+
+        println!("Settings reloaded.");
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Stops the emulator, optionally after a countdown in minutes that users online are warned about
+pub struct ConsoleShutdownCommand;
+
+impl ConsoleCommand for ConsoleShutdownCommand {
+    fn get_key(&self) -> &'static str {
+        "shutdown"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "[minutes] Shuts the hotel down, by default after shutdown.countdown minutes"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let minutes = match args.first() {
+            Some(minutes) => minutes.parse::<u32>().map_err(|_| format!("'{}' is not a number of minutes", minutes))?,
+            None => crate::get_config().get_int("shutdown.countdown").unwrap_or(0).max(0) as u32,
+        };
+
+        println!("Shutting down in {} minute(s).", minutes);
+        crate::shutdown(minutes);
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Does nothing but answer, handy to check the console still reacts on a busy hotel
+pub struct ConsoleTestCommand;
+
+impl ConsoleCommand for ConsoleTestCommand {
+    fn get_key(&self) -> &'static str {
+        "test"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Checks the console is responding"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        println!("This is a test command for live debugging.");
+        if !args.is_empty() {
+            println!("Arguments: {:?}", args);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+// The trait and the registry
+pub mod console_command;
+
+// Commands
+pub mod console_info_command;
+pub mod console_reconnect_camera_command;
+pub mod console_reload_config_command;
+pub mod console_shutdown_command;
+pub mod console_test_command;
+pub mod show_interactions_command;
+pub mod show_rcon_commands;
+pub mod thankyou_sulove_command;
+pub mod update_texts_command;
+
+pub use console_command::{ConsoleCommand, ConsoleCommandManager, ConsoleContext};
+pub use console_info_command::ConsoleInfoCommand;
+pub use console_reconnect_camera_command::ConsoleReconnectCameraCommand;
+pub use console_reload_config_command::ConsoleReloadConfigCommand;
+pub use console_shutdown_command::ConsoleShutdownCommand;
+pub use console_test_command::ConsoleTestCommand;
+pub use show_interactions_command::ShowInteractionsCommand;
+pub use show_rcon_commands::ShowRconCommands;
+pub use update_texts_command::UpdateTextsCommand;
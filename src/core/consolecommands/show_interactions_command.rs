@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Lists the item interaction types furniture can use
+pub struct ShowInteractionsCommand;
+
+impl ConsoleCommand for ShowInteractionsCommand {
+    fn get_key(&self) -> &'static str {
+        "show_interactions"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Lists the item interactions"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let environment = crate::get_game_environment();
+        let names = environment.get_item_manager().get_interactions().get_names();
+
+        println!("{} item interactions:", names.len());
+        for name in names {
+            println!("  {}", name);
+        }
+
+        Ok(())
+    }
+}
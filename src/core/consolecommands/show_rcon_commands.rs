@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Lists the keys the RCON server accepts
+pub struct ShowRconCommands;
+
+impl ConsoleCommand for ShowRconCommands {
+    fn get_key(&self) -> &'static str {
+        "show_rcon_commands"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Lists the RCON commands"
+    }
+
+    fn handle(&self, context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let keys = context.get_rcon_message_handler().get_command_keys();
+
+        println!("{} RCON commands:", keys.len());
+        for key in keys {
+            println!("  {}", key);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use crate::core::consolecommands::console_command::{ConsoleCommand, ConsoleContext};
+
+/// Reloads the texts from the `emulator_texts` table, for translators fixing wording
+pub struct UpdateTextsCommand;
+
+impl ConsoleCommand for UpdateTextsCommand {
+    fn get_key(&self) -> &'static str {
+        "update_texts"
+    }
+
+    fn get_usage(&self) -> &'static str {
+        "Reloads the texts from the database"
+    }
+
+    fn handle(&self, _context: &ConsoleContext, _args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let runtime = crate::get_runtime().ok_or("The thread pool isn't running")?;
+        let database = crate::get_database();
+        let texts = crate::get_texts();
+
+        runtime.block_on(texts.load(Some(database.get_repositories().get_settings().as_ref())))
+            .map_err(|e| e.to_string())?;
+
+        println!("Reloaded {} texts.", texts.get_count());
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use log::warn;
+use num_bigint::BigInt;
+
+use crate::core::configuration_manager::ConfigurationManager;
+use crate::crypto::habbo_diffie_hellman::DEFAULT_DH_BIT_SIZE;
+
+/// Anything smaller is trivial to break, anything much larger makes startup crawl
+const DH_BIT_SIZE_RANGE: std::ops::RangeInclusive<u64> = 64..=2048;
+
+/// Settings for the RSA/Diffie-Hellman handshake and the RC4 stream encryption that follows it
+#[derive(Debug, Clone)]
+pub struct CryptoConfig {
+    enabled: bool,
+    exponent: String,
+    modulus: String,
+    private_exponent: String,
+    dh_bit_size: u64,
+}
+
+impl CryptoConfig {
+    pub fn new(enabled: bool, exponent: String, modulus: String, private_exponent: String, dh_bit_size: u64) -> Self {
+        CryptoConfig {
+            enabled,
+            exponent,
+            modulus,
+            private_exponent,
+            dh_bit_size,
+        }
+    }
+
+    /// Reads `enc.enabled`, the hex encoded RSA keys `enc.e`, `enc.n` and `enc.d` and the
+    /// Diffie-Hellman prime size `enc.dh.bits`.
+    /// Encryption stays off when it is enabled without a complete key set.
+    pub fn load(config: &ConfigurationManager) -> Self {
+        let exponent = config.get_string("enc.e").unwrap_or_default();
+        let modulus = config.get_string("enc.n").unwrap_or_default();
+        let private_exponent = config.get_string("enc.d").unwrap_or_default();
+
+        let mut enabled = config.get_bool("enc.enabled").unwrap_or(false);
+        if enabled && (exponent.is_empty() || modulus.is_empty() || private_exponent.is_empty()) {
+            warn!("Encryption is enabled but enc.e, enc.n or enc.d is missing, disabling encryption");
+            enabled = false;
+        }
+
+        let is_hex = |value: &str| BigInt::parse_bytes(value.as_bytes(), 16).is_some();
+        if enabled && !(is_hex(&exponent) && is_hex(&modulus) && is_hex(&private_exponent)) {
+            warn!("Encryption is enabled but enc.e, enc.n or enc.d is not a hex number, disabling encryption");
+            enabled = false;
+        }
+
+        let mut dh_bit_size = config.get_int("enc.dh.bits").map(|bits| bits as u64).unwrap_or(DEFAULT_DH_BIT_SIZE);
+        if !DH_BIT_SIZE_RANGE.contains(&dh_bit_size) {
+            warn!("enc.dh.bits must be between {} and {}, using {}",
+                DH_BIT_SIZE_RANGE.start(), DH_BIT_SIZE_RANGE.end(), DEFAULT_DH_BIT_SIZE);
+            dh_bit_size = DEFAULT_DH_BIT_SIZE;
+        }
+
+        CryptoConfig::new(enabled, exponent, modulus, private_exponent, dh_bit_size)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_exponent(&self) -> &str {
+        &self.exponent
+    }
+
+    pub fn get_modulus(&self) -> &str {
+        &self.modulus
+    }
+
+    pub fn get_private_exponent(&self) -> &str {
+        &self.private_exponent
+    }
+
+    pub fn get_dh_bit_size(&self) -> u64 {
+        self.dh_bit_size
+    }
+}
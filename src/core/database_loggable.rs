@@ -0,0 +1,43 @@
+/// A value of a logged row
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogValue {
+    Int(i64),
+    Text(String),
+}
+
+impl From<i32> for LogValue {
+    fn from(value: i32) -> Self {
+        LogValue::Int(value as i64)
+    }
+}
+
+impl From<i64> for LogValue {
+    fn from(value: i64) -> Self {
+        LogValue::Int(value)
+    }
+}
+
+impl From<&str> for LogValue {
+    fn from(value: &str) -> Self {
+        LogValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for LogValue {
+    fn from(value: String) -> Self {
+        LogValue::Text(value)
+    }
+}
+
+/// Something that happened in the hotel and ends up as a row in a log table, like a chat
+/// message or a command. Queue it on the `DatabaseLogger`, it is written in a batch later.
+pub trait DatabaseLoggable: Send + Sync {
+    /// Table the row goes into
+    fn get_table(&self) -> &'static str;
+
+    /// Columns of the row, every event of a table has the same ones
+    fn get_columns(&self) -> &'static [&'static str];
+
+    /// Values of the row, in the order of `get_columns`
+    fn get_values(&self) -> Vec<LogValue>;
+}
@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use log::{error, warn};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::core::database_loggable::{DatabaseLoggable, LogValue};
+use crate::core::disposable::Disposable;
+use crate::database::database_pool::{on_pool, DatabasePool};
+
+/// Most placeholders in one INSERT, older SQLite versions don't allow more
+const MAX_PLACEHOLDERS: usize = 999;
+
+/// Times a row is tried before it is given up on
+const MAX_ATTEMPTS: u32 = 5;
+
+struct QueuedEvent {
+    event: Box<dyn DatabaseLoggable>,
+    attempts: u32,
+    // A row that failed isn't tried again before this
+    retry_at: Option<Instant>,
+}
+
+/// Queues log rows and writes them behind the scenes, one multi-row INSERT per table.
+/// The queue is written every interval, or sooner once it holds a full batch. When a batch
+/// fails its rows are written one by one, a row that still fails waits longer after every attempt.
+pub struct DatabaseLogger {
+    pool: DatabasePool,
+    queue: Mutex<VecDeque<QueuedEvent>>,
+    batch_size: usize,
+    max_queued: usize,
+    retry_delay: Mutex<Duration>,
+    dropped: AtomicUsize,
+    batch_ready: Notify,
+    flushing: tokio::sync::Mutex<()>,
+    stopped: CancellationToken,
+}
+
+impl DatabaseLogger {
+    pub fn new(pool: DatabasePool, batch_size: usize) -> Self {
+        let batch_size = batch_size.max(1);
+
+        DatabaseLogger {
+            pool,
+            queue: Mutex::new(VecDeque::new()),
+            batch_size,
+            max_queued: batch_size * 100,
+            retry_delay: Mutex::new(Duration::from_secs(2)),
+            dropped: AtomicUsize::new(0),
+            batch_ready: Notify::new(),
+            flushing: tokio::sync::Mutex::new(()),
+            stopped: CancellationToken::new(),
+        }
+    }
+
+    /// Queues a row, it is written with the next batch. While the database is unreachable the
+    /// queue is capped, the oldest rows make room for new ones.
+    pub fn log<T: DatabaseLoggable + 'static>(&self, event: T) {
+        let mut queue = self.queue.lock().unwrap();
+
+        if queue.len() >= self.max_queued {
+            queue.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        queue.push_back(QueuedEvent { event: Box::new(event), attempts: 0, retry_at: None });
+
+        // Only when the batch fills up, rows that failed and wait for a retry don't count
+        if queue.len() == self.batch_size {
+            self.batch_ready.notify_one();
+        }
+    }
+
+    /// Rows waiting to be written
+    pub fn get_queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// How long a row waits after its first failed attempt, doubled after every next one
+    pub fn set_retry_delay(&self, retry_delay: Duration) {
+        *self.retry_delay.lock().unwrap() = retry_delay;
+    }
+
+    /// Writes the queue every `interval` until the logger is stopped
+    pub async fn run(self: Arc<Self>, interval: Duration) {
+        loop {
+            tokio::select! {
+                _ = self.stopped.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+                _ = self.batch_ready.notified() => {}
+            }
+
+            self.flush().await;
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stopped.cancel();
+    }
+
+    /// Writes everything queued and returns the number of rows written. Rows that failed go back
+    /// to the front of the queue and are skipped until their retry is due.
+    pub async fn flush(&self) -> usize {
+        self.flush_queue(false).await
+    }
+
+    async fn flush_queue(&self, ignore_retry_delay: bool) -> usize {
+        let _flushing = self.flushing.lock().await;
+
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!("Dropped {} log rows, the queue was full", dropped);
+        }
+
+        let now = Instant::now();
+        let events: Vec<QueuedEvent> = {
+            let mut queue = self.queue.lock().unwrap();
+            let (due, waiting): (Vec<_>, Vec<_>) = queue.drain(..)
+                .partition(|queued| ignore_retry_delay || queued.retry_at.is_none_or(|retry_at| retry_at <= now));
+            *queue = waiting.into();
+            due
+        };
+        if events.is_empty() {
+            return 0;
+        }
+
+        // Group by table, keeping the order the rows were logged in
+        let mut tables: Vec<(&'static str, Vec<QueuedEvent>)> = Vec::new();
+        for queued in events {
+            let table = queued.event.get_table();
+            match tables.iter_mut().find(|(name, _)| *name == table) {
+                Some((_, rows)) => rows.push(queued),
+                None => tables.push((table, vec![queued])),
+            }
+        }
+
+        let mut written = 0;
+        let mut failed = Vec::new();
+        let mut given_up = 0;
+
+        let mut unreachable = false;
+
+        for (table, mut rows) in tables {
+            let columns = rows[0].event.get_columns();
+            let rows_per_insert = (MAX_PLACEHOLDERS / columns.len().max(1)).clamp(1, self.batch_size);
+
+            while !rows.is_empty() {
+                let rest = rows.split_off(rows.len().min(rows_per_insert));
+                let batch = std::mem::replace(&mut rows, rest);
+
+                // Once the database can't be reached the rest waits for the next flush untried
+                if unreachable {
+                    failed.extend(batch);
+                    continue;
+                }
+
+                match self.insert(table, columns, &batch).await {
+                    Ok(()) => written += batch.len(),
+                    Err(e) => {
+                        error!("Failed to write {} log rows to {}: {}", batch.len(), table, e);
+
+                        // One bad row fails the whole INSERT, on their own the others still get written.
+                        // Connection errors fail every row the same, the batch is retried as a whole.
+                        let split = batch.len() > 1 && matches!(e, sqlx::Error::Database(_));
+                        unreachable = !matches!(e, sqlx::Error::Database(_));
+
+                        for mut queued in batch {
+                            if split && self.insert(table, columns, std::slice::from_ref(&queued)).await.is_ok() {
+                                written += 1;
+                                continue;
+                            }
+
+                            queued.attempts += 1;
+                            if queued.attempts < MAX_ATTEMPTS {
+                                queued.retry_at = Some(now + self.get_retry_delay(queued.attempts));
+                                failed.push(queued);
+                            } else {
+                                given_up += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if given_up > 0 {
+            error!("Gave up on {} log rows after {} attempts", given_up, MAX_ATTEMPTS);
+        }
+
+        // Ahead of whatever was logged while writing
+        let mut queue = self.queue.lock().unwrap();
+        for queued in failed.into_iter().rev() {
+            queue.push_front(queued);
+        }
+
+        written
+    }
+
+    fn get_retry_delay(&self, attempts: u32) -> Duration {
+        *self.retry_delay.lock().unwrap() * 2u32.pow(attempts.saturating_sub(1))
+    }
+
+    async fn insert(&self, table: &str, columns: &[&str], rows: &[QueuedEvent]) -> Result<(), sqlx::Error> {
+        let placeholders = format!("({})", vec!["?"; columns.len()].join(", "));
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            table,
+            columns.iter().map(|column| format!("`{}`", column)).collect::<Vec<_>>().join(", "),
+            vec![placeholders; rows.len()].join(", "),
+        );
+        let values: Vec<LogValue> = rows.iter().flat_map(|queued| queued.event.get_values()).collect();
+
+        on_pool!(&self.pool, pool => {
+            let mut query = sqlx::query(&sql);
+            for value in &values {
+                query = match value {
+                    LogValue::Int(value) => query.bind(*value),
+                    LogValue::Text(value) => query.bind(value.as_str()),
+                };
+            }
+            query.execute(pool).await?;
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Disposable for DatabaseLogger {
+    fn get_name(&self) -> &'static str {
+        "Database Logger"
+    }
+
+    async fn dispose(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.stop();
+
+        // Nothing flushes after the shutdown, rows waiting for a retry get their last chance now
+        self.flush_queue(true).await;
+
+        match self.get_queued() {
+            0 => Ok(()),
+            queued => Err(format!("{} log rows could not be written", queued).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::command_log::CommandLog;
+    use crate::database::database::Database;
+    use crate::database::migrations::{Migrator, MIGRATIONS};
+
+    async fn logger(batch_size: usize) -> DatabaseLogger {
+        let pool = DatabasePool::Sqlite(Database::connect_sqlite(":memory:").unwrap());
+        Migrator::new(MIGRATIONS).run(&pool, false).await.unwrap();
+        DatabaseLogger::new(pool, batch_size)
+    }
+
+    struct Unknown;
+
+    impl DatabaseLoggable for Unknown {
+        fn get_table(&self) -> &'static str {
+            "unknown"
+        }
+
+        fn get_columns(&self) -> &'static [&'static str] {
+            &["a"]
+        }
+
+        fn get_values(&self) -> Vec<LogValue> {
+            vec![1.into()]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_in_batches() {
+        let logger = logger(2).await;
+        for index in 0..5 {
+            logger.log(CommandLog::new(1, ":about", &index.to_string(), true));
+        }
+
+        assert_eq!(logger.get_queued(), 5);
+        assert_eq!(logger.flush().await, 5);
+        assert_eq!(logger.get_queued(), 0);
+
+        let count: i64 = on_pool!(&logger.pool, pool => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM commandlogs WHERE succes = 'yes'").fetch_one(pool).await.unwrap()
+        });
+        assert_eq!(count, 5);
+    }
+
+    /// A `commandlogs` row without values, its columns end up NULL
+    struct Broken;
+
+    impl DatabaseLoggable for Broken {
+        fn get_table(&self) -> &'static str {
+            "commandlogs"
+        }
+
+        fn get_columns(&self) -> &'static [&'static str] {
+            &["user_id", "timestamp", "command", "params", "succes"]
+        }
+
+        fn get_values(&self) -> Vec<LogValue> {
+            vec![]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_failed_rows() {
+        let logger = logger(10).await;
+        logger.set_retry_delay(Duration::ZERO);
+        logger.log(Unknown);
+        logger.log(CommandLog::new(1, ":about", "", false));
+
+        // The failing table doesn't hold up the others, its row waits for the next flush
+        assert_eq!(logger.flush().await, 1);
+        assert_eq!(logger.get_queued(), 1);
+
+        for _ in 1..MAX_ATTEMPTS {
+            logger.flush().await;
+        }
+        assert_eq!(logger.get_queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_bad_row_fails_alone() {
+        let logger = logger(10).await;
+        logger.log(CommandLog::new(1, ":about", "", true));
+        logger.log(Broken);
+        logger.log(CommandLog::new(1, ":about", "", true));
+
+        // The batch fails, its good rows are written one by one
+        assert_eq!(logger.flush().await, 2);
+        assert_eq!(logger.get_queued(), 1);
+
+        // The bad row backs off instead of being tried again on the next flush
+        assert_eq!(logger.flush().await, 0);
+        assert_eq!(logger.queue.lock().unwrap()[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_database_keeps_batch() {
+        let logger = logger(10).await;
+        logger.log(CommandLog::new(1, ":about", "", true));
+        logger.log(CommandLog::new(1, ":about", "", true));
+        logger.log(Unknown);
+        logger.pool.close().await;
+
+        // Nothing is tried row by row, the first failing table holds back the other one too
+        assert_eq!(logger.flush().await, 0);
+        let attempts: Vec<u32> = logger.queue.lock().unwrap().iter().map(|queued| queued.attempts).collect();
+        assert_eq!(attempts, [1, 1, 0]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use log::{error, info};
+use tokio::time::{timeout_at, Instant};
+
+/// Something holding state that has to be saved or released when the hotel shuts down,
+/// like users and rooms with changes that aren't in the database yet
+#[async_trait]
+pub trait Disposable: Send + Sync {
+    /// Name used in the shutdown log
+    fn get_name(&self) -> &'static str;
+
+    /// Saves what changed and releases what is held, called once during the shutdown
+    async fn dispose(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Everything that has to be disposed on shutdown, in the order it was registered
+pub struct DisposableRegistry {
+    disposables: Mutex<Vec<Arc<dyn Disposable>>>,
+}
+
+impl DisposableRegistry {
+    pub fn new() -> Self {
+        Self {
+            disposables: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, disposable: Arc<dyn Disposable>) {
+        self.disposables.lock().unwrap().push(disposable);
+    }
+
+    /// Disposes everything registered so far, each one gets whatever is left until the deadline.
+    /// A failure doesn't stop the rest. Returns false if anything failed or ran out of time.
+    pub async fn dispose_all(&self, deadline: Instant) -> bool {
+        let disposables: Vec<_> = self.disposables.lock().unwrap().drain(..).collect();
+        let mut success = true;
+
+        for disposable in disposables {
+            match timeout_at(deadline, disposable.dispose()).await {
+                Ok(Ok(())) => info!("Disposed {}", disposable.get_name()),
+                Ok(Err(e)) => {
+                    error!("Failed to dispose {}: {}", disposable.get_name(), e);
+                    success = false;
+                }
+                Err(_) => {
+                    error!("Ran out of time while disposing {}", disposable.get_name());
+                    success = false;
+                }
+            }
+        }
+
+        success
+    }
+}
+
+impl Default for DisposableRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+        delay: Duration,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Disposable for Recorder {
+        fn get_name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn dispose(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            tokio::time::sleep(self.delay).await;
+            self.log.lock().unwrap().push(self.name);
+            if self.fail {
+                return Err("failed".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn recorder(name: &'static str, log: &Arc<Mutex<Vec<&'static str>>>, delay: Duration, fail: bool) -> Arc<dyn Disposable> {
+        Arc::new(Recorder { name, log: Arc::clone(log), delay, fail })
+    }
+
+    #[tokio::test]
+    async fn test_dispose_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let registry = DisposableRegistry::new();
+        registry.register(recorder("users", &log, Duration::ZERO, false));
+        registry.register(recorder("rooms", &log, Duration::ZERO, true));
+        registry.register(recorder("logs", &log, Duration::ZERO, false));
+
+        // A failing one doesn't keep the others from saving
+        assert!(!registry.dispose_all(Instant::now() + Duration::from_secs(5)).await);
+        assert_eq!(*log.lock().unwrap(), ["users", "rooms", "logs"]);
+
+        // Everything is only disposed once
+        assert!(registry.dispose_all(Instant::now() + Duration::from_secs(5)).await);
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_dispose_is_bounded() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let registry = DisposableRegistry::new();
+        registry.register(recorder("stuck", &log, Duration::from_secs(60), false));
+
+        let started = std::time::Instant::now();
+        assert!(!registry.dispose_all(Instant::now() + Duration::from_millis(50)).await);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
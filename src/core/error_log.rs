@@ -0,0 +1,351 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use log::warn;
+use serde_json::json;
+
+use crate::core::configuration_manager::ConfigurationManager;
+use crate::core::database_loggable::{DatabaseLoggable, LogValue};
+use crate::core::database_logger::DatabaseLogger;
+use crate::util::debug_utils::DebugUtils;
+use crate::util::logback::SqlExceptionFilter;
+
+tokio::task_local! {
+    /// User id and header of the packet being handled, errors meanwhile are tagged with them
+    pub static PACKET_CONTEXT: (i32, i32);
+}
+
+thread_local! {
+    /// Panic of the packet handler unwinding on this thread, the packet manager reports it
+    /// once it caught the panic
+    static HANDLER_PANIC: RefCell<Option<ErrorLog>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorType {
+    Panic,
+    PacketError,
+    SqlError,
+}
+
+impl ErrorType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorType::Panic => "Panic",
+            ErrorType::PacketError => "PacketError",
+            ErrorType::SqlError => "SqlError",
+        }
+    }
+}
+
+/// An error staff can look into, a row of `emulator_errors`
+#[derive(Debug, Clone)]
+pub struct ErrorLog {
+    timestamp: i64,
+    error_type: ErrorType,
+    message: String,
+    user_id: i32,
+    packet_header: i32,
+    stacktrace: String,
+}
+
+impl ErrorLog {
+    /// An error tagged with the packet being handled if any
+    pub fn new(error_type: ErrorType, message: &str) -> Self {
+        let (user_id, packet_header) = PACKET_CONTEXT.try_with(|context| *context).unwrap_or((0, 0));
+
+        ErrorLog {
+            timestamp: crate::get_unix_timestamp() as i64,
+            error_type,
+            message: message.to_string(),
+            user_id,
+            packet_header,
+            stacktrace: String::new(),
+        }
+    }
+
+    /// An error with the chain of errors that caused it. A backtrace taken here
+    /// would only show where the error got logged, not where it happened.
+    pub fn from_error(error_type: ErrorType, error: &(dyn Error + 'static)) -> Self {
+        let mut causes = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(format!("Caused by: {}", cause));
+            source = cause.source();
+        }
+
+        ErrorLog { stacktrace: causes.join("\n"), ..Self::new(error_type, &error.to_string()) }
+    }
+
+    /// A panic with the location it happened at and the stack trace of the panicking thread
+    pub fn from_panic(info: &PanicHookInfo) -> Self {
+        let message = Self::get_panic_message(info.payload());
+        let message = match info.location() {
+            Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+            None => message.to_string(),
+        };
+
+        ErrorLog { stacktrace: DebugUtils::get_stacktrace(), ..Self::new(ErrorType::Panic, &message) }
+    }
+
+    /// A packet handler panic that was caught, as the panic hook saw it if it did
+    pub fn from_handler_panic(payload: &(dyn Any + Send)) -> Self {
+        HANDLER_PANIC.with(|panic| panic.borrow_mut().take())
+            .unwrap_or_else(|| Self::new(ErrorType::Panic, Self::get_panic_message(payload)))
+    }
+
+    fn get_panic_message(payload: &(dyn Any + Send)) -> &str {
+        payload.downcast_ref::<&str>().copied()
+            .or_else(|| payload.downcast_ref::<String>().map(|message| message.as_str()))
+            .unwrap_or("Box<dyn Any>")
+    }
+
+    pub fn with_packet(mut self, user_id: i32, packet_header: i32) -> Self {
+        self.user_id = user_id;
+        self.packet_header = packet_header;
+        self
+    }
+
+    pub fn get_error_type(&self) -> ErrorType {
+        self.error_type
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_user_id(&self) -> i32 {
+        self.user_id
+    }
+
+    pub fn get_packet_header(&self) -> i32 {
+        self.packet_header
+    }
+
+    pub fn get_stacktrace(&self) -> &str {
+        &self.stacktrace
+    }
+
+    /// One line of the error log file
+    pub fn to_json(&self) -> String {
+        json!({
+            "timestamp": self.timestamp,
+            "version": env!("CARGO_PKG_VERSION"),
+            "build_hash": option_env!("SULOVE_BUILD_HASH").unwrap_or(""),
+            "type": self.error_type.as_str(),
+            "message": self.message,
+            "user_id": self.user_id,
+            "packet_header": self.packet_header,
+            "stacktrace": self.stacktrace,
+        }).to_string()
+    }
+}
+
+impl DatabaseLoggable for ErrorLog {
+    fn get_table(&self) -> &'static str {
+        "emulator_errors"
+    }
+
+    fn get_columns(&self) -> &'static [&'static str] {
+        &["timestamp", "version", "build_hash", "type", "user_id", "packet_header", "stacktrace"]
+    }
+
+    fn get_values(&self) -> Vec<LogValue> {
+        vec![
+            self.timestamp.into(),
+            env!("CARGO_PKG_VERSION").into(),
+            option_env!("SULOVE_BUILD_HASH").unwrap_or("").into(),
+            self.error_type.as_str().into(),
+            self.user_id.into(),
+            self.packet_header.into(),
+            match self.stacktrace.is_empty() {
+                true => self.message.clone().into(),
+                false => format!("{}\n{}", self.message, self.stacktrace).into(),
+            },
+        ]
+    }
+}
+
+/// A JSON lines file of errors. Once it outgrows `max_size` it moves to `<path>.1`,
+/// older files shift up and the one past `max_files` is deleted.
+pub struct ErrorLogFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: u32,
+    lock: Mutex<()>,
+}
+
+impl ErrorLogFile {
+    pub fn new(path: &str, max_size: u64, max_files: u32) -> Self {
+        ErrorLogFile {
+            path: PathBuf::from(path),
+            max_size,
+            max_files,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn write(&self, error: &ErrorLog) -> io::Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let line = error.to_json() + "\n";
+
+        let size = fs::metadata(&self.path).map(|metadata| metadata.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+
+        for index in (1..self.max_files).rev() {
+            match fs::rename(self.get_rotated(index), self.get_rotated(index + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+
+        fs::rename(&self.path, self.get_rotated(1))
+    }
+
+    fn get_rotated(&self, index: u32) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.path.display(), index))
+    }
+}
+
+/// Sends errors to `emulator_errors` through the `DatabaseLogger`, to the error log file, or both.
+/// Repeats of the same SQL error are held back by the `SqlExceptionFilter`.
+pub struct ErrorLogger {
+    database_logger: Option<Arc<DatabaseLogger>>,
+    file: Option<ErrorLogFile>,
+    sql_filter: SqlExceptionFilter,
+}
+
+impl ErrorLogger {
+    pub fn new(database_logger: Option<Arc<DatabaseLogger>>, file: Option<ErrorLogFile>, sql_filter: SqlExceptionFilter) -> Self {
+        ErrorLogger { database_logger, file, sql_filter }
+    }
+
+    /// Error logger as configured by the `logging.errors.*` settings
+    pub fn load(config: &ConfigurationManager, database_logger: Arc<DatabaseLogger>) -> Self {
+        let database_logger = match config.get_bool("logging.errors.database").unwrap_or(true) {
+            true => Some(database_logger),
+            false => None,
+        };
+
+        let file = config.get_string("logging.errors.file").ok()
+            .filter(|path| !path.is_empty())
+            .map(|path| ErrorLogFile::new(
+                &path,
+                config.get_i64("logging.errors.file.max_size").unwrap_or(10 * 1024 * 1024).max(1) as u64,
+                config.get_int("logging.errors.file.max_files").unwrap_or(5).max(0) as u32,
+            ));
+
+        let window = config.get_duration("logging.errors.sql_window").unwrap_or(Duration::from_secs(60));
+
+        Self::new(database_logger, file, SqlExceptionFilter::new(window))
+    }
+
+    /// Reports a panic from the panic hook. A panic of a packet handler is held back for the
+    /// packet manager, which catches it and reports it with `ErrorLog::from_handler_panic`.
+    pub fn report_panic(&self, info: &PanicHookInfo) {
+        let error = ErrorLog::from_panic(info);
+        match PACKET_CONTEXT.try_with(|_| ()) {
+            Ok(()) => HANDLER_PANIC.with(|panic| *panic.borrow_mut() = Some(error)),
+            Err(_) => self.report(error),
+        }
+    }
+
+    pub fn report(&self, error: ErrorLog) {
+        if error.get_error_type() == ErrorType::SqlError && !self.sql_filter.filter(error.get_message()) {
+            return;
+        }
+
+        if let Some(file) = &self.file
+            && let Err(e) = file.write(&error)
+        {
+            warn!("Failed to write to the error log file: {}", e);
+        }
+
+        if let Some(database_logger) = &self.database_logger {
+            database_logger.log(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::database::Database;
+    use crate::database::database_pool::DatabasePool;
+    use crate::database::migrations::{Migrator, MIGRATIONS};
+
+    #[tokio::test]
+    async fn test_packet_context() {
+        let error = PACKET_CONTEXT.scope((5, 2596), async {
+            ErrorLog::new(ErrorType::PacketError, "Room not found")
+        }).await;
+
+        assert_eq!((error.get_user_id(), error.get_packet_header()), (5, 2596));
+        assert_eq!(ErrorLog::new(ErrorType::PacketError, "").get_user_id(), 0);
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let error = io::Error::other("Connection reset");
+        let error = sqlx::Error::Io(error);
+        let log = ErrorLog::from_error(ErrorType::SqlError, &error);
+
+        assert_eq!(log.get_message(), error.to_string());
+        assert_eq!(log.get_stacktrace(), "Caused by: Connection reset");
+        assert!(ErrorLog::new(ErrorType::PacketError, "Room not found").get_stacktrace().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_report_to_database() {
+        let pool = DatabasePool::Sqlite(Database::connect_sqlite(":memory:").unwrap());
+        Migrator::new(MIGRATIONS).run(&pool, false).await.unwrap();
+        let database_logger = Arc::new(DatabaseLogger::new(pool, 10));
+        let error_logger = ErrorLogger::new(Some(database_logger.clone()), None, SqlExceptionFilter::new(Duration::from_secs(60)));
+
+        error_logger.report(ErrorLog::new(ErrorType::PacketError, "Room not found").with_packet(1, 2596));
+        error_logger.report(ErrorLog::new(ErrorType::SqlError, "Table 'rooms' doesn't exist"));
+        error_logger.report(ErrorLog::new(ErrorType::SqlError, "Table 'rooms' doesn't exist"));
+
+        assert_eq!(database_logger.flush().await, 2);
+    }
+
+    #[test]
+    fn test_rotate_file() {
+        let path = std::env::temp_dir().join(format!("sulove-errors-{}.log", std::process::id()));
+        let file = ErrorLogFile::new(path.to_str().unwrap(), 1, 2);
+        let rotated = |index| PathBuf::from(format!("{}.{}", path.display(), index));
+
+        for _ in 0..4 {
+            file.write(&ErrorLog::new(ErrorType::Panic, "oops")).unwrap();
+        }
+
+        // Every write rotates, the oldest one is gone
+        assert!(path.exists() && rotated(1).exists() && rotated(2).exists() && !rotated(3).exists());
+        let line = fs::read_to_string(&path).unwrap();
+        assert!(line.contains("\"type\":\"Panic\"") && line.contains("\"message\":\"oops\""));
+
+        for path in [path.clone(), rotated(1), rotated(2)] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::sync::{Arc, RwLock};
+
+use log::{info, warn};
+
+use crate::database::repositories::SettingsRepository;
+
+/// Separates a key from its language, `generic.alert@de` is the German `generic.alert`
+const LANGUAGE_SEPARATOR: char = '@';
+
+/// The texts shown to users, from the `emulator_texts` table or a file when the table can't
+/// be read. A reload builds a new map and swaps it in, readers never see half of one.
+pub struct TextsManager {
+    texts_path: String,
+    language: String,
+    texts: RwLock<Arc<HashMap<String, String>>>,
+}
+
+impl TextsManager {
+    /// `texts_path` is the fallback file, `language` the one used when none is asked for
+    pub fn new(texts_path: &str, language: &str) -> Self {
+        TextsManager {
+            texts_path: texts_path.to_string(),
+            language: language.to_lowercase(),
+            texts: RwLock::new(Arc::new(HashMap::new())),
+        }
+    }
+
+    /// Loads the texts from the database, or from the file if that fails
+    pub async fn load(&self, settings: Option<&dyn SettingsRepository>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let texts = match settings {
+            Some(settings) => match settings.get_texts().await {
+                Ok(texts) => texts.into_iter().collect(),
+                Err(e) => {
+                    warn!("Failed to load emulator_texts, using {}: {}", self.texts_path, e);
+                    self.read_file()?
+                }
+            },
+            None => self.read_file()?,
+        };
+
+        info!("Loaded {} texts", texts.len());
+        *self.texts.write().unwrap() = Arc::new(texts);
+
+        Ok(())
+    }
+
+    fn read_file(&self) -> Result<HashMap<String, String>, Box<dyn Error + Send + Sync>> {
+        let content = fs::read_to_string(&self.texts_path)
+            .map_err(|e| format!("Failed to read {}: {}", self.texts_path, e))?;
+
+        Ok(Self::parse(&content))
+    }
+
+    /// Parses `key=value` lines, the keys below a `[language]` header belong to that language
+    fn parse(content: &str) -> HashMap<String, String> {
+        let mut texts = HashMap::new();
+        let mut language = String::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                language = name.trim().to_lowercase();
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim();
+                let key = if language.is_empty() { key.to_string() } else { format!("{}{}{}", key, LANGUAGE_SEPARATOR, language) };
+                texts.insert(key, value.trim().replace("\\n", "\n"));
+            }
+        }
+
+        texts
+    }
+
+    fn get(&self, key: &str, language: &str) -> Option<String> {
+        let texts = Arc::clone(&self.texts.read().unwrap());
+
+        texts.get(&format!("{}{}{}", key, LANGUAGE_SEPARATOR, language.to_lowercase()))
+            .or_else(|| texts.get(key))
+            .cloned()
+    }
+
+    /// The text in the default language, the key itself if it is missing
+    pub fn get_text(&self, key: &str) -> String {
+        self.get_text_for(key, &self.language)
+    }
+
+    pub fn get_text_or(&self, key: &str, default: &str) -> String {
+        self.get(key, &self.language).unwrap_or_else(|| default.to_string())
+    }
+
+    /// The text in `language`, falling back to the text without a language
+    pub fn get_text_for(&self, key: &str, language: &str) -> String {
+        self.get(key, language).unwrap_or_else(|| {
+            warn!("Missing text {}", key);
+            key.to_string()
+        })
+    }
+
+    /// The text with every `%name%` replaced by its value
+    pub fn format(&self, key: &str, values: &[(&str, &str)]) -> String {
+        Self::interpolate(&self.get_text(key), values)
+    }
+
+    pub fn format_for(&self, key: &str, language: &str, values: &[(&str, &str)]) -> String {
+        Self::interpolate(&self.get_text_for(key, language), values)
+    }
+
+    fn interpolate(text: &str, values: &[(&str, &str)]) -> String {
+        let mut text = text.to_string();
+
+        for (name, value) in values {
+            text = text.replace(&format!("%{}%", name), value);
+        }
+
+        text
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.texts.read().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::InMemorySettingsRepository;
+
+    fn manager(content: &str) -> TextsManager {
+        let manager = TextsManager::new("", "en");
+        *manager.texts.write().unwrap() = Arc::new(TextsManager::parse(content));
+        manager
+    }
+
+    #[test]
+    fn test_languages_and_placeholders() {
+        let texts = manager("# comment\ngeneric.welcome = Welcome %username%!\n[DE]\ngeneric.welcome=Willkommen %username%!\n");
+
+        assert_eq!(texts.format("generic.welcome", &[("username", "Sulake")]), "Welcome Sulake!");
+        assert_eq!(texts.format_for("generic.welcome", "de", &[("username", "Sulake")]), "Willkommen Sulake!");
+
+        // Languages without a translation get the default text, unknown keys show the key
+        assert_eq!(texts.get_text_for("generic.welcome", "nl"), "Welcome %username%!");
+        assert_eq!(texts.get_text("generic.missing"), "generic.missing");
+        assert_eq!(texts.get_text_or("generic.missing", "fallback"), "fallback");
+    }
+
+    #[tokio::test]
+    async fn test_load_from_repository() {
+        let settings = InMemorySettingsRepository::new();
+        settings.insert_text("hotel.alert", "From the database");
+
+        let texts = TextsManager::new("does-not-exist.ini", "en");
+        texts.load(Some(&settings)).await.unwrap();
+
+        assert_eq!(texts.get_text("hotel.alert"), "From the database");
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_the_texts() {
+        let path = std::env::temp_dir().join(format!("sulove-texts-{}.ini", std::process::id()));
+        fs::write(&path, "hotel.alert=Old\n").unwrap();
+
+        let texts = TextsManager::new(path.to_str().unwrap(), "en");
+        texts.load(None).await.unwrap();
+        assert_eq!(texts.get_text("hotel.alert"), "Old");
+
+        fs::write(&path, "hotel.alert=New\nhotel.other=Other\n").unwrap();
+        texts.load(None).await.unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(texts.get_text("hotel.alert"), "New");
+        assert_eq!(texts.get_count(), 2);
+    }
+}
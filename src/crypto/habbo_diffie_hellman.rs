@@ -1,7 +1,10 @@
 use crate::crypto::habbo_rsa_crypto::HabboRSACrypto;
+use crate::crypto::utils::prime_utils::PrimeUtils;
 use num_bigint::{BigInt, RandBigInt};
-use rand::{thread_rng, Rng};
+use rand::thread_rng;
 use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
 use std::sync::Arc;
 
 pub struct HabboDiffieHellman {
@@ -12,8 +15,8 @@ pub struct HabboDiffieHellman {
     dh_public: BigInt,
 }
 
-const DH_PRIMES_BIT_SIZE: u64 = 128;
-const DH_KEY_BIT_SIZE: u64 = 128;
+/// Size of the safe prime when `enc.dh.bits` isn't configured
+pub const DEFAULT_DH_BIT_SIZE: u64 = 128;
 
 #[derive(Debug)]
 pub enum HabboCryptoError {
@@ -21,8 +24,20 @@ pub enum HabboCryptoError {
     InvalidInput(String),
 }
 
+impl fmt::Display for HabboCryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HabboCryptoError::CryptoOperationFailed(message) => write!(f, "Crypto operation failed: {}", message),
+            HabboCryptoError::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+        }
+    }
+}
+
+impl Error for HabboCryptoError {}
+
 impl HabboDiffieHellman {
-    pub fn new(crypto: Arc<HabboRSACrypto>) -> Self {
+    /// Generates a new safe prime of `bit_size` bits with a matching generator and a key pair for it
+    pub fn new(crypto: Arc<HabboRSACrypto>, bit_size: u64) -> Self {
         let mut instance = Self {
             crypto,
             dh_prime: BigInt::from(0),
@@ -31,7 +46,22 @@ impl HabboDiffieHellman {
             dh_public: BigInt::from(0),
         };
         
-        instance.generate_dh_primes();
+        instance.generate_dh_primes(bit_size);
+        instance.generate_dh_keys();
+        instance
+    }
+
+    /// Reuses a prime and generator from an earlier exchange, only the key pair is new.
+    /// Finding a safe prime is slow, so the server generates one at startup and shares it.
+    pub fn with_group(crypto: Arc<HabboRSACrypto>, dh_prime: BigInt, dh_generator: BigInt) -> Self {
+        let mut instance = Self {
+            crypto,
+            dh_prime,
+            dh_generator,
+            dh_private: BigInt::from(0),
+            dh_public: BigInt::from(0),
+        };
+        
         instance.generate_dh_keys();
         instance
     }
@@ -44,21 +74,16 @@ impl HabboDiffieHellman {
         &self.dh_generator
     }
 
-    fn generate_dh_primes(&mut self) {
-        let mut rng = thread_rng();
-        
-        self.dh_prime = rng.gen_bigint(DH_PRIMES_BIT_SIZE);
-        self.dh_generator = rng.gen_bigint(DH_PRIMES_BIT_SIZE);
-
-        if self.dh_generator > self.dh_prime {
-            std::mem::swap(&mut self.dh_prime, &mut self.dh_generator);
-        }
+    fn generate_dh_primes(&mut self, bit_size: u64) {
+        self.dh_prime = PrimeUtils::generate_safe_prime(bit_size);
+        self.dh_generator = PrimeUtils::generate_generator(&self.dh_prime);
     }
 
     fn generate_dh_keys(&mut self) {
         let mut rng = thread_rng();
         
-        self.dh_private = rng.gen_bigint(DH_KEY_BIT_SIZE);
+        // Private key in [2, p - 2]
+        self.dh_private = rng.gen_bigint_range(&BigInt::from(2), &(&self.dh_prime - 1));
         // g^a mod p
         self.dh_public = self.dh_generator.modpow(&self.dh_private, &self.dh_prime);
     }
@@ -129,12 +154,28 @@ impl HabboDiffieHellman {
             )));
         }
 
+        if !PrimeUtils::is_valid_generator(&self.dh_generator, &self.dh_prime) {
+            return Err(HabboCryptoError::InvalidInput(format!(
+                "Generator does not generate a prime order subgroup!\nPrime: {}\nGenerator: {}",
+                self.dh_prime, self.dh_generator
+            )));
+        }
+
         self.generate_dh_keys();
         Ok(())
     }
 
     pub fn get_shared_key(&self, public_key_str: &str) -> Result<Vec<u8>, HabboCryptoError> {
         let public_key = self.decrypt_big_integer(public_key_str)?;
+
+        // 1 and p - 1 would make the shared key 1 or +-1, anyone could guess it
+        if public_key < BigInt::from(2) || public_key > &self.dh_prime - 2 {
+            return Err(HabboCryptoError::InvalidInput(format!(
+                "Public key has to be in [2, p - 2]!\nPrime: {}\nPublic key: {}",
+                self.dh_prime, public_key
+            )));
+        }
+
         let shared_key = public_key.modpow(&self.dh_private, &self.dh_prime);
         
         // Convert BigInt to unsigned byte array
@@ -8,9 +8,10 @@ pub struct HabboEncryption {
 }
 
 impl HabboEncryption {
-    pub fn new(e: &str, n: &str, d: &str) -> Self {
+    /// Loads the RSA key and generates the Diffie-Hellman prime all handshakes share
+    pub fn new(e: &str, n: &str, d: &str, dh_bit_size: u64) -> Self {
         let crypto = Arc::new(HabboRSACrypto::new_with_private_key(e, n, d));
-        let diffie = HabboDiffieHellman::new(Arc::clone(&crypto));
+        let diffie = HabboDiffieHellman::new(Arc::clone(&crypto), dh_bit_size);
         
         HabboEncryption {
             crypto,
@@ -25,4 +26,14 @@ impl HabboEncryption {
     pub fn get_diffie(&self) -> &HabboDiffieHellman {
         &self.diffie
     }
+
+    /// Creates a fresh Diffie-Hellman exchange signed with the server's RSA key,
+    /// every connection gets its own key pair so no two clients end up with the same shared key
+    pub fn create_diffie(&self) -> HabboDiffieHellman {
+        HabboDiffieHellman::with_group(
+            Arc::clone(&self.crypto),
+            self.diffie.get_dh_prime().clone(),
+            self.diffie.get_dh_generator().clone(),
+        )
+    }
 }
\ No newline at end of file
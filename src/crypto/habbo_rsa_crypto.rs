@@ -1,5 +1,7 @@
 use crate::crypto::exceptions::HabboCryptoException;
+use crate::crypto::utils::prime_utils::PrimeUtils;
 use num_bigint::BigInt;
+use num_traits::One;
 use rand::{thread_rng, Rng};
 use std::io::Cursor;
 use std::io::Write;
@@ -39,6 +41,31 @@ impl HabboRSACrypto {
         }
     }
 
+    /// Generates a new key pair with a modulus of `bits` bits and returns e, n and d hex encoded,
+    /// in the format `new_with_private_key` and the `enc.e`, `enc.n` and `enc.d` settings expect
+    pub fn generate_keys(bits: u64, exponent: u64) -> (String, String, String) {
+        let e = BigInt::from(exponent);
+
+        loop {
+            let p = PrimeUtils::generate_probable_prime(bits / 2);
+            let q = PrimeUtils::generate_probable_prime(bits - bits / 2);
+            if p == q {
+                continue;
+            }
+
+            let n = &p * &q;
+            if n.bits() != bits {
+                continue;
+            }
+
+            // e has to be invertible, which rules out primes where e divides p - 1 or q - 1
+            let phi = (&p - BigInt::one()) * (&q - BigInt::one());
+            if let Some(d) = e.modinv(&phi) {
+                return (e.to_str_radix(16), n.to_str_radix(16), d.to_str_radix(16));
+            }
+        }
+    }
+
     pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, HabboCryptoException> {
         self.do_encrypt(data, true, 2)
     }
@@ -133,13 +160,13 @@ impl HabboRSACrypto {
         let p = *pos;
         let bounded_end = std::cmp::min(end, std::cmp::min(src.len(), p + n - 11));
         *pos = bounded_end;
-        let mut i = bounded_end - 1;
+        let mut i = bounded_end;
         let mut n_index = n;
 
-        while i >= p && n_index > 11 {
+        while i > p && n_index > 11 {
+            i -= 1;
             n_index -= 1;
             result[n_index] = src[i];
-            i -= 1;
         }
 
         n_index -= 1;
@@ -222,4 +249,27 @@ fn calculate_bigint_bits(big_int: &BigInt) -> usize {
     }
     
     bits
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_keys() {
+        let (e, n, d) = HabboRSACrypto::generate_keys(512, 65537);
+        let server = HabboRSACrypto::new_with_private_key(&e, &n, &d);
+        let client = HabboRSACrypto::new(&e, &n);
+
+        assert_eq!(BigInt::parse_bytes(n.as_bytes(), 16).unwrap().bits(), 512);
+
+        // What the client encrypts only the server can read
+        let encrypted = client.encrypt(b"client public key").unwrap();
+        assert_eq!(server.decrypt(&encrypted).unwrap(), b"client public key");
+        assert!(client.decrypt(&encrypted).is_err());
+
+        // What the server signs the client can check
+        let signed = server.sign(b"server public key").unwrap();
+        assert_eq!(client.verify(&signed).unwrap(), b"server public key");
+    }
+}
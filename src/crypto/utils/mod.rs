@@ -1 +1,2 @@
-pub mod big_integer_utils;
\ No newline at end of file
+pub mod big_integer_utils;
+pub mod prime_utils;
\ No newline at end of file
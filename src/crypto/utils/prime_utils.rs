@@ -0,0 +1,177 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+
+/// Miller-Rabin rounds, a composite passes all of them with a chance of at most 4^-32
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+/// Used to throw out most candidates before running Miller-Rabin
+const SMALL_PRIMES: [u32; 46] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+    97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191,
+    193, 197, 199,
+];
+
+/// Utility functions for generating and checking primes
+pub struct PrimeUtils;
+
+impl PrimeUtils {
+    /// Miller-Rabin probable prime test with random bases
+    pub fn is_probable_prime(n: &BigInt) -> bool {
+        let one = BigInt::one();
+        let two = BigInt::from(2);
+
+        if n < &two {
+            return false;
+        }
+
+        for &small_prime in SMALL_PRIMES.iter() {
+            let small_prime = BigInt::from(small_prime);
+            if n == &small_prime {
+                return true;
+            }
+            if (n % &small_prime).is_zero() {
+                return false;
+            }
+        }
+
+        // Write n - 1 as d * 2^s with d odd
+        let n_minus_one = n - &one;
+        let s = n_minus_one.trailing_zeros().unwrap_or(0);
+        let d = &n_minus_one >> s;
+
+        let mut rng = thread_rng();
+        'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+            let a = rng.gen_bigint_range(&two, &n_minus_one);
+            let mut x = a.modpow(&d, n);
+
+            if x == one || x == n_minus_one {
+                continue;
+            }
+
+            for _ in 1..s {
+                x = &x * &x % n;
+                if x == n_minus_one {
+                    continue 'witness;
+                }
+            }
+
+            return false;
+        }
+
+        true
+    }
+
+    /// Generates a probable prime of exactly `bits` bits
+    pub fn generate_probable_prime(bits: u64) -> BigInt {
+        loop {
+            let candidate = Self::random_odd(bits);
+            if Self::is_probable_prime(&candidate) {
+                return candidate;
+            }
+        }
+    }
+
+    /// Generates a safe prime p = 2q + 1 of exactly `bits` bits, where q is prime as well
+    pub fn generate_safe_prime(bits: u64) -> BigInt {
+        loop {
+            let q = Self::random_odd(bits - 1);
+
+            // p is divisible by a small prime r when q = (r - 1) / 2 mod r, skip those cheaply
+            let sieved = SMALL_PRIMES.iter().skip(1).any(|&r| {
+                let rest = &q % BigInt::from(r);
+                rest.is_zero() || rest == BigInt::from((r - 1) / 2)
+            });
+            if sieved {
+                continue;
+            }
+
+            if !Self::is_probable_prime(&q) {
+                continue;
+            }
+
+            let p = (&q << 1) + BigInt::one();
+            if Self::is_probable_prime(&p) {
+                return p;
+            }
+        }
+    }
+
+    /// Checks that `generator` generates the subgroup of prime order q of the safe prime p = 2q + 1.
+    /// That rules out 0, 1, p - 1 and anything else that would leak the private key through a small subgroup.
+    pub fn is_valid_generator(generator: &BigInt, prime: &BigInt) -> bool {
+        let one = BigInt::one();
+        let p_minus_one = prime - &one;
+
+        if generator <= &one || generator >= &p_minus_one {
+            return false;
+        }
+
+        generator.modpow(&(&p_minus_one >> 1), prime) == one
+    }
+
+    /// Picks a random generator for the prime order subgroup of the safe prime p
+    pub fn generate_generator(prime: &BigInt) -> BigInt {
+        let mut rng = thread_rng();
+        let two = BigInt::from(2);
+        let p_minus_one = prime - BigInt::one();
+
+        loop {
+            // Squares are exactly the elements of the subgroup of order q
+            let h = rng.gen_bigint_range(&two, &p_minus_one);
+            let generator = h.modpow(&two, prime);
+
+            if Self::is_valid_generator(&generator, prime) {
+                return generator;
+            }
+        }
+    }
+
+    /// A random odd number with the top bit set, so it has exactly `bits` bits
+    fn random_odd(bits: u64) -> BigInt {
+        let mut rng = thread_rng();
+        let candidate = BigInt::from(rng.gen_biguint(bits));
+        candidate | (BigInt::one() << (bits - 1)) | BigInt::one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_probable_prime() {
+        for prime in [2u64, 3, 199, 211, 7919, 2147483647, 18446744073709551557] {
+            assert!(PrimeUtils::is_probable_prime(&BigInt::from(prime)), "{} is prime", prime);
+        }
+
+        // 561, 41041 and 825265 are Carmichael numbers, they fool a plain Fermat test
+        for composite in [0u64, 1, 4, 561, 41041, 825265, 2147483647 * 3, 4294967297] {
+            assert!(!PrimeUtils::is_probable_prime(&BigInt::from(composite)), "{} is composite", composite);
+        }
+    }
+
+    #[test]
+    fn test_generate_safe_prime() {
+        let prime = PrimeUtils::generate_safe_prime(128);
+
+        assert_eq!(prime.bits(), 128);
+        assert!(PrimeUtils::is_probable_prime(&prime));
+        assert!(PrimeUtils::is_probable_prime(&((&prime - BigInt::one()) >> 1)));
+
+        let generator = PrimeUtils::generate_generator(&prime);
+        assert!(PrimeUtils::is_valid_generator(&generator, &prime));
+    }
+
+    #[test]
+    fn test_is_valid_generator() {
+        // 23 = 2 * 11 + 1
+        let prime = BigInt::from(23);
+
+        assert!(PrimeUtils::is_valid_generator(&BigInt::from(2), &prime));
+        assert!(!PrimeUtils::is_valid_generator(&BigInt::from(1), &prime));
+        assert!(!PrimeUtils::is_valid_generator(&BigInt::from(22), &prime));
+        // 5 generates the whole group, the client could learn the lowest bit of its private key
+        assert!(!PrimeUtils::is_valid_generator(&BigInt::from(5), &prime));
+    }
+}
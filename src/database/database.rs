@@ -1,48 +1,96 @@
+use std::str::FromStr;
 use std::sync::Arc;
-use log::{error, info};
-use sqlx::{MySql, MySqlPool, Pool};
+use log::info;
 use sqlx::mysql::MySqlPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 
 use crate::core::configuration_manager::ConfigurationManager;
+use crate::database::database_pool::{on_pool, DatabaseDriver, DatabasePool};
+use crate::database::repositories::Repositories;
 
 pub struct Database {
-    pool: Pool<MySql>,
+    pool: DatabasePool,
+    repositories: Repositories,
 }
 
 impl Database {
     pub fn new(config: Arc<ConfigurationManager>) -> Result<Self, Box<dyn std::error::Error>> {
+        let driver = config.get_string("db.driver").unwrap_or_else(|_| "mysql".to_string());
+        let driver = DatabaseDriver::from_value(&driver)
+            .ok_or_else(|| format!("Unknown database driver {}, use mysql or sqlite", driver))?;
+
+        let pool = match driver {
+            DatabaseDriver::MySql => DatabasePool::MySql(Self::connect_mysql(&config)?),
+            DatabaseDriver::Sqlite => {
+                let path = config.get_string("db.sqlite.path").unwrap_or_else(|_| "sulove.db".to_string());
+                let pool = Self::connect_sqlite(&path)?;
+                info!("Connected to database: sqlite://{}", path);
+                DatabasePool::Sqlite(pool)
+            }
+        };
+
+        Ok(Self::from_pool(pool))
+    }
+
+    pub fn from_pool(pool: DatabasePool) -> Self {
+        Database { repositories: Repositories::sql(pool.clone()), pool }
+    }
+
+    fn connect_mysql(config: &ConfigurationManager) -> Result<sqlx::Pool<sqlx::MySql>, Box<dyn std::error::Error>> {
         // Get database configuration
         let db_host = config.get_string("db.host").unwrap_or_else(|_| "localhost".to_string());
-        let db_port = config.get_int("db.port").unwrap_or_else(|_| 3306);
+        let db_port = config.get_int("db.port").unwrap_or(3306);
         let db_name = config.get_string("db.name").unwrap_or_else(|_| "sulove".to_string());
         let db_user = config.get_string("db.username").unwrap_or_else(|_| "root".to_string());
-        let db_pass = config.get_string("db.password").unwrap_or_else(|_| "".to_string());
-        
+        let db_pass = config.get_string("db.password").unwrap_or_default();
+
         // Build connection string
         let connection_string = format!(
             "mysql://{}:{}@{}:{}/{}",
             db_user, db_pass, db_host, db_port, db_name
         );
-        
+
         // Create connection pool
         let pool = MySqlPoolOptions::new()
             .max_connections(config.get_int("runtime.threads").unwrap_or(10) as u32 * 2)
             .min_connections(10)
             .connect_lazy(&connection_string)?;
-        
+
         info!("Connected to database: {}@{}:{}/{}", db_user, db_host, db_port, db_name);
-        
-        Ok(Database { pool })
+
+        Ok(pool)
     }
-    
-    pub fn get_pool(&self) -> &Pool<MySql> {
+
+    /// Opens or creates the database file. `:memory:` gives a database that is gone once
+    /// its connection closes, so it is limited to one connection.
+    pub fn connect_sqlite(path: &str) -> Result<sqlx::Pool<sqlx::Sqlite>, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path))?
+            .create_if_missing(true)
+            .foreign_keys(false);
+
+        let max_connections = if path == ":memory:" { 1 } else { 4 };
+
+        Ok(SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_lazy_with(options))
+    }
+
+    pub fn get_pool(&self) -> &DatabasePool {
         &self.pool
     }
-    
+
+    pub fn get_repositories(&self) -> &Repositories {
+        &self.repositories
+    }
+
+    /// Waits for the connections in use to be returned and closes all of them
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     pub async fn test_connection(&self) -> Result<(), sqlx::Error> {
-        sqlx::query("SELECT 1")
-            .execute(self.get_pool())
-            .await
-            .map(|_| ())
+        on_pool!(&self.pool, pool => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()))
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,56 @@
-use std::sync::Arc;
-use sqlx::{MySql, Pool};
+use sqlx::{MySql, Pool, Sqlite};
 
-pub struct DatabasePool {
-    pool: Arc<Pool<MySql>>,
+/// The database servers the emulator can run on, picked with `db.driver`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseDriver {
+    MySql,
+    Sqlite,
 }
 
+impl DatabaseDriver {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "mysql" | "mariadb" => Some(DatabaseDriver::MySql),
+            "sqlite" => Some(DatabaseDriver::Sqlite),
+            _ => None,
+        }
+    }
+}
+
+/// A connection pool of either driver. Pools are cheap to clone, clones share their connections.
+#[derive(Debug, Clone)]
+pub enum DatabasePool {
+    MySql(Pool<MySql>),
+    Sqlite(Pool<Sqlite>),
+}
+
+/// Runs `$body` with `$pool` bound to the pool of whichever driver is in use. The body is
+/// compiled once per driver, so queries written once work on both.
+macro_rules! on_pool {
+    ($database_pool:expr, $pool:ident => $body:expr) => {
+        match $database_pool {
+            $crate::database::database_pool::DatabasePool::MySql($pool) => $body,
+            $crate::database::database_pool::DatabasePool::Sqlite($pool) => $body,
+        }
+    };
+}
+
+pub(crate) use on_pool;
+
 impl DatabasePool {
-    pub fn new(pool: Arc<Pool<MySql>>) -> Self {
-        DatabasePool { pool }
+    pub fn get_driver(&self) -> DatabaseDriver {
+        match self {
+            DatabasePool::MySql(_) => DatabaseDriver::MySql,
+            DatabasePool::Sqlite(_) => DatabaseDriver::Sqlite,
+        }
+    }
+
+    /// Runs a statement that returns no rows
+    pub async fn execute(&self, sql: &str) -> Result<u64, sqlx::Error> {
+        on_pool!(self, pool => sqlx::query(sql).execute(pool).await.map(|result| result.rows_affected()))
     }
-    
-    pub fn get_pool(&self) -> Arc<Pool<MySql>> {
-        self.pool.clone()
+
+    pub async fn close(&self) {
+        on_pool!(self, pool => pool.close().await)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,40 @@
+-- Users and everything they own that isn't furniture
+CREATE TABLE IF NOT EXISTS users (
+    id INT NOT NULL AUTO_INCREMENT,
+    username VARCHAR(25) NOT NULL,
+    real_name VARCHAR(25) NOT NULL DEFAULT '',
+    password VARCHAR(64) NOT NULL DEFAULT '',
+    mail VARCHAR(500) NOT NULL DEFAULT '',
+    account_created INT NOT NULL DEFAULT 0,
+    last_login INT NOT NULL DEFAULT 0,
+    last_online INT NOT NULL DEFAULT 0,
+    motto VARCHAR(127) NOT NULL DEFAULT '',
+    look VARCHAR(256) NOT NULL DEFAULT 'hr-115-42.hd-195-19.ch-3030-82.lg-275-1408.fa-1201.ca-1804-64',
+    gender ENUM('M', 'F') NOT NULL DEFAULT 'M',
+    `rank` INT NOT NULL DEFAULT 1,
+    credits INT NOT NULL DEFAULT 2500,
+    online ENUM('0', '1', '2') NOT NULL DEFAULT '0',
+    auth_ticket VARCHAR(256) NOT NULL DEFAULT '',
+    ip_register VARCHAR(45) NOT NULL DEFAULT '',
+    ip_current VARCHAR(45) NOT NULL DEFAULT '',
+    home_room INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (id),
+    UNIQUE KEY username (username),
+    KEY auth_ticket (auth_ticket)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS users_currency (
+    user_id INT NOT NULL,
+    type INT NOT NULL,
+    amount INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, type)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS users_badges (
+    id INT NOT NULL AUTO_INCREMENT,
+    user_id INT NOT NULL,
+    slot_id INT NOT NULL DEFAULT 0,
+    badge_code VARCHAR(32) NOT NULL,
+    PRIMARY KEY (id),
+    KEY user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,36 @@
+-- Rooms and the layouts they are built on
+CREATE TABLE IF NOT EXISTS room_models (
+    name VARCHAR(100) NOT NULL,
+    door_x INT NOT NULL DEFAULT 0,
+    door_y INT NOT NULL DEFAULT 0,
+    door_dir INT NOT NULL DEFAULT 2,
+    heightmap TEXT NOT NULL,
+    public_items TEXT NOT NULL,
+    club_only ENUM('0', '1') NOT NULL DEFAULT '0',
+    PRIMARY KEY (name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS rooms (
+    id INT NOT NULL AUTO_INCREMENT,
+    owner_id INT NOT NULL DEFAULT 0,
+    owner_name VARCHAR(25) NOT NULL DEFAULT '',
+    name VARCHAR(50) NOT NULL DEFAULT '',
+    description VARCHAR(512) NOT NULL DEFAULT '',
+    model VARCHAR(100) NOT NULL DEFAULT 'model_a',
+    password VARCHAR(20) NOT NULL DEFAULT '',
+    state ENUM('open', 'locked', 'password', 'invisible') NOT NULL DEFAULT 'open',
+    users INT NOT NULL DEFAULT 0,
+    users_max INT NOT NULL DEFAULT 25,
+    category INT NOT NULL DEFAULT 0,
+    score INT NOT NULL DEFAULT 0,
+    paper_floor VARCHAR(5) NOT NULL DEFAULT '0.0',
+    paper_wall VARCHAR(5) NOT NULL DEFAULT '0.0',
+    paper_landscape VARCHAR(5) NOT NULL DEFAULT '0.0',
+    thickness_wall INT NOT NULL DEFAULT 0,
+    thickness_floor INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (id),
+    KEY owner_id (owner_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+INSERT IGNORE INTO room_models (name, door_x, door_y, door_dir, heightmap, public_items) VALUES
+    ('model_a', 3, 5, 2, 'xxxxxxxxxxxx\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxx000000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxx00000000\rxxxxxxxxxxxx\rxxxxxxxxxxxx', '');
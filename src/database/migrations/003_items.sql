@@ -0,0 +1,45 @@
+-- Furniture definitions and the furniture users own
+CREATE TABLE IF NOT EXISTS items_base (
+    id INT NOT NULL AUTO_INCREMENT,
+    sprite_id INT NOT NULL DEFAULT 0,
+    item_name VARCHAR(70) NOT NULL,
+    public_name VARCHAR(56) NOT NULL DEFAULT '',
+    width INT NOT NULL DEFAULT 1,
+    length INT NOT NULL DEFAULT 1,
+    stack_height DOUBLE NOT NULL DEFAULT 0,
+    allow_stack ENUM('0', '1') NOT NULL DEFAULT '1',
+    allow_sit ENUM('0', '1') NOT NULL DEFAULT '0',
+    allow_lay ENUM('0', '1') NOT NULL DEFAULT '0',
+    allow_walk ENUM('0', '1') NOT NULL DEFAULT '0',
+    allow_trade ENUM('0', '1') NOT NULL DEFAULT '1',
+    allow_recycle ENUM('0', '1') NOT NULL DEFAULT '0',
+    allow_marketplace_sell ENUM('0', '1') NOT NULL DEFAULT '0',
+    allow_gift ENUM('0', '1') NOT NULL DEFAULT '1',
+    allow_inventory_stack ENUM('0', '1') NOT NULL DEFAULT '1',
+    type VARCHAR(3) NOT NULL DEFAULT 's',
+    interaction_type VARCHAR(500) NOT NULL DEFAULT 'default',
+    interaction_modes_count INT NOT NULL DEFAULT 1,
+    vending_ids VARCHAR(255) NOT NULL DEFAULT '0',
+    multiheight VARCHAR(50) NOT NULL DEFAULT '0',
+    customparams VARCHAR(256) NOT NULL DEFAULT '',
+    effect_id_male INT NOT NULL DEFAULT 0,
+    effect_id_female INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS items (
+    id INT NOT NULL AUTO_INCREMENT,
+    user_id INT NOT NULL DEFAULT 0,
+    room_id INT NOT NULL DEFAULT 0,
+    item_id INT NOT NULL DEFAULT 0,
+    wall_pos VARCHAR(20) NOT NULL DEFAULT '',
+    x INT NOT NULL DEFAULT 0,
+    y INT NOT NULL DEFAULT 0,
+    z DOUBLE NOT NULL DEFAULT 0,
+    rot INT NOT NULL DEFAULT 0,
+    extra_data VARCHAR(1024) NOT NULL DEFAULT '',
+    limited_data VARCHAR(10) NOT NULL DEFAULT '0:0',
+    PRIMARY KEY (id),
+    KEY user_id (user_id),
+    KEY room_id (room_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,38 @@
+-- Catalog pages and what they sell
+CREATE TABLE IF NOT EXISTS catalog_pages (
+    id INT NOT NULL AUTO_INCREMENT,
+    parent_id INT NOT NULL DEFAULT -1,
+    caption VARCHAR(128) NOT NULL DEFAULT '',
+    page_layout VARCHAR(64) NOT NULL DEFAULT 'default_3x3',
+    icon_image INT NOT NULL DEFAULT 1,
+    min_rank INT NOT NULL DEFAULT 1,
+    order_num INT NOT NULL DEFAULT 1,
+    visible ENUM('0', '1') NOT NULL DEFAULT '1',
+    enabled ENUM('0', '1') NOT NULL DEFAULT '1',
+    club_only ENUM('0', '1') NOT NULL DEFAULT '0',
+    page_headline VARCHAR(1024) NOT NULL DEFAULT '',
+    page_teaser VARCHAR(64) NOT NULL DEFAULT '',
+    page_text1 TEXT,
+    page_text2 TEXT,
+    PRIMARY KEY (id),
+    KEY parent_id (parent_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS catalog_items (
+    id INT NOT NULL AUTO_INCREMENT,
+    item_ids VARCHAR(666) NOT NULL,
+    page_id INT NOT NULL,
+    catalog_name VARCHAR(100) NOT NULL,
+    cost_credits INT NOT NULL DEFAULT 3,
+    cost_points INT NOT NULL DEFAULT 0,
+    points_type INT NOT NULL DEFAULT 0,
+    amount INT NOT NULL DEFAULT 1,
+    limited_stack INT NOT NULL DEFAULT 0,
+    limited_sells INT NOT NULL DEFAULT 0,
+    order_number INT NOT NULL DEFAULT 1,
+    offer_id INT NOT NULL DEFAULT -1,
+    club_only ENUM('0', '1') NOT NULL DEFAULT '0',
+    extradata VARCHAR(500) NOT NULL DEFAULT '',
+    PRIMARY KEY (id),
+    KEY page_id (page_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
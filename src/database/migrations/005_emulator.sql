@@ -0,0 +1,12 @@
+-- Settings and texts that can be changed while the hotel runs
+CREATE TABLE IF NOT EXISTS emulator_settings (
+    `key` VARCHAR(100) NOT NULL,
+    `value` VARCHAR(512) NOT NULL,
+    PRIMARY KEY (`key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS emulator_texts (
+    `key` VARCHAR(100) NOT NULL,
+    `value` VARCHAR(4096) NOT NULL,
+    PRIMARY KEY (`key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
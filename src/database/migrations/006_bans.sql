@@ -0,0 +1,15 @@
+-- Account, IP and machine bans
+CREATE TABLE IF NOT EXISTS bans (
+    id INT NOT NULL AUTO_INCREMENT,
+    user_id INT NOT NULL DEFAULT 0,
+    ip VARCHAR(45) NOT NULL DEFAULT '',
+    machine_id VARCHAR(255) NOT NULL DEFAULT '',
+    user_staff_id INT NOT NULL DEFAULT 0,
+    timestamp INT NOT NULL DEFAULT 0,
+    ban_expire INT NOT NULL DEFAULT 0,
+    ban_reason VARCHAR(200) NOT NULL DEFAULT '',
+    type ENUM('account', 'ip', 'machine', 'super') NOT NULL DEFAULT 'account',
+    PRIMARY KEY (id),
+    KEY user_id (user_id),
+    KEY ip (ip)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
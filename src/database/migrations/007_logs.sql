@@ -0,0 +1,32 @@
+-- Logs written by the emulator
+CREATE TABLE IF NOT EXISTS commandlogs (
+    id INT NOT NULL AUTO_INCREMENT,
+    user_id INT NOT NULL,
+    timestamp INT NOT NULL,
+    command VARCHAR(256) NOT NULL DEFAULT '',
+    params VARCHAR(100) NOT NULL DEFAULT '',
+    succes ENUM('no', 'yes') NOT NULL DEFAULT 'yes',
+    PRIMARY KEY (id),
+    KEY user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS chatlogs_room (
+    id INT NOT NULL AUTO_INCREMENT,
+    room_id INT NOT NULL DEFAULT 0,
+    user_from_id INT NOT NULL,
+    user_to_id INT NOT NULL DEFAULT 0,
+    message VARCHAR(255) NOT NULL DEFAULT '',
+    timestamp INT NOT NULL,
+    PRIMARY KEY (id),
+    KEY room_id (room_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS emulator_errors (
+    id INT NOT NULL AUTO_INCREMENT,
+    timestamp INT NOT NULL,
+    version VARCHAR(64) NOT NULL DEFAULT '',
+    build_hash VARCHAR(64) NOT NULL DEFAULT '',
+    type VARCHAR(32) NOT NULL DEFAULT 'Exception',
+    stacktrace TEXT NOT NULL,
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
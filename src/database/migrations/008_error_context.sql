@@ -0,0 +1,4 @@
+-- Who sent which packet when an error happened
+ALTER TABLE emulator_errors ADD COLUMN user_id INT NOT NULL DEFAULT 0;
+
+ALTER TABLE emulator_errors ADD COLUMN packet_header INT NOT NULL DEFAULT 0;
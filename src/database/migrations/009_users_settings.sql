@@ -0,0 +1,15 @@
+-- Stats and preferences of a user, the row is created on the first login
+CREATE TABLE IF NOT EXISTS users_settings (
+    user_id INT NOT NULL,
+    respects_received INT NOT NULL DEFAULT 0,
+    daily_respect_points INT NOT NULL DEFAULT 3,
+    daily_pet_respect_points INT NOT NULL DEFAULT 3,
+    achievement_score INT NOT NULL DEFAULT 0,
+    volume_system INT NOT NULL DEFAULT 100,
+    volume_furni INT NOT NULL DEFAULT 100,
+    volume_trax INT NOT NULL DEFAULT 100,
+    old_chat ENUM('0', '1') NOT NULL DEFAULT '0',
+    block_following ENUM('0', '1') NOT NULL DEFAULT '0',
+    block_friendrequests ENUM('0', '1') NOT NULL DEFAULT '0',
+    PRIMARY KEY (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,10 @@
+-- Layouts made for a single room, like with the floor plan editor. They take the place of the room's model.
+CREATE TABLE IF NOT EXISTS room_models_custom (
+    id INT NOT NULL,
+    name VARCHAR(100) NOT NULL DEFAULT '',
+    door_x INT NOT NULL DEFAULT 0,
+    door_y INT NOT NULL DEFAULT 0,
+    door_dir INT NOT NULL DEFAULT 2,
+    heightmap TEXT NOT NULL,
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,18 @@
+-- The heightmap of model_a from 002 used \r escapes, which SQLite stores as a backslash and an r.
+-- A literal line break means the same to both drivers.
+UPDATE room_models SET heightmap = 'xxxxxxxxxxxx
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxx000000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxx00000000
+xxxxxxxxxxxx
+xxxxxxxxxxxx' WHERE name = 'model_a';
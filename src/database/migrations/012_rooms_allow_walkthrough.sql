@@ -0,0 +1,2 @@
+-- Whether users may walk over tiles other users stand on, set by the room owner
+ALTER TABLE rooms ADD COLUMN allow_walkthrough ENUM('0', '1') NOT NULL DEFAULT '1';
@@ -0,0 +1,264 @@
+//! Versioned schema migrations, embedded in the binary
+//! Applied versions are recorded in `schema_version`, every migration runs once
+
+use std::error::Error;
+use log::info;
+use regex::Regex;
+
+use crate::database::database_pool::{on_pool, DatabaseDriver, DatabasePool};
+
+/// A single step of the schema, identified by its version
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every migration in the order they are applied. New ones go at the end with the next version,
+/// a migration that shipped is never edited. They are written for MySQL, see `to_sqlite`.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "users", sql: include_str!("001_users.sql") },
+    Migration { version: 2, name: "rooms", sql: include_str!("002_rooms.sql") },
+    Migration { version: 3, name: "items", sql: include_str!("003_items.sql") },
+    Migration { version: 4, name: "catalog", sql: include_str!("004_catalog.sql") },
+    Migration { version: 5, name: "emulator", sql: include_str!("005_emulator.sql") },
+    Migration { version: 6, name: "bans", sql: include_str!("006_bans.sql") },
+    Migration { version: 7, name: "logs", sql: include_str!("007_logs.sql") },
+    Migration { version: 8, name: "error_context", sql: include_str!("008_error_context.sql") },
+    Migration { version: 9, name: "users_settings", sql: include_str!("009_users_settings.sql") },
+    Migration { version: 10, name: "room_models_custom", sql: include_str!("010_room_models_custom.sql") },
+    Migration { version: 11, name: "room_models_line_breaks", sql: include_str!("011_room_models_line_breaks.sql") },
+    Migration { version: 12, name: "rooms_allow_walkthrough", sql: include_str!("012_rooms_allow_walkthrough.sql") },
+];
+
+const CREATE_SCHEMA_VERSION: &str = "CREATE TABLE IF NOT EXISTS schema_version (
+    version INT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (version)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4";
+
+impl Migration {
+    /// The statements of the migration for `driver`, one query each
+    pub fn get_statements(&self, driver: DatabaseDriver) -> Vec<String> {
+        split_statements(self.sql)
+            .into_iter()
+            .map(|statement| translate(&statement, driver))
+            .collect()
+    }
+}
+
+/// Splits a script on the `;` that end statements, skipping `--` comment lines.
+/// Semicolons inside quotes stay part of their statement.
+fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for line in sql.lines() {
+        if quote.is_none() && line.trim_start().starts_with("--") {
+            continue;
+        }
+
+        for c in line.chars() {
+            match (quote, c) {
+                (None, ';') => {
+                    if !current.trim().is_empty() {
+                        statements.push(current.trim().to_string());
+                    }
+                    current.clear();
+                    continue;
+                }
+                (None, '\'' | '"' | '`') => quote = Some(c),
+                (Some(open), _) if open == c => quote = None,
+                _ => {}
+            }
+            current.push(c);
+        }
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+fn translate(statement: &str, driver: DatabaseDriver) -> String {
+    match driver {
+        DatabaseDriver::MySql => statement.to_string(),
+        DatabaseDriver::Sqlite => to_sqlite(statement),
+    }
+}
+
+/// Rewrites the MySQL the migrations are written in for SQLite. Only covers what the
+/// migrations use: table options, auto increment ids, enums, inline keys and INSERT IGNORE.
+fn to_sqlite(statement: &str) -> String {
+    let table_options = Regex::new(r"\)\s*ENGINE\s*=[^)]*$").unwrap();
+    let auto_increment = Regex::new(r"^(\s*)(`?\w+`?)\s+INT\s+NOT NULL\s+AUTO_INCREMENT").unwrap();
+    let enumeration = Regex::new(r"ENUM\([^)]*\)").unwrap();
+    let unique_key = Regex::new(r"^(\s*)UNIQUE KEY\s+`?\w+`?\s*(\(.*\))").unwrap();
+    let trailing_comma = Regex::new(r",(\s*\))$").unwrap();
+
+    let statement = statement.replacen("INSERT IGNORE", "INSERT OR IGNORE", 1);
+    if statement.starts_with("ALTER TABLE") {
+        return enumeration.replace_all(&statement, "TEXT").to_string();
+    }
+    if !statement.starts_with("CREATE TABLE") {
+        return statement;
+    }
+
+    let mut has_auto_increment = false;
+    let mut lines = Vec::new();
+
+    for line in statement.lines() {
+        let trimmed = line.trim_start();
+
+        // SQLite creates indexes separately, the ones MySQL needs for lookups are left out
+        if trimmed.starts_with("KEY ") || (has_auto_increment && trimmed.starts_with("PRIMARY KEY")) {
+            continue;
+        }
+
+        let line = if auto_increment.is_match(line) {
+            has_auto_increment = true;
+            auto_increment.replace(line, "${1}${2} INTEGER PRIMARY KEY AUTOINCREMENT").to_string()
+        } else {
+            unique_key.replace(line, "${1}UNIQUE ${2}").to_string()
+        };
+
+        lines.push(enumeration.replace_all(&line, "TEXT").to_string());
+    }
+
+    let statement = table_options.replace(&lines.join("\n"), ")").to_string();
+    trailing_comma.replace(&statement, "\n)").to_string()
+}
+
+/// Applies the migrations that aren't in `schema_version` yet
+pub struct Migrator {
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new(migrations: &'static [Migration]) -> Self {
+        Migrator { migrations }
+    }
+
+    /// The versions that were applied before
+    pub async fn get_applied(&self, pool: &DatabasePool) -> Result<Vec<u32>, sqlx::Error> {
+        pool.execute(&translate(CREATE_SCHEMA_VERSION, pool.get_driver())).await?;
+
+        let versions: Vec<i32> = on_pool!(pool, pool => {
+            sqlx::query_scalar("SELECT version FROM schema_version ORDER BY version")
+                .fetch_all(pool)
+                .await?
+        });
+
+        Ok(versions.into_iter().map(|version| version as u32).collect())
+    }
+
+    pub async fn get_pending(&self, pool: &DatabasePool) -> Result<Vec<&'static Migration>, sqlx::Error> {
+        let applied = self.get_applied(pool).await?;
+
+        Ok(self.migrations.iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .collect())
+    }
+
+    /// Applies the pending migrations in order and returns their versions.
+    /// A dry run only prints what would be executed.
+    pub async fn run(&self, pool: &DatabasePool, dry_run: bool) -> Result<Vec<u32>, Box<dyn Error + Send + Sync>> {
+        let pending = self.get_pending(pool).await?;
+
+        if pending.is_empty() {
+            info!("Database schema is up to date");
+            return Ok(Vec::new());
+        }
+
+        for migration in &pending {
+            if dry_run {
+                println!("-- Migration {} ({})", migration.version, migration.name);
+                for statement in migration.get_statements(pool.get_driver()) {
+                    println!("{};\n", statement);
+                }
+                continue;
+            }
+
+            info!("Applying migration {} ({})", migration.version, migration.name);
+
+            // MySQL commits schema changes right away, a failed migration is fixed by hand
+            // and applies the rest of its statements again on the next run
+            for statement in migration.get_statements(pool.get_driver()) {
+                pool.execute(&statement)
+                    .await
+                    .map_err(|e| format!("Migration {} failed: {}", migration.version, e))?;
+            }
+
+            on_pool!(pool, pool => {
+                sqlx::query("INSERT INTO schema_version (version, name) VALUES (?, ?)")
+                    .bind(migration.version as i32)
+                    .bind(migration.name)
+                    .execute(pool)
+                    .await?;
+            });
+        }
+
+        Ok(pending.iter().map(|migration| migration.version).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_ordered() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1, "{} is out of order", migration.name);
+            assert!(!migration.get_statements(DatabaseDriver::MySql).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let statements = split_statements(
+            "-- comment; with a semicolon\nCREATE TABLE a (b INT);\nINSERT INTO a VALUES ('x;y');\n\nSELECT 1",
+        );
+
+        assert_eq!(statements, [
+            "CREATE TABLE a (b INT)",
+            "INSERT INTO a VALUES ('x;y')",
+            "SELECT 1",
+        ]);
+    }
+
+    #[test]
+    fn test_to_sqlite() {
+        let statement = to_sqlite(
+            "CREATE TABLE IF NOT EXISTS a (\n    id INT NOT NULL AUTO_INCREMENT,\n    state ENUM('0', '1') NOT NULL DEFAULT '0',\n    \
+             name VARCHAR(25) NOT NULL,\n    PRIMARY KEY (id),\n    UNIQUE KEY name (name),\n    KEY state (state)\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4",
+        );
+
+        assert_eq!(statement, "CREATE TABLE IF NOT EXISTS a (\n    id INTEGER PRIMARY KEY AUTOINCREMENT,\n    \
+                               state TEXT NOT NULL DEFAULT '0',\n    name VARCHAR(25) NOT NULL,\n    UNIQUE (name)\n)");
+        assert_eq!(to_sqlite("INSERT IGNORE INTO a VALUES (1)"), "INSERT OR IGNORE INTO a VALUES (1)");
+        assert_eq!(
+            to_sqlite("ALTER TABLE a ADD COLUMN b ENUM('0', '1') NOT NULL DEFAULT '1'"),
+            "ALTER TABLE a ADD COLUMN b TEXT NOT NULL DEFAULT '1'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_sqlite() {
+        let pool = DatabasePool::Sqlite(crate::database::database::Database::connect_sqlite(":memory:").unwrap());
+        let migrator = Migrator::new(MIGRATIONS);
+
+        // A dry run leaves the schema alone
+        assert_eq!(migrator.run(&pool, true).await.unwrap().len(), MIGRATIONS.len());
+        assert_eq!(migrator.get_pending(&pool).await.unwrap().len(), MIGRATIONS.len());
+
+        assert_eq!(migrator.run(&pool, false).await.unwrap().len(), MIGRATIONS.len());
+        assert!(migrator.run(&pool, false).await.unwrap().is_empty());
+        assert_eq!(pool.execute("INSERT INTO emulator_texts (`key`, `value`) VALUES ('a', 'b')").await.unwrap(), 1);
+    }
+}
@@ -2,4 +2,6 @@
 //! Handles database connections and operations
 
 pub mod database;
-pub mod database_pool;
\ No newline at end of file
+pub mod database_pool;
+pub mod migrations;
+pub mod repositories;
\ No newline at end of file
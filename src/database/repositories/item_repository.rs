@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use sqlx::{ColumnIndex, Decode, Row, Type};
+
+use crate::database::database_pool::{on_pool, DatabasePool};
+
+/// A row of `items_base`, what a piece of furniture is
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDefinitionRecord {
+    pub id: i32,
+    pub sprite_id: i32,
+    pub item_name: String,
+    pub public_name: String,
+    pub width: i32,
+    pub length: i32,
+    pub stack_height: f64,
+    pub allow_stack: bool,
+    pub allow_sit: bool,
+    pub allow_lay: bool,
+    pub allow_walk: bool,
+    pub item_type: String,
+    pub interaction_type: String,
+    pub interaction_modes_count: i32,
+    /// Hand items a vending machine gives out, separated by `,` or `;`
+    pub vending_ids: String,
+    /// Heights of a multiheight item by state, separated by `;`
+    pub multiheight: String,
+}
+
+impl ItemDefinitionRecord {
+    fn from_row<R: Row>(row: &R) -> Result<Self, sqlx::Error>
+    where
+        for<'c> &'c str: ColumnIndex<R>,
+        for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> f64: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        // The flags are ENUM('0', '1') columns
+        let flag = |column: &str| -> Result<bool, sqlx::Error> { Ok(row.try_get::<String, _>(column)? == "1") };
+
+        Ok(ItemDefinitionRecord {
+            id: row.try_get("id")?,
+            sprite_id: row.try_get("sprite_id")?,
+            item_name: row.try_get("item_name")?,
+            public_name: row.try_get("public_name")?,
+            width: row.try_get("width")?,
+            length: row.try_get("length")?,
+            stack_height: row.try_get("stack_height")?,
+            allow_stack: flag("allow_stack")?,
+            allow_sit: flag("allow_sit")?,
+            allow_lay: flag("allow_lay")?,
+            allow_walk: flag("allow_walk")?,
+            item_type: row.try_get("type")?,
+            interaction_type: row.try_get("interaction_type")?,
+            interaction_modes_count: row.try_get("interaction_modes_count")?,
+            vending_ids: row.try_get("vending_ids")?,
+            multiheight: row.try_get("multiheight")?,
+        })
+    }
+}
+
+/// A row of `items`, a piece of furniture someone owns. A room id of 0 means it is in the inventory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemRecord {
+    pub id: i32,
+    pub user_id: i32,
+    pub room_id: i32,
+    pub item_id: i32,
+    pub wall_pos: String,
+    pub x: i32,
+    pub y: i32,
+    pub z: f64,
+    pub rot: i32,
+    pub extra_data: String,
+}
+
+impl ItemRecord {
+    fn from_row<R: Row>(row: &R) -> Result<Self, sqlx::Error>
+    where
+        for<'c> &'c str: ColumnIndex<R>,
+        for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> f64: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        Ok(ItemRecord {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            room_id: row.try_get("room_id")?,
+            item_id: row.try_get("item_id")?,
+            wall_pos: row.try_get("wall_pos")?,
+            x: row.try_get("x")?,
+            y: row.try_get("y")?,
+            z: row.try_get("z")?,
+            rot: row.try_get("rot")?,
+            extra_data: row.try_get("extra_data")?,
+        })
+    }
+}
+
+const ITEM_COLUMNS: &str = "id, user_id, room_id, item_id, wall_pos, x, y, z, rot, extra_data";
+
+/// Furniture definitions and the furniture users own
+#[async_trait]
+pub trait ItemRepository: Send + Sync {
+    async fn get_item_definitions(&self) -> Result<Vec<ItemDefinitionRecord>, sqlx::Error>;
+
+    async fn get_room_items(&self, room_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error>;
+
+    async fn get_inventory_items(&self, user_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error>;
+
+    /// Writes where an item is and its state
+    async fn save_item(&self, item: &ItemRecord) -> Result<(), sqlx::Error>;
+
+    async fn delete_item(&self, item_id: i32) -> Result<(), sqlx::Error>;
+}
+
+/// Reads and writes the tables of either database driver
+pub struct SqlItemRepository {
+    pool: DatabasePool,
+}
+
+impl SqlItemRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        SqlItemRepository { pool }
+    }
+}
+
+#[async_trait]
+impl ItemRepository for SqlItemRepository {
+    async fn get_item_definitions(&self) -> Result<Vec<ItemDefinitionRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query("SELECT id, sprite_id, item_name, public_name, width, length, stack_height, allow_stack, allow_sit, \
+                         allow_lay, allow_walk, type, interaction_type, interaction_modes_count, vending_ids, multiheight \
+                         FROM items_base ORDER BY id")
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(ItemDefinitionRecord::from_row)
+                .collect()
+        })
+    }
+
+    async fn get_room_items(&self, room_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM items WHERE room_id = ?", ITEM_COLUMNS))
+                .bind(room_id)
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(ItemRecord::from_row)
+                .collect()
+        })
+    }
+
+    async fn get_inventory_items(&self, user_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM items WHERE user_id = ? AND room_id = 0", ITEM_COLUMNS))
+                .bind(user_id)
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(ItemRecord::from_row)
+                .collect()
+        })
+    }
+
+    async fn save_item(&self, item: &ItemRecord) -> Result<(), sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query("UPDATE items SET user_id = ?, room_id = ?, wall_pos = ?, x = ?, y = ?, z = ?, rot = ?, extra_data = ? WHERE id = ?")
+                .bind(item.user_id)
+                .bind(item.room_id)
+                .bind(&item.wall_pos)
+                .bind(item.x)
+                .bind(item.y)
+                .bind(item.z)
+                .bind(item.rot)
+                .bind(&item.extra_data)
+                .bind(item.id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+
+    async fn delete_item(&self, item_id: i32) -> Result<(), sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query("DELETE FROM items WHERE id = ?")
+                .bind(item_id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory, for tests
+#[derive(Default)]
+pub struct InMemoryItemRepository {
+    definitions: Mutex<Vec<ItemDefinitionRecord>>,
+    items: Mutex<BTreeMap<i32, ItemRecord>>,
+}
+
+impl InMemoryItemRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_item_definition(&self, definition: ItemDefinitionRecord) {
+        self.definitions.lock().unwrap().push(definition);
+    }
+
+    pub fn insert_item(&self, item: ItemRecord) {
+        self.items.lock().unwrap().insert(item.id, item);
+    }
+}
+
+#[async_trait]
+impl ItemRepository for InMemoryItemRepository {
+    async fn get_item_definitions(&self) -> Result<Vec<ItemDefinitionRecord>, sqlx::Error> {
+        Ok(self.definitions.lock().unwrap().clone())
+    }
+
+    async fn get_room_items(&self, room_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error> {
+        Ok(self.items.lock().unwrap().values().filter(|item| item.room_id == room_id).cloned().collect())
+    }
+
+    async fn get_inventory_items(&self, user_id: i32) -> Result<Vec<ItemRecord>, sqlx::Error> {
+        Ok(self.items.lock().unwrap().values()
+            .filter(|item| item.user_id == user_id && item.room_id == 0)
+            .cloned()
+            .collect())
+    }
+
+    async fn save_item(&self, item: &ItemRecord) -> Result<(), sqlx::Error> {
+        if let Some(saved) = self.items.lock().unwrap().get_mut(&item.id) {
+            *saved = item.clone();
+        }
+        Ok(())
+    }
+
+    async fn delete_item(&self, item_id: i32) -> Result<(), sqlx::Error> {
+        self.items.lock().unwrap().remove(&item_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Repositories over the database, one per area of the hotel
+//! Every repository has an SQL implementation for MySQL and SQLite, and one that keeps everything in memory for tests
+
+pub mod item_repository;
+pub mod room_repository;
+pub mod settings_repository;
+pub mod user_repository;
+
+use std::sync::Arc;
+
+use crate::database::database_pool::DatabasePool;
+
+pub use item_repository::{InMemoryItemRepository, ItemDefinitionRecord, ItemRecord, ItemRepository, SqlItemRepository};
+pub use room_repository::{InMemoryRoomRepository, RoomModelRecord, RoomRecord, RoomRepository, SqlRoomRepository};
+pub use settings_repository::{InMemorySettingsRepository, SettingsRepository, SqlSettingsRepository};
+pub use user_repository::{InMemoryUserRepository, SqlUserRepository, UserRecord, UserRepository, UserSettingsRecord};
+
+/// Every repository, handed to whatever needs to read or write data
+#[derive(Clone)]
+pub struct Repositories {
+    users: Arc<dyn UserRepository>,
+    rooms: Arc<dyn RoomRepository>,
+    items: Arc<dyn ItemRepository>,
+    settings: Arc<dyn SettingsRepository>,
+}
+
+impl Repositories {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        rooms: Arc<dyn RoomRepository>,
+        items: Arc<dyn ItemRepository>,
+        settings: Arc<dyn SettingsRepository>,
+    ) -> Self {
+        Repositories { users, rooms, items, settings }
+    }
+
+    /// Repositories over the database, whichever driver it uses
+    pub fn sql(pool: DatabasePool) -> Self {
+        Self::new(
+            Arc::new(SqlUserRepository::new(pool.clone())),
+            Arc::new(SqlRoomRepository::new(pool.clone())),
+            Arc::new(SqlItemRepository::new(pool.clone())),
+            Arc::new(SqlSettingsRepository::new(pool)),
+        )
+    }
+
+    /// Empty repositories that live in memory
+    pub fn in_memory() -> Self {
+        Self::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryRoomRepository::new()),
+            Arc::new(InMemoryItemRepository::new()),
+            Arc::new(InMemorySettingsRepository::new()),
+        )
+    }
+
+    pub fn get_users(&self) -> &Arc<dyn UserRepository> {
+        &self.users
+    }
+
+    pub fn get_rooms(&self) -> &Arc<dyn RoomRepository> {
+        &self.rooms
+    }
+
+    pub fn get_items(&self) -> &Arc<dyn ItemRepository> {
+        &self.items
+    }
+
+    pub fn get_settings(&self) -> &Arc<dyn SettingsRepository> {
+        &self.settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::database::Database;
+    use crate::database::migrations::{Migrator, MIGRATIONS};
+
+    #[tokio::test]
+    async fn test_sqlite_repositories() {
+        let pool = DatabasePool::Sqlite(Database::connect_sqlite(":memory:").unwrap());
+        Migrator::new(MIGRATIONS).run(&pool, false).await.unwrap();
+        let repositories = Repositories::sql(pool.clone());
+
+        pool.execute("INSERT INTO users (username, motto, look, gender, `rank`, credits) VALUES ('sulove', '', '', 'M', 1, 100)")
+            .await
+            .unwrap();
+
+        let users = repositories.get_users();
+        assert_eq!(users.get_user(1).await.unwrap().unwrap().username, "sulove");
+        assert_eq!(users.add_credits(1, 50).await.unwrap(), Some(150));
+        assert_eq!(users.add_credits(2, 50).await.unwrap(), None);
+        assert_eq!(users.add_currency(1, 5, 10).await.unwrap(), 10);
+        assert_eq!(users.add_currency(1, 5, 10).await.unwrap(), 20);
+        assert_eq!(users.add_badge(1, "ADM").await.unwrap(), 1);
+        assert!(users.has_badge(1, "ADM").await.unwrap());
+        assert_eq!(users.get_currencies(1).await.unwrap(), [(5, 20)]);
+        assert_eq!(users.get_user_settings(1).await.unwrap(), UserSettingsRecord::default());
+        assert_eq!(users.get_user_settings(1).await.unwrap().daily_respect_points, 3);
+
+        let mut user = users.get_user(1).await.unwrap().unwrap();
+        user.motto = "Saved".to_string();
+        users.save_user(&user).await.unwrap();
+        assert_eq!(users.get_user(1).await.unwrap().unwrap(), user);
+
+        let user_settings = UserSettingsRecord { old_chat: true, volume_trax: 50, ..UserSettingsRecord::default() };
+        users.save_user_settings(1, &user_settings).await.unwrap();
+        assert_eq!(users.get_user_settings(1).await.unwrap(), user_settings);
+
+        pool.execute("UPDATE users SET auth_ticket = 'ticket' WHERE id = 1").await.unwrap();
+        assert!(users.get_user_by_auth_ticket("").await.unwrap().is_none());
+        assert_eq!(users.get_user_by_auth_ticket("ticket").await.unwrap().unwrap().id, 1);
+        assert!(users.claim_auth_ticket(1, "ticket").await.unwrap());
+        assert!(!users.claim_auth_ticket(1, "ticket").await.unwrap());
+        assert!(users.get_user_by_auth_ticket("ticket").await.unwrap().is_none());
+        users.set_online(1, true).await.unwrap();
+
+        let settings = repositories.get_settings();
+        settings.set_setting("hotel.name", "Sulove").await.unwrap();
+        settings.set_setting("hotel.name", "Hotel").await.unwrap();
+        assert_eq!(settings.get_settings().await.unwrap(), [("hotel.name".to_string(), "Hotel".to_string())]);
+
+        // 011 replaced the \r escapes of model_a, SQLite kept them as a backslash and an r
+        let models = repositories.get_rooms().get_room_models().await.unwrap();
+        assert_eq!(models[0].heightmap.lines().count(), 16);
+        assert!(!models[0].heightmap.contains('\\'));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use sqlx::{ColumnIndex, Decode, Row, Type};
+
+use crate::database::database_pool::{on_pool, DatabasePool};
+
+/// A row of `rooms`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomRecord {
+    pub id: i32,
+    pub owner_id: i32,
+    pub owner_name: String,
+    pub name: String,
+    pub description: String,
+    pub model: String,
+    pub state: String,
+    pub users_max: i32,
+    pub category: i32,
+    pub score: i32,
+    /// Users may walk over tiles other users stand on
+    pub allow_walkthrough: bool,
+}
+
+impl RoomRecord {
+    fn from_row<R: Row>(row: &R) -> Result<Self, sqlx::Error>
+    where
+        for<'c> &'c str: ColumnIndex<R>,
+        for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        Ok(RoomRecord {
+            id: row.try_get("id")?,
+            owner_id: row.try_get("owner_id")?,
+            owner_name: row.try_get("owner_name")?,
+            name: row.try_get("name")?,
+            description: row.try_get("description")?,
+            model: row.try_get("model")?,
+            state: row.try_get("state")?,
+            users_max: row.try_get("users_max")?,
+            category: row.try_get("category")?,
+            score: row.try_get("score")?,
+            // An ENUM('0', '1') column
+            allow_walkthrough: row.try_get::<String, _>("allow_walkthrough")? == "1",
+        })
+    }
+}
+
+/// A row of `room_models`, the layout a room is built on
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomModelRecord {
+    pub name: String,
+    pub door_x: i32,
+    pub door_y: i32,
+    pub door_dir: i32,
+    pub heightmap: String,
+}
+
+const ROOM_COLUMNS: &str = "id, owner_id, owner_name, name, description, model, state, users_max, category, score, allow_walkthrough";
+
+/// Rooms and their layouts
+#[async_trait]
+pub trait RoomRepository: Send + Sync {
+    async fn get_room(&self, room_id: i32) -> Result<Option<RoomRecord>, sqlx::Error>;
+
+    async fn get_rooms_by_owner(&self, owner_id: i32) -> Result<Vec<RoomRecord>, sqlx::Error>;
+
+    async fn get_room_models(&self) -> Result<Vec<RoomModelRecord>, sqlx::Error>;
+
+    /// The layout made for one room only, it takes the place of the room's model
+    async fn get_custom_room_model(&self, room_id: i32) -> Result<Option<RoomModelRecord>, sqlx::Error>;
+
+    /// Writes the settings of an existing room
+    async fn save_room(&self, room: &RoomRecord) -> Result<(), sqlx::Error>;
+}
+
+/// Reads and writes the tables of either database driver
+pub struct SqlRoomRepository {
+    pool: DatabasePool,
+}
+
+impl SqlRoomRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        SqlRoomRepository { pool }
+    }
+}
+
+#[async_trait]
+impl RoomRepository for SqlRoomRepository {
+    async fn get_room(&self, room_id: i32) -> Result<Option<RoomRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM rooms WHERE id = ?", ROOM_COLUMNS))
+                .bind(room_id)
+                .fetch_optional(pool)
+                .await?
+                .map(|row| RoomRecord::from_row(&row))
+                .transpose()
+        })
+    }
+
+    async fn get_rooms_by_owner(&self, owner_id: i32) -> Result<Vec<RoomRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM rooms WHERE owner_id = ? ORDER BY id", ROOM_COLUMNS))
+                .bind(owner_id)
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(RoomRecord::from_row)
+                .collect()
+        })
+    }
+
+    async fn get_room_models(&self) -> Result<Vec<RoomModelRecord>, sqlx::Error> {
+        let rows: Vec<(String, i32, i32, i32, String)> = on_pool!(&self.pool, pool => {
+            sqlx::query_as("SELECT name, door_x, door_y, door_dir, heightmap FROM room_models")
+                .fetch_all(pool)
+                .await?
+        });
+
+        Ok(rows.into_iter()
+            .map(|(name, door_x, door_y, door_dir, heightmap)| RoomModelRecord { name, door_x, door_y, door_dir, heightmap })
+            .collect())
+    }
+
+    async fn get_custom_room_model(&self, room_id: i32) -> Result<Option<RoomModelRecord>, sqlx::Error> {
+        let row: Option<(String, i32, i32, i32, String)> = on_pool!(&self.pool, pool => {
+            sqlx::query_as("SELECT name, door_x, door_y, door_dir, heightmap FROM room_models_custom WHERE id = ?")
+                .bind(room_id)
+                .fetch_optional(pool)
+                .await?
+        });
+
+        Ok(row.map(|(name, door_x, door_y, door_dir, heightmap)| RoomModelRecord { name, door_x, door_y, door_dir, heightmap }))
+    }
+
+    async fn save_room(&self, room: &RoomRecord) -> Result<(), sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query("UPDATE rooms SET name = ?, description = ?, model = ?, state = ?, users_max = ?, category = ?, score = ?, \
+                         allow_walkthrough = ? WHERE id = ?")
+                .bind(&room.name)
+                .bind(&room.description)
+                .bind(&room.model)
+                .bind(&room.state)
+                .bind(room.users_max)
+                .bind(room.category)
+                .bind(room.score)
+                .bind(if room.allow_walkthrough { "1" } else { "0" })
+                .bind(room.id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory, for tests
+#[derive(Default)]
+pub struct InMemoryRoomRepository {
+    rooms: Mutex<HashMap<i32, RoomRecord>>,
+    models: Mutex<Vec<RoomModelRecord>>,
+    custom_models: Mutex<HashMap<i32, RoomModelRecord>>,
+}
+
+impl InMemoryRoomRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_room(&self, room: RoomRecord) {
+        self.rooms.lock().unwrap().insert(room.id, room);
+    }
+
+    pub fn insert_room_model(&self, model: RoomModelRecord) {
+        self.models.lock().unwrap().push(model);
+    }
+
+    pub fn insert_custom_room_model(&self, room_id: i32, model: RoomModelRecord) {
+        self.custom_models.lock().unwrap().insert(room_id, model);
+    }
+}
+
+#[async_trait]
+impl RoomRepository for InMemoryRoomRepository {
+    async fn get_room(&self, room_id: i32) -> Result<Option<RoomRecord>, sqlx::Error> {
+        Ok(self.rooms.lock().unwrap().get(&room_id).cloned())
+    }
+
+    async fn get_rooms_by_owner(&self, owner_id: i32) -> Result<Vec<RoomRecord>, sqlx::Error> {
+        let mut rooms: Vec<_> = self.rooms.lock().unwrap().values()
+            .filter(|room| room.owner_id == owner_id)
+            .cloned()
+            .collect();
+        rooms.sort_by_key(|room| room.id);
+        Ok(rooms)
+    }
+
+    async fn get_room_models(&self) -> Result<Vec<RoomModelRecord>, sqlx::Error> {
+        Ok(self.models.lock().unwrap().clone())
+    }
+
+    async fn get_custom_room_model(&self, room_id: i32) -> Result<Option<RoomModelRecord>, sqlx::Error> {
+        Ok(self.custom_models.lock().unwrap().get(&room_id).cloned())
+    }
+
+    async fn save_room(&self, room: &RoomRecord) -> Result<(), sqlx::Error> {
+        if let Some(saved) = self.rooms.lock().unwrap().get_mut(&room.id) {
+            *saved = room.clone();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+
+use crate::database::database_pool::{on_pool, DatabaseDriver, DatabasePool};
+
+/// The `emulator_settings` and `emulator_texts` tables
+#[async_trait]
+pub trait SettingsRepository: Send + Sync {
+    async fn get_settings(&self) -> Result<Vec<(String, String)>, sqlx::Error>;
+
+    async fn get_texts(&self) -> Result<Vec<(String, String)>, sqlx::Error>;
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<(), sqlx::Error>;
+}
+
+/// Reads and writes the tables of either database driver
+pub struct SqlSettingsRepository {
+    pool: DatabasePool,
+}
+
+impl SqlSettingsRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        SqlSettingsRepository { pool }
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for SqlSettingsRepository {
+    async fn get_settings(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query_as("SELECT `key`, `value` FROM emulator_settings")
+                .fetch_all(pool)
+                .await
+        })
+    }
+
+    async fn get_texts(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query_as("SELECT `key`, `value` FROM emulator_texts")
+                .fetch_all(pool)
+                .await
+        })
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        let upsert = match self.pool.get_driver() {
+            DatabaseDriver::MySql => "ON DUPLICATE KEY UPDATE `value` = VALUES(`value`)",
+            DatabaseDriver::Sqlite => "ON CONFLICT (`key`) DO UPDATE SET `value` = excluded.`value`",
+        };
+        let query = format!("INSERT INTO emulator_settings (`key`, `value`) VALUES (?, ?) {}", upsert);
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&query)
+                .bind(key)
+                .bind(value)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory, for tests
+#[derive(Default)]
+pub struct InMemorySettingsRepository {
+    settings: Mutex<BTreeMap<String, String>>,
+    texts: Mutex<BTreeMap<String, String>>,
+}
+
+impl InMemorySettingsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_text(&self, key: &str, value: &str) {
+        self.texts.lock().unwrap().insert(key.to_string(), value.to_string());
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for InMemorySettingsRepository {
+    async fn get_settings(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        Ok(self.settings.lock().unwrap().iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+    }
+
+    async fn get_texts(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        Ok(self.texts.lock().unwrap().iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        self.settings.lock().unwrap().insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
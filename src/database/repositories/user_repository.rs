@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use sqlx::{ColumnIndex, Decode, Row, Type};
+
+use crate::database::database_pool::{on_pool, DatabaseDriver, DatabasePool};
+
+/// A row of `users`, only the columns the emulator uses
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserRecord {
+    pub id: i32,
+    pub username: String,
+    pub motto: String,
+    pub look: String,
+    pub gender: String,
+    pub rank: i32,
+    pub credits: i32,
+    pub home_room: i32,
+}
+
+impl UserRecord {
+    fn from_row<R: Row>(row: &R) -> Result<Self, sqlx::Error>
+    where
+        for<'c> &'c str: ColumnIndex<R>,
+        for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        Ok(UserRecord {
+            id: row.try_get("id")?,
+            username: row.try_get("username")?,
+            motto: row.try_get("motto")?,
+            look: row.try_get("look")?,
+            gender: row.try_get("gender")?,
+            rank: row.try_get("rank")?,
+            credits: row.try_get("credits")?,
+            home_room: row.try_get("home_room")?,
+        })
+    }
+}
+
+/// A row of `users_settings`, the stats and preferences of a user
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserSettingsRecord {
+    pub respects_received: i32,
+    pub daily_respect_points: i32,
+    pub daily_pet_respect_points: i32,
+    pub achievement_score: i32,
+    pub volume_system: i32,
+    pub volume_furni: i32,
+    pub volume_trax: i32,
+    pub old_chat: bool,
+    pub block_following: bool,
+    pub block_friendrequests: bool,
+}
+
+impl Default for UserSettingsRecord {
+    // The column defaults, for users that never logged in
+    fn default() -> Self {
+        UserSettingsRecord {
+            respects_received: 0,
+            daily_respect_points: 3,
+            daily_pet_respect_points: 3,
+            achievement_score: 0,
+            volume_system: 100,
+            volume_furni: 100,
+            volume_trax: 100,
+            old_chat: false,
+            block_following: false,
+            block_friendrequests: false,
+        }
+    }
+}
+
+impl UserSettingsRecord {
+    fn from_row<R: Row>(row: &R) -> Result<Self, sqlx::Error>
+    where
+        for<'c> &'c str: ColumnIndex<R>,
+        for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+        for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        // The flags are ENUM('0', '1') columns
+        let flag = |column: &str| -> Result<bool, sqlx::Error> { Ok(row.try_get::<String, _>(column)? == "1") };
+
+        Ok(UserSettingsRecord {
+            respects_received: row.try_get("respects_received")?,
+            daily_respect_points: row.try_get("daily_respect_points")?,
+            daily_pet_respect_points: row.try_get("daily_pet_respect_points")?,
+            achievement_score: row.try_get("achievement_score")?,
+            volume_system: row.try_get("volume_system")?,
+            volume_furni: row.try_get("volume_furni")?,
+            volume_trax: row.try_get("volume_trax")?,
+            old_chat: flag("old_chat")?,
+            block_following: flag("block_following")?,
+            block_friendrequests: flag("block_friendrequests")?,
+        })
+    }
+}
+
+const USER_COLUMNS: &str = "id, username, motto, look, gender, `rank`, credits, home_room";
+
+const SETTINGS_COLUMNS: &str = "respects_received, daily_respect_points, daily_pet_respect_points, achievement_score, \
+                                volume_system, volume_furni, volume_trax, old_chat, block_following, block_friendrequests";
+
+/// Users, their currencies and their badges
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn get_user(&self, user_id: i32) -> Result<Option<UserRecord>, sqlx::Error>;
+
+    /// The user an SSO ticket belongs to, an empty ticket never matches
+    async fn get_user_by_auth_ticket(&self, ticket: &str) -> Result<Option<UserRecord>, sqlx::Error>;
+
+    /// Tickets are single use, logging in clears the ticket. Only one of the connections that
+    /// send the same ticket at once gets true.
+    async fn claim_auth_ticket(&self, user_id: i32, ticket: &str) -> Result<bool, sqlx::Error>;
+
+    /// Marks the user online or offline and records when
+    async fn set_online(&self, user_id: i32, online: bool) -> Result<(), sqlx::Error>;
+
+    /// Stats and preferences, created with the defaults if the user has none yet
+    async fn get_user_settings(&self, user_id: i32) -> Result<UserSettingsRecord, sqlx::Error>;
+
+    /// Writes what a logged in user can change about themselves, the username and rank are left alone
+    async fn save_user(&self, user: &UserRecord) -> Result<(), sqlx::Error>;
+
+    async fn save_user_settings(&self, user_id: i32, settings: &UserSettingsRecord) -> Result<(), sqlx::Error>;
+
+    /// Adds credits and returns the new balance, None if the user doesn't exist
+    async fn add_credits(&self, user_id: i32, amount: i32) -> Result<Option<i32>, sqlx::Error>;
+
+    async fn get_currency(&self, user_id: i32, currency_type: i32) -> Result<i32, sqlx::Error>;
+
+    /// Every `users_currency` balance of the user as (type, amount)
+    async fn get_currencies(&self, user_id: i32) -> Result<Vec<(i32, i32)>, sqlx::Error>;
+
+    /// Adds to a `users_currency` balance and returns the new one
+    async fn add_currency(&self, user_id: i32, currency_type: i32, amount: i32) -> Result<i32, sqlx::Error>;
+
+    async fn has_badge(&self, user_id: i32, badge_code: &str) -> Result<bool, sqlx::Error>;
+
+    /// Gives a badge and returns the id of its row
+    async fn add_badge(&self, user_id: i32, badge_code: &str) -> Result<i32, sqlx::Error>;
+}
+
+/// Reads and writes the tables of either database driver
+pub struct SqlUserRepository {
+    pool: DatabasePool,
+}
+
+impl SqlUserRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        SqlUserRepository { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqlUserRepository {
+    async fn get_user(&self, user_id: i32) -> Result<Option<UserRecord>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?
+                .map(|row| UserRecord::from_row(&row))
+                .transpose()
+        })
+    }
+
+    async fn get_user_by_auth_ticket(&self, ticket: &str) -> Result<Option<UserRecord>, sqlx::Error> {
+        if ticket.is_empty() {
+            return Ok(None);
+        }
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM users WHERE auth_ticket = ? LIMIT 1", USER_COLUMNS))
+                .bind(ticket)
+                .fetch_optional(pool)
+                .await?
+                .map(|row| UserRecord::from_row(&row))
+                .transpose()
+        })
+    }
+
+    async fn claim_auth_ticket(&self, user_id: i32, ticket: &str) -> Result<bool, sqlx::Error> {
+        if ticket.is_empty() {
+            return Ok(false);
+        }
+
+        let rows_affected = on_pool!(&self.pool, pool => {
+            sqlx::query("UPDATE users SET auth_ticket = '' WHERE id = ? AND auth_ticket = ?")
+                .bind(user_id)
+                .bind(ticket)
+                .execute(pool)
+                .await?
+                .rows_affected()
+        });
+
+        Ok(rows_affected == 1)
+    }
+
+    async fn set_online(&self, user_id: i32, online: bool) -> Result<(), sqlx::Error> {
+        let query = match online {
+            true => "UPDATE users SET online = '1', last_login = ? WHERE id = ?",
+            false => "UPDATE users SET online = '0', last_online = ? WHERE id = ?",
+        };
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query(query)
+                .bind(crate::get_unix_timestamp() as i32)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+
+    async fn get_user_settings(&self, user_id: i32) -> Result<UserSettingsRecord, sqlx::Error> {
+        let settings = on_pool!(&self.pool, pool => {
+            sqlx::query(&format!("SELECT {} FROM users_settings WHERE user_id = ?", SETTINGS_COLUMNS))
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?
+                .map(|row| UserSettingsRecord::from_row(&row))
+                .transpose()?
+        });
+
+        if let Some(settings) = settings {
+            return Ok(settings);
+        }
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query("INSERT INTO users_settings (user_id) VALUES (?)")
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(UserSettingsRecord::default())
+    }
+
+    async fn save_user(&self, user: &UserRecord) -> Result<(), sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query("UPDATE users SET motto = ?, look = ?, gender = ?, credits = ?, home_room = ? WHERE id = ?")
+                .bind(&user.motto)
+                .bind(&user.look)
+                .bind(&user.gender)
+                .bind(user.credits)
+                .bind(user.home_room)
+                .bind(user.id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+
+    async fn save_user_settings(&self, user_id: i32, settings: &UserSettingsRecord) -> Result<(), sqlx::Error> {
+        let flag = |value: bool| if value { "1" } else { "0" };
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query("UPDATE users_settings SET respects_received = ?, daily_respect_points = ?, daily_pet_respect_points = ?, \
+                         achievement_score = ?, volume_system = ?, volume_furni = ?, volume_trax = ?, old_chat = ?, \
+                         block_following = ?, block_friendrequests = ? WHERE user_id = ?")
+                .bind(settings.respects_received)
+                .bind(settings.daily_respect_points)
+                .bind(settings.daily_pet_respect_points)
+                .bind(settings.achievement_score)
+                .bind(settings.volume_system)
+                .bind(settings.volume_furni)
+                .bind(settings.volume_trax)
+                .bind(flag(settings.old_chat))
+                .bind(flag(settings.block_following))
+                .bind(flag(settings.block_friendrequests))
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        });
+
+        Ok(())
+    }
+
+    async fn add_credits(&self, user_id: i32, amount: i32) -> Result<Option<i32>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            let result = sqlx::query("UPDATE users SET credits = credits + ? WHERE id = ?")
+                .bind(amount)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+
+            if result.rows_affected() == 0 {
+                return Ok(None);
+            }
+
+            sqlx::query_scalar("SELECT credits FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+        })
+    }
+
+    async fn get_currency(&self, user_id: i32, currency_type: i32) -> Result<i32, sqlx::Error> {
+        let amount: Option<i32> = on_pool!(&self.pool, pool => {
+            sqlx::query_scalar("SELECT amount FROM users_currency WHERE user_id = ? AND type = ?")
+                .bind(user_id)
+                .bind(currency_type)
+                .fetch_optional(pool)
+                .await?
+        });
+
+        Ok(amount.unwrap_or(0))
+    }
+
+    async fn get_currencies(&self, user_id: i32) -> Result<Vec<(i32, i32)>, sqlx::Error> {
+        on_pool!(&self.pool, pool => {
+            sqlx::query_as("SELECT type, amount FROM users_currency WHERE user_id = ? ORDER BY type")
+                .bind(user_id)
+                .fetch_all(pool)
+                .await
+        })
+    }
+
+    async fn add_currency(&self, user_id: i32, currency_type: i32, amount: i32) -> Result<i32, sqlx::Error> {
+        let upsert = match self.pool.get_driver() {
+            DatabaseDriver::MySql => "ON DUPLICATE KEY UPDATE amount = amount + VALUES(amount)",
+            DatabaseDriver::Sqlite => "ON CONFLICT (user_id, type) DO UPDATE SET amount = amount + excluded.amount",
+        };
+        let query = format!("INSERT INTO users_currency (user_id, type, amount) VALUES (?, ?, ?) {}", upsert);
+
+        on_pool!(&self.pool, pool => {
+            sqlx::query(&query)
+                .bind(user_id)
+                .bind(currency_type)
+                .bind(amount)
+                .execute(pool)
+                .await?;
+        });
+
+        self.get_currency(user_id, currency_type).await
+    }
+
+    async fn has_badge(&self, user_id: i32, badge_code: &str) -> Result<bool, sqlx::Error> {
+        let id: Option<i32> = on_pool!(&self.pool, pool => {
+            sqlx::query_scalar("SELECT id FROM users_badges WHERE user_id = ? AND badge_code = ?")
+                .bind(user_id)
+                .bind(badge_code)
+                .fetch_optional(pool)
+                .await?
+        });
+
+        Ok(id.is_some())
+    }
+
+    async fn add_badge(&self, user_id: i32, badge_code: &str) -> Result<i32, sqlx::Error> {
+        let query = "INSERT INTO users_badges (user_id, slot_id, badge_code) VALUES (?, 0, ?)";
+
+        // Both drivers name the id of the new row differently
+        let id = match &self.pool {
+            DatabasePool::MySql(pool) => {
+                sqlx::query(query).bind(user_id).bind(badge_code).execute(pool).await?.last_insert_id() as i64
+            }
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(query).bind(user_id).bind(badge_code).execute(pool).await?.last_insert_rowid()
+            }
+        };
+
+        Ok(id as i32)
+    }
+}
+
+/// Keeps everything in memory, for tests
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<HashMap<i32, UserRecord>>,
+    auth_tickets: Mutex<HashMap<i32, String>>,
+    online: Mutex<HashMap<i32, bool>>,
+    settings: Mutex<HashMap<i32, UserSettingsRecord>>,
+    currencies: Mutex<HashMap<(i32, i32), i32>>,
+    badges: Mutex<Vec<(i32, i32, String)>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_user(&self, user: UserRecord) {
+        self.users.lock().unwrap().insert(user.id, user);
+    }
+
+    pub fn set_auth_ticket(&self, user_id: i32, ticket: &str) {
+        self.auth_tickets.lock().unwrap().insert(user_id, ticket.to_string());
+    }
+
+    pub fn is_online(&self, user_id: i32) -> bool {
+        self.online.lock().unwrap().get(&user_id).copied().unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn get_user(&self, user_id: i32) -> Result<Option<UserRecord>, sqlx::Error> {
+        Ok(self.users.lock().unwrap().get(&user_id).cloned())
+    }
+
+    async fn get_user_by_auth_ticket(&self, ticket: &str) -> Result<Option<UserRecord>, sqlx::Error> {
+        if ticket.is_empty() {
+            return Ok(None);
+        }
+
+        let user_id = self.auth_tickets.lock().unwrap().iter()
+            .find(|(_, user_ticket)| user_ticket.as_str() == ticket)
+            .map(|(user_id, _)| *user_id);
+
+        match user_id {
+            Some(user_id) => self.get_user(user_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn claim_auth_ticket(&self, user_id: i32, ticket: &str) -> Result<bool, sqlx::Error> {
+        let mut auth_tickets = self.auth_tickets.lock().unwrap();
+        if ticket.is_empty() || auth_tickets.get(&user_id).is_none_or(|user_ticket| user_ticket != ticket) {
+            return Ok(false);
+        }
+
+        auth_tickets.remove(&user_id);
+        Ok(true)
+    }
+
+    async fn set_online(&self, user_id: i32, online: bool) -> Result<(), sqlx::Error> {
+        self.online.lock().unwrap().insert(user_id, online);
+        Ok(())
+    }
+
+    async fn get_user_settings(&self, user_id: i32) -> Result<UserSettingsRecord, sqlx::Error> {
+        Ok(self.settings.lock().unwrap().entry(user_id).or_default().clone())
+    }
+
+    async fn save_user(&self, user: &UserRecord) -> Result<(), sqlx::Error> {
+        if let Some(saved) = self.users.lock().unwrap().get_mut(&user.id) {
+            saved.motto = user.motto.clone();
+            saved.look = user.look.clone();
+            saved.gender = user.gender.clone();
+            saved.credits = user.credits;
+            saved.home_room = user.home_room;
+        }
+        Ok(())
+    }
+
+    async fn save_user_settings(&self, user_id: i32, settings: &UserSettingsRecord) -> Result<(), sqlx::Error> {
+        self.settings.lock().unwrap().insert(user_id, settings.clone());
+        Ok(())
+    }
+
+    async fn add_credits(&self, user_id: i32, amount: i32) -> Result<Option<i32>, sqlx::Error> {
+        Ok(self.users.lock().unwrap().get_mut(&user_id).map(|user| {
+            user.credits += amount;
+            user.credits
+        }))
+    }
+
+    async fn get_currency(&self, user_id: i32, currency_type: i32) -> Result<i32, sqlx::Error> {
+        Ok(self.currencies.lock().unwrap().get(&(user_id, currency_type)).copied().unwrap_or(0))
+    }
+
+    async fn get_currencies(&self, user_id: i32) -> Result<Vec<(i32, i32)>, sqlx::Error> {
+        let mut currencies: Vec<_> = self.currencies.lock().unwrap().iter()
+            .filter(|((user, _), _)| *user == user_id)
+            .map(|((_, currency_type), amount)| (*currency_type, *amount))
+            .collect();
+        currencies.sort();
+        Ok(currencies)
+    }
+
+    async fn add_currency(&self, user_id: i32, currency_type: i32, amount: i32) -> Result<i32, sqlx::Error> {
+        let mut currencies = self.currencies.lock().unwrap();
+        let balance = currencies.entry((user_id, currency_type)).or_insert(0);
+        *balance += amount;
+        Ok(*balance)
+    }
+
+    async fn has_badge(&self, user_id: i32, badge_code: &str) -> Result<bool, sqlx::Error> {
+        Ok(self.badges.lock().unwrap().iter().any(|(_, user, code)| *user == user_id && code == badge_code))
+    }
+
+    async fn add_badge(&self, user_id: i32, badge_code: &str) -> Result<i32, sqlx::Error> {
+        let mut badges = self.badges.lock().unwrap();
+        let id = badges.len() as i32 + 1;
+        badges.push((id, user_id, badge_code.to_string()));
+        Ok(id)
+    }
+}
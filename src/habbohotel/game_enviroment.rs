@@ -1,26 +1,41 @@
+use std::error::Error;
 use std::sync::{Arc, RwLock};
+use async_trait::async_trait;
 use log::info;
 
+use crate::core::disposable::Disposable;
+use crate::database::repositories::Repositories;
+use crate::habbohotel::items::ItemManager;
+use crate::habbohotel::rooms::RoomManager;
+use crate::habbohotel::users::HabboManager;
+
 // This class will manage all the subsystems of the hotel
 // In a full implementation, it would contain references to all the
 // hotel subsystems like rooms, users, catalog, etc.
 pub struct GameEnvironment {
+    repositories: Repositories,
+    habbo_manager: HabboManager,
+    item_manager: ItemManager,
+    room_manager: RoomManager,
     // These would be the various managers for different parts of the hotel
     // For example:
-    // rooms_manager: Arc<RwLock<RoomsManager>>,
     // navigator_manager: Arc<RwLock<NavigatorManager>>,
     // catalog_manager: Arc<RwLock<CatalogManager>>,
     // etc.
 }
 
 impl GameEnvironment {
-    pub fn new() -> Self {
+    pub fn new(repositories: Repositories) -> Self {
         GameEnvironment {
+            item_manager: ItemManager::new(repositories.clone()),
+            room_manager: RoomManager::new(repositories.clone()),
+            repositories,
+            habbo_manager: HabboManager::new(),
             // Initialize all managers here
         }
     }
     
-    pub fn load(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn load(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         info!("Loading Game Environment...");
         
         // Load all the managers in the correct order
@@ -30,19 +45,54 @@ impl GameEnvironment {
         // 3. Load navigator categories
         // 4. Load catalog pages
         // etc.
+        self.item_manager.load_items().await?;
+        self.room_manager.load_models().await?;
         
         info!("Game Environment loaded successfully!");
         
         Ok(())
     }
     
-    pub fn dispose(&self) {
+    /// Saves the users that are still online and then the loaded rooms, the database
+    /// is still open until this returns
+    pub async fn dispose(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         info!("Disposing Game Environment...");
         
-        // Dispose all managers in the correct order
+        // Dispose all managers in the correct order, users first since they stand in rooms
+        let users = self.habbo_manager.dispose(self.repositories.get_users().as_ref()).await;
+        let rooms = self.room_manager.dispose().await;
+        users.and(rooms)?;
         
         info!("Game Environment disposed successfully!");
+        
+        Ok(())
     }
     
     // Getters for all the managers would be here
-}
\ No newline at end of file
+    pub fn get_repositories(&self) -> &Repositories {
+        &self.repositories
+    }
+
+    pub fn get_habbo_manager(&self) -> &HabboManager {
+        &self.habbo_manager
+    }
+
+    pub fn get_item_manager(&self) -> &ItemManager {
+        &self.item_manager
+    }
+
+    pub fn get_room_manager(&self) -> &RoomManager {
+        &self.room_manager
+    }
+}
+
+#[async_trait]
+impl Disposable for GameEnvironment {
+    fn get_name(&self) -> &'static str {
+        "Game Environment"
+    }
+
+    async fn dispose(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        GameEnvironment::dispose(self).await
+    }
+}
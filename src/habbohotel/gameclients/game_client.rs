@@ -0,0 +1,256 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use bytes::Bytes;
+use log::{debug, warn};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_util::sync::CancellationToken;
+
+use crate::crypto::habbo_diffie_hellman::HabboDiffieHellman;
+use crate::crypto::habbo_rc4::HabboRC4;
+use crate::habbohotel::users::Habbo;
+use crate::messages::server_message::ServerMessage;
+use crate::networking::gameserver::encoders::GameServerMessageEncoder;
+use crate::networking::gameserver::game_server_attributes::CryptoAttribute;
+use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+
+/// What to do when a client doesn't read its packets fast enough and its send queue fills up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueOverflowPolicy {
+    /// Disconnect the client, it is too far behind to catch up anyway
+    Disconnect,
+    /// Drop the packet and keep the client connected
+    Drop,
+}
+
+impl QueueOverflowPolicy {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "disconnect" => Some(QueueOverflowPolicy::Disconnect),
+            "drop" => Some(QueueOverflowPolicy::Drop),
+            _ => None,
+        }
+    }
+}
+
+/// A single connection to the game server.
+///
+/// Packets are never written to the socket directly. They are encoded and put on a bounded
+/// queue that the connection's writer task drains, so sending never waits on a slow client.
+pub struct GameClient {
+    id: u64,
+    address: SocketAddr,
+    outgoing: mpsc::Sender<OutgoingFrame>,
+    overflow_policy: QueueOverflowPolicy,
+    shutdown: CancellationToken,
+    crypto_client: CryptoAttribute,
+    diffie_hellman: Mutex<Option<HabboDiffieHellman>>,
+    authenticated: AtomicBool,
+    user_id: AtomicI32,
+    habbo: RwLock<Option<Arc<Habbo>>>,
+}
+
+impl GameClient {
+    /// Creates a client and the receiving end of its send queue, which belongs to the writer task
+    pub fn new(
+        id: u64,
+        address: SocketAddr,
+        queue_size: usize,
+        overflow_policy: QueueOverflowPolicy,
+    ) -> (Self, mpsc::Receiver<OutgoingFrame>) {
+        let (outgoing, receiver) = mpsc::channel(queue_size.max(1));
+
+        let client = GameClient {
+            id,
+            address,
+            outgoing,
+            overflow_policy,
+            shutdown: CancellationToken::new(),
+            crypto_client: CryptoAttribute::new(),
+            diffie_hellman: Mutex::new(None),
+            authenticated: AtomicBool::new(false),
+            user_id: AtomicI32::new(0),
+            habbo: RwLock::new(None),
+        };
+
+        (client, receiver)
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Whether the client logged in with its SSO ticket, before that only `NoAuthMessage` handlers run
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated.load(Ordering::Acquire)
+    }
+
+    pub fn set_authenticated(&self, authenticated: bool) {
+        self.authenticated.store(authenticated, Ordering::Release);
+    }
+
+    /// Id of the user logged in on this connection, 0 before the login
+    pub fn get_user_id(&self) -> i32 {
+        self.user_id.load(Ordering::Acquire)
+    }
+
+    pub fn set_user_id(&self, user_id: i32) {
+        self.user_id.store(user_id, Ordering::Release);
+    }
+
+    /// The user logged in on this connection, set by the SSO login
+    pub fn get_habbo(&self) -> Option<Arc<Habbo>> {
+        self.habbo.read().unwrap().clone()
+    }
+
+    pub fn set_habbo(&self, habbo: Arc<Habbo>) {
+        *self.habbo.write().unwrap() = Some(habbo);
+    }
+
+    /// Takes the user off the connection once it closes, the two hold on to each other until then
+    pub fn take_habbo(&self) -> Option<Arc<Habbo>> {
+        self.habbo.write().unwrap().take()
+    }
+
+    /// The cipher slot the connection's decoder reads incoming data through
+    pub fn get_crypto_client(&self) -> CryptoAttribute {
+        self.crypto_client.clone()
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.crypto_client.is_set()
+    }
+
+    /// Keeps the key exchange started by `InitDiffieHandshakeEvent` until the client completes it
+    pub fn set_diffie_hellman(&self, diffie_hellman: HabboDiffieHellman) {
+        *self.diffie_hellman.lock().unwrap() = Some(diffie_hellman);
+    }
+
+    /// Takes the pending key exchange, a handshake can only be completed once
+    pub fn take_diffie_hellman(&self) -> Option<HabboDiffieHellman> {
+        self.diffie_hellman.lock().unwrap().take()
+    }
+
+    /// Installs the RC4 ciphers for both directions once the Diffie-Hellman handshake is done.
+    /// The response and everything queued before it still go out in plain text, everything after is encrypted.
+    pub fn enable_encryption(&self, shared_key: &[u8], response: ServerMessage) -> bool {
+        if shared_key.is_empty() {
+            warn!("Refusing to enable encryption for client {} with an empty shared key", self.id);
+            return false;
+        }
+
+        // The client encrypts right after reading the response, so the incoming cipher has to be
+        // in place before the response can reach it
+        self.crypto_client.set(HabboRC4::new(shared_key));
+        if !self.send_response(response) {
+            self.disconnect();
+            return false;
+        }
+
+        // Both sides have to switch together, a client that misses the switch can't be talked to
+        if !self.enqueue(OutgoingFrame::EnableEncryption(Box::new(HabboRC4::new(shared_key)))) {
+            self.disconnect();
+            return false;
+        }
+
+        true
+    }
+
+    /// Encodes and queues a single message for this client
+    pub fn send_response(&self, message: ServerMessage) -> bool {
+        self.send_frame(GameServerMessageEncoder::encode_frame(message))
+    }
+
+    /// Queues an already encoded frame, used by broadcasts to encode a message only once
+    pub fn send_frame(&self, frame: Bytes) -> bool {
+        self.enqueue(OutgoingFrame::Packet(frame))
+    }
+
+    /// Queues bytes that bypass the encoder chain
+    pub fn send_raw(&self, data: Bytes) -> bool {
+        self.enqueue(OutgoingFrame::Raw(data))
+    }
+
+    fn enqueue(&self, frame: OutgoingFrame) -> bool {
+        if self.is_disconnected() {
+            return false;
+        }
+
+        match self.outgoing.try_send(frame) {
+            Ok(_) => true,
+            Err(TrySendError::Full(_)) => {
+                match self.overflow_policy {
+                    QueueOverflowPolicy::Disconnect => {
+                        warn!("Send queue of client {} ({}) is full, disconnecting", self.id, self.address);
+                        self.disconnect();
+                    }
+                    QueueOverflowPolicy::Drop => {
+                        debug!("Send queue of client {} ({}) is full, dropping packet", self.id, self.address);
+                    }
+                }
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                self.disconnect();
+                false
+            }
+        }
+    }
+
+    /// Closes the connection once everything that is queued right now has been written
+    pub fn close(&self) {
+        if self.outgoing.try_send(OutgoingFrame::Close).is_err() {
+            self.disconnect();
+        }
+    }
+
+    /// Closes the connection right away, anything still queued is discarded
+    pub fn disconnect(&self) {
+        self.shutdown.cancel();
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Resolves once the client has been disconnected, from either side
+    pub async fn disconnected(&self) {
+        self.shutdown.cancelled().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(queue_size: usize, overflow_policy: QueueOverflowPolicy) -> (GameClient, mpsc::Receiver<OutgoingFrame>) {
+        GameClient::new(1, "127.0.0.1:30000".parse().unwrap(), queue_size, overflow_policy)
+    }
+
+    #[test]
+    fn test_full_queue_disconnects() {
+        let (client, _receiver) = client(2, QueueOverflowPolicy::Disconnect);
+
+        assert!(client.send_response(ServerMessage::new(1)));
+        assert!(client.send_response(ServerMessage::new(2)));
+        assert!(!client.send_response(ServerMessage::new(3)));
+        assert!(client.is_disconnected());
+    }
+
+    #[test]
+    fn test_full_queue_drops() {
+        let (client, mut receiver) = client(1, QueueOverflowPolicy::Drop);
+
+        assert!(client.send_response(ServerMessage::new(1)));
+        assert!(!client.send_response(ServerMessage::new(2)));
+        assert!(!client.is_disconnected());
+
+        // Once the writer catches up the client can be sent to again
+        assert!(receiver.try_recv().is_ok());
+        assert!(client.send_response(ServerMessage::new(3)));
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+
+use crate::habbohotel::gameclients::{GameClient, QueueOverflowPolicy};
+use crate::messages::server_message::ServerMessage;
+use crate::networking::gameserver::encoders::GameServerMessageEncoder;
+use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+
+/// Keeps track of every connected game client
+pub struct GameClientManager {
+    clients: RwLock<HashMap<u64, Arc<GameClient>>>,
+    next_id: AtomicU64,
+    queue_size: usize,
+    overflow_policy: QueueOverflowPolicy,
+}
+
+impl GameClientManager {
+    pub fn new(queue_size: usize, overflow_policy: QueueOverflowPolicy) -> Self {
+        GameClientManager {
+            clients: RwLock::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            queue_size,
+            overflow_policy,
+        }
+    }
+
+    /// Creates a client for a new connection, the receiver is handed to its writer task
+    pub fn create_client(&self, address: SocketAddr) -> (Arc<GameClient>, mpsc::Receiver<OutgoingFrame>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (client, receiver) = GameClient::new(id, address, self.queue_size, self.overflow_policy);
+
+        (Arc::new(client), receiver)
+    }
+
+    pub fn add_client(&self, client: Arc<GameClient>) -> bool {
+        let mut clients = self.clients.write().unwrap();
+
+        if clients.contains_key(&client.get_id()) {
+            return false;
+        }
+
+        clients.insert(client.get_id(), client);
+        true
+    }
+
+    pub fn remove_client(&self, id: u64) -> Option<Arc<GameClient>> {
+        self.clients.write().unwrap().remove(&id)
+    }
+
+    /// Finds the connection a user is logged in on
+    pub fn get_client_by_user_id(&self, user_id: i32) -> Option<Arc<GameClient>> {
+        if user_id <= 0 {
+            return None;
+        }
+
+        self.clients.read().unwrap().values()
+            .find(|client| client.get_user_id() == user_id)
+            .cloned()
+    }
+
+    pub fn get_clients(&self) -> Vec<Arc<GameClient>> {
+        self.clients.read().unwrap().values().cloned().collect()
+    }
+
+    pub fn get_online_count(&self) -> usize {
+        self.clients.read().unwrap().len()
+    }
+
+    /// Sends a message to every connected client
+    pub fn broadcast(&self, message: ServerMessage) {
+        Self::broadcast_to(&self.get_clients(), message);
+    }
+
+    /// Sends a message to the given clients, like everyone in a room.
+    /// The message is encoded once and a full queue only affects that one client.
+    pub fn broadcast_to(clients: &[Arc<GameClient>], message: ServerMessage) {
+        let frame = GameServerMessageEncoder::encode_frame(message);
+
+        for client in clients {
+            client.send_frame(frame.clone());
+        }
+    }
+
+    /// Disconnects every client, used when the server goes down
+    pub fn dispose(&self) {
+        for client in self.get_clients() {
+            client.disconnect();
+        }
+
+        self.clients.write().unwrap().clear();
+    }
+}
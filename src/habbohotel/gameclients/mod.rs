@@ -0,0 +1,9 @@
+//! Game clients module for the Sulove emulator
+//! Keeps track of every connection to the game server
+
+pub mod game_client;
+pub mod game_client_manager;
+
+// Re-export the main structs
+pub use game_client::{GameClient, QueueOverflowPolicy};
+pub use game_client_manager::GameClientManager;
@@ -0,0 +1,15 @@
+use crate::habbohotel::items::interactions::interaction::{next_state, Interaction};
+use crate::habbohotel::items::Item;
+
+/// Furniture that only switches between its states, like lamps
+pub struct DefaultInteraction;
+
+impl Interaction for DefaultInteraction {
+    fn get_name(&self) -> &'static str {
+        "default"
+    }
+
+    fn on_use(&self, item: &Item, state: &str) -> Option<String> {
+        next_state(state, item.get_interaction_modes_count())
+    }
+}
@@ -0,0 +1,29 @@
+use rand::{thread_rng, Rng};
+
+use crate::habbohotel::items::interactions::Interaction;
+use crate::habbohotel::items::Item;
+
+/// State of a die while it rolls
+pub const ROLLING: &str = "-1";
+
+/// A die. Using it starts a roll, the room picks the result with `roll` when it stops.
+pub struct DiceInteraction;
+
+impl DiceInteraction {
+    pub fn roll() -> String {
+        thread_rng().gen_range(1..=6).to_string()
+    }
+}
+
+impl Interaction for DiceInteraction {
+    fn get_name(&self) -> &'static str {
+        "dice"
+    }
+
+    fn on_use(&self, _item: &Item, state: &str) -> Option<String> {
+        match state {
+            ROLLING => None,
+            _ => Some(ROLLING.to_string()),
+        }
+    }
+}
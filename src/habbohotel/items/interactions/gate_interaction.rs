@@ -0,0 +1,19 @@
+use crate::habbohotel::items::interactions::interaction::{next_state, Interaction};
+use crate::habbohotel::items::Item;
+
+/// A gate that can be walked through while it is open
+pub struct GateInteraction;
+
+impl Interaction for GateInteraction {
+    fn get_name(&self) -> &'static str {
+        "gate"
+    }
+
+    fn can_walk_on(&self, _item: &Item, state: &str) -> bool {
+        state == "1"
+    }
+
+    fn on_use(&self, item: &Item, state: &str) -> Option<String> {
+        next_state(state, item.get_interaction_modes_count().max(2))
+    }
+}
@@ -0,0 +1,33 @@
+use crate::habbohotel::items::Item;
+
+/// What furniture does, picked by `items_base.interaction_type`. The state is the extra data
+/// of the item, like `1` for an open gate.
+pub trait Interaction: Send + Sync {
+    /// The interaction type in `items_base`
+    fn get_name(&self) -> &'static str;
+
+    /// Whether users can walk onto the item in the given state
+    fn can_walk_on(&self, item: &Item, _state: &str) -> bool {
+        item.allows_walk() || item.allows_sit() || item.allows_lay()
+    }
+
+    /// Height of the item in the given state, what stands on top of it starts there
+    fn get_stack_height(&self, item: &Item, _state: &str) -> f64 {
+        item.get_stack_height()
+    }
+
+    /// The state the item switches to when a user uses it, None when it doesn't react
+    fn on_use(&self, _item: &Item, _state: &str) -> Option<String> {
+        None
+    }
+}
+
+/// The state after the given one, for items that cycle through `modes` states
+pub fn next_state(state: &str, modes: i32) -> Option<String> {
+    if modes <= 1 {
+        return None;
+    }
+
+    let current = state.parse::<i32>().unwrap_or(0).clamp(0, modes - 1);
+    Some(((current + 1) % modes).to_string())
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::habbohotel::items::interactions::{
+    DefaultInteraction, DiceInteraction, GateInteraction, Interaction, MultiheightInteraction,
+    OneWayGateInteraction, RollerInteraction, TeleportInteraction, VendingMachineInteraction,
+};
+
+/// Every interaction by the name `items_base.interaction_type` uses
+pub struct InteractionRegistry {
+    interactions: BTreeMap<&'static str, Arc<dyn Interaction>>,
+    default: Arc<dyn Interaction>,
+}
+
+impl InteractionRegistry {
+    pub fn new() -> Self {
+        let default: Arc<dyn Interaction> = Arc::new(DefaultInteraction);
+        let mut registry = Self {
+            interactions: BTreeMap::new(),
+            default: default.clone(),
+        };
+
+        registry.register(default);
+        registry.register(Arc::new(GateInteraction));
+        registry.register(Arc::new(DiceInteraction));
+        registry.register(Arc::new(TeleportInteraction));
+        registry.register(Arc::new(RollerInteraction));
+        registry.register(Arc::new(OneWayGateInteraction));
+        registry.register(Arc::new(VendingMachineInteraction));
+        registry.register(Arc::new(MultiheightInteraction));
+
+        registry
+    }
+
+    /// Adds an interaction, replacing one with the same name
+    pub fn register(&mut self, interaction: Arc<dyn Interaction>) {
+        self.interactions.insert(interaction.get_name(), interaction);
+    }
+
+    /// The interaction with a name, names are case insensitive
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Interaction>> {
+        self.interactions.get(name.to_lowercase().as_str()).cloned()
+    }
+
+    /// What furniture with an unknown interaction gets
+    pub fn get_default(&self) -> Arc<dyn Interaction> {
+        self.default.clone()
+    }
+
+    pub fn get_names(&self) -> Vec<&'static str> {
+        self.interactions.keys().copied().collect()
+    }
+}
+
+impl Default for InteractionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,24 @@
+// The trait and the registry
+pub mod interaction;
+pub mod interaction_registry;
+
+// Interactions
+pub mod default_interaction;
+pub mod dice_interaction;
+pub mod gate_interaction;
+pub mod multiheight_interaction;
+pub mod one_way_gate_interaction;
+pub mod roller_interaction;
+pub mod teleport_interaction;
+pub mod vending_machine_interaction;
+
+pub use default_interaction::DefaultInteraction;
+pub use dice_interaction::DiceInteraction;
+pub use gate_interaction::GateInteraction;
+pub use interaction::Interaction;
+pub use interaction_registry::InteractionRegistry;
+pub use multiheight_interaction::MultiheightInteraction;
+pub use one_way_gate_interaction::OneWayGateInteraction;
+pub use roller_interaction::RollerInteraction;
+pub use teleport_interaction::TeleportInteraction;
+pub use vending_machine_interaction::VendingMachineInteraction;
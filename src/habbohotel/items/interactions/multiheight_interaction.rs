@@ -0,0 +1,22 @@
+use crate::habbohotel::items::interactions::interaction::{next_state, Interaction};
+use crate::habbohotel::items::Item;
+
+/// Furniture that changes height with its state, like adjustable tables. The heights come
+/// from `items_base.multiheight`.
+pub struct MultiheightInteraction;
+
+impl Interaction for MultiheightInteraction {
+    fn get_name(&self) -> &'static str {
+        "multiheight"
+    }
+
+    fn get_stack_height(&self, item: &Item, state: &str) -> f64 {
+        state.parse::<usize>().ok()
+            .and_then(|state| item.get_multiheights().get(state).copied())
+            .unwrap_or(item.get_stack_height())
+    }
+
+    fn on_use(&self, item: &Item, state: &str) -> Option<String> {
+        next_state(state, item.get_multiheights().len() as i32)
+    }
+}
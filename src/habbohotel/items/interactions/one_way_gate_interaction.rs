@@ -0,0 +1,23 @@
+use crate::habbohotel::items::interactions::Interaction;
+use crate::habbohotel::items::Item;
+
+/// A gate users pass in one direction only. It can't be walked onto, using it from the front
+/// opens it and the room walks the user through.
+pub struct OneWayGateInteraction;
+
+impl Interaction for OneWayGateInteraction {
+    fn get_name(&self) -> &'static str {
+        "onewaygate"
+    }
+
+    fn can_walk_on(&self, _item: &Item, _state: &str) -> bool {
+        false
+    }
+
+    fn on_use(&self, _item: &Item, state: &str) -> Option<String> {
+        match state {
+            "1" => None,
+            _ => Some("1".to_string()),
+        }
+    }
+}
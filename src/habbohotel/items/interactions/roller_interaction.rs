@@ -0,0 +1,15 @@
+use crate::habbohotel::items::interactions::Interaction;
+use crate::habbohotel::items::Item;
+
+/// A roller, moves what stands on it one tile every room cycle
+pub struct RollerInteraction;
+
+impl Interaction for RollerInteraction {
+    fn get_name(&self) -> &'static str {
+        "roller"
+    }
+
+    fn can_walk_on(&self, _item: &Item, _state: &str) -> bool {
+        true
+    }
+}
@@ -0,0 +1,19 @@
+use crate::habbohotel::items::interactions::Interaction;
+use crate::habbohotel::items::Item;
+
+/// A teleport, linked to another one. Its door opens for the user stepping in, the room
+/// moves them to the other side.
+pub struct TeleportInteraction;
+
+impl Interaction for TeleportInteraction {
+    fn get_name(&self) -> &'static str {
+        "teleport"
+    }
+
+    fn on_use(&self, _item: &Item, state: &str) -> Option<String> {
+        match state {
+            "1" => None,
+            _ => Some("1".to_string()),
+        }
+    }
+}
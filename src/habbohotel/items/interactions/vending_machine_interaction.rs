@@ -0,0 +1,28 @@
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::habbohotel::items::interactions::Interaction;
+use crate::habbohotel::items::Item;
+
+/// Hands out one of its `vending_ids` as a hand item
+pub struct VendingMachineInteraction;
+
+impl VendingMachineInteraction {
+    /// The hand item to give, None when the machine has nothing set up
+    pub fn pick_hand_item(item: &Item) -> Option<i32> {
+        item.get_vending_ids().choose(&mut thread_rng()).copied()
+    }
+}
+
+impl Interaction for VendingMachineInteraction {
+    fn get_name(&self) -> &'static str {
+        "vendingmachine"
+    }
+
+    fn on_use(&self, item: &Item, state: &str) -> Option<String> {
+        match state == "1" || item.get_vending_ids().is_empty() {
+            true => None,
+            false => Some("1".to_string()),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::database::repositories::ItemDefinitionRecord;
+use crate::habbohotel::items::Interaction;
+
+/// What a piece of furniture is, a row of `items_base` with its interaction
+pub struct Item {
+    id: i32,
+    sprite_id: i32,
+    name: String,
+    public_name: String,
+    item_type: String,
+    width: i32,
+    length: i32,
+    stack_height: f64,
+    allow_stack: bool,
+    allow_sit: bool,
+    allow_lay: bool,
+    allow_walk: bool,
+    interaction_modes_count: i32,
+    vending_ids: Vec<i32>,
+    multiheights: Vec<f64>,
+    interaction: Arc<dyn Interaction>,
+}
+
+impl Item {
+    pub fn new(record: ItemDefinitionRecord, interaction: Arc<dyn Interaction>) -> Self {
+        let vending_ids = record.vending_ids.split([',', ';'])
+            .filter_map(|id| id.trim().parse().ok())
+            .filter(|id| *id > 0)
+            .collect();
+
+        let multiheights = match record.multiheight.contains(';') {
+            true => record.multiheight.split(';').filter_map(|height| height.trim().parse().ok()).collect(),
+            false => Vec::new(),
+        };
+
+        Item {
+            id: record.id,
+            sprite_id: record.sprite_id,
+            name: record.item_name,
+            public_name: record.public_name,
+            item_type: record.item_type,
+            width: record.width,
+            length: record.length,
+            stack_height: record.stack_height,
+            allow_stack: record.allow_stack,
+            allow_sit: record.allow_sit,
+            allow_lay: record.allow_lay,
+            allow_walk: record.allow_walk,
+            interaction_modes_count: record.interaction_modes_count,
+            vending_ids,
+            multiheights,
+            interaction,
+        }
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_sprite_id(&self) -> i32 {
+        self.sprite_id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_public_name(&self) -> &str {
+        &self.public_name
+    }
+
+    /// `s` for floor items, `i` for wall items
+    pub fn get_item_type(&self) -> &str {
+        &self.item_type
+    }
+
+    pub fn is_wall_item(&self) -> bool {
+        self.item_type == "i"
+    }
+
+    pub fn get_width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn get_length(&self) -> i32 {
+        self.length
+    }
+
+    pub fn get_stack_height(&self) -> f64 {
+        self.stack_height
+    }
+
+    pub fn allows_stack(&self) -> bool {
+        self.allow_stack
+    }
+
+    pub fn allows_sit(&self) -> bool {
+        self.allow_sit
+    }
+
+    pub fn allows_lay(&self) -> bool {
+        self.allow_lay
+    }
+
+    pub fn allows_walk(&self) -> bool {
+        self.allow_walk
+    }
+
+    pub fn get_interaction_modes_count(&self) -> i32 {
+        self.interaction_modes_count
+    }
+
+    pub fn get_vending_ids(&self) -> &[i32] {
+        &self.vending_ids
+    }
+
+    /// Heights by state, empty unless the item is multiheight
+    pub fn get_multiheights(&self) -> &[f64] {
+        &self.multiheights
+    }
+
+    pub fn get_interaction(&self) -> &Arc<dyn Interaction> {
+        &self.interaction
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use log::{info, warn};
+
+use crate::database::repositories::Repositories;
+use crate::habbohotel::items::{InteractionRegistry, Item};
+
+/// Furniture definitions from `items_base`, each with the interaction it names
+pub struct ItemManager {
+    repositories: Repositories,
+    interactions: InteractionRegistry,
+    items: RwLock<HashMap<i32, Arc<Item>>>,
+}
+
+impl ItemManager {
+    pub fn new(repositories: Repositories) -> Self {
+        ItemManager {
+            repositories,
+            interactions: InteractionRegistry::new(),
+            items: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Loads `items_base`. Items with an interaction that isn't registered get the default
+    /// one, every unknown name is warned about once.
+    pub async fn load_items(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let records = self.repositories.get_items().get_item_definitions().await?;
+        let mut items = HashMap::with_capacity(records.len());
+        let mut unknown: BTreeMap<String, usize> = BTreeMap::new();
+
+        for record in records {
+            let interaction = match self.interactions.get(&record.interaction_type) {
+                Some(interaction) => interaction,
+                None => {
+                    *unknown.entry(record.interaction_type.clone()).or_default() += 1;
+                    self.interactions.get_default()
+                }
+            };
+
+            items.insert(record.id, Arc::new(Item::new(record, interaction)));
+        }
+
+        for (name, count) in unknown {
+            warn!("Unknown interaction '{}' used by {} items, they get the default interaction", name, count);
+        }
+
+        info!("Loaded {} item definitions", items.len());
+        *self.items.write().unwrap() = items;
+
+        Ok(())
+    }
+
+    pub fn get_item(&self, id: i32) -> Option<Arc<Item>> {
+        self.items.read().unwrap().get(&id).cloned()
+    }
+
+    pub fn get_item_by_name(&self, name: &str) -> Option<Arc<Item>> {
+        self.items.read().unwrap().values().find(|item| item.get_name() == name).cloned()
+    }
+
+    pub fn get_item_count(&self) -> usize {
+        self.items.read().unwrap().len()
+    }
+
+    pub fn get_interactions(&self) -> &InteractionRegistry {
+        &self.interactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::{InMemoryItemRepository, InMemoryRoomRepository, InMemorySettingsRepository, InMemoryUserRepository, ItemDefinitionRecord};
+    use crate::habbohotel::items::interactions::dice_interaction::ROLLING;
+
+    fn definition(id: i32, interaction_type: &str) -> ItemDefinitionRecord {
+        ItemDefinitionRecord {
+            id,
+            sprite_id: id,
+            item_name: format!("item_{}", id),
+            public_name: String::new(),
+            width: 1,
+            length: 1,
+            stack_height: 1.0,
+            allow_stack: true,
+            allow_sit: false,
+            allow_lay: false,
+            allow_walk: false,
+            item_type: "s".to_string(),
+            interaction_type: interaction_type.to_string(),
+            interaction_modes_count: 2,
+            vending_ids: "0".to_string(),
+            multiheight: "0".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_items() {
+        let items = Arc::new(InMemoryItemRepository::new());
+        items.insert_item_definition(definition(1, "default"));
+        items.insert_item_definition(definition(2, "Gate"));
+        items.insert_item_definition(definition(3, "dice"));
+        items.insert_item_definition(ItemDefinitionRecord { multiheight: "0.5;1;1.5".to_string(), ..definition(4, "multiheight") });
+        items.insert_item_definition(ItemDefinitionRecord { vending_ids: "2,4".to_string(), ..definition(5, "vendingmachine") });
+        items.insert_item_definition(definition(6, "wired_something"));
+
+        let manager = ItemManager::new(Repositories::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryRoomRepository::new()),
+            items,
+            Arc::new(InMemorySettingsRepository::new()),
+        ));
+        manager.load_items().await.unwrap();
+        assert_eq!(manager.get_item_count(), 6);
+
+        let lamp = manager.get_item(1).unwrap();
+        assert_eq!(lamp.get_interaction().on_use(&lamp, "1").as_deref(), Some("0"));
+
+        let gate = manager.get_item_by_name("item_2").unwrap();
+        assert_eq!(gate.get_interaction().get_name(), "gate");
+        assert!(!gate.get_interaction().can_walk_on(&gate, "0"));
+        assert!(gate.get_interaction().can_walk_on(&gate, "1"));
+
+        let dice = manager.get_item(3).unwrap();
+        assert_eq!(dice.get_interaction().on_use(&dice, "4").as_deref(), Some(ROLLING));
+        assert!(dice.get_interaction().on_use(&dice, ROLLING).is_none());
+
+        let table = manager.get_item(4).unwrap();
+        assert_eq!(table.get_interaction().get_stack_height(&table, "2"), 1.5);
+        assert_eq!(table.get_interaction().on_use(&table, "2").as_deref(), Some("0"));
+
+        let vending = manager.get_item(5).unwrap();
+        assert_eq!(vending.get_vending_ids(), [2, 4]);
+
+        // Unknown interactions fall back to the default one
+        assert_eq!(manager.get_item(6).unwrap().get_interaction().get_name(), "default");
+    }
+}
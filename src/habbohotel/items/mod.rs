@@ -0,0 +1,9 @@
+//! Furniture definitions from `items_base` and the interactions that make them do things
+
+pub mod interactions;
+pub mod item;
+pub mod item_manager;
+
+pub use interactions::{Interaction, InteractionRegistry};
+pub use item::Item;
+pub use item_manager::ItemManager;
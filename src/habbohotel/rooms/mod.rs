@@ -0,0 +1,13 @@
+//! Rooms, their layouts and the `RoomManager` that loads them
+
+pub mod room;
+pub mod room_layout;
+pub mod room_manager;
+pub mod room_tile;
+pub mod room_unit;
+
+pub use room::Room;
+pub use room_layout::RoomLayout;
+pub use room_manager::RoomManager;
+pub use room_tile::{RoomTile, RoomTileState};
+pub use room_unit::{RoomUnit, RoomUnitStatus};
@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+use crate::database::repositories::RoomRecord;
+use crate::habbohotel::gameclients::{GameClient, GameClientManager};
+use crate::habbohotel::rooms::{RoomLayout, RoomUnit, RoomUnitStatus};
+use crate::habbohotel::users::Habbo;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::rooms::users::{RoomUserRemoveComposer, RoomUsersComposer, UserUpdateComposer};
+use crate::messages::server_message::ServerMessage;
+use crate::util::pathfinding::{Pathfinder, Rotation, WalkOptions};
+
+/// Time between two room cycles
+pub const ROOM_TICK: Duration = Duration::from_millis(500);
+
+/// A room that is loaded, with the layout it was built on and the users inside
+pub struct Room {
+    data: RoomRecord,
+    layout: RwLock<RoomLayout>,
+    pathfinder: Pathfinder,
+    units: Mutex<BTreeMap<i32, RoomUnit>>,
+    next_unit_id: AtomicI32,
+    unloaded: AtomicBool,
+    stopped: CancellationToken,
+}
+
+impl Room {
+    pub fn new(data: RoomRecord, layout: RoomLayout, pathfinder: Pathfinder) -> Self {
+        Room {
+            data,
+            layout: RwLock::new(layout),
+            pathfinder,
+            units: Mutex::new(BTreeMap::new()),
+            next_unit_id: AtomicI32::new(1),
+            unloaded: AtomicBool::new(false),
+            stopped: CancellationToken::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.data.id
+    }
+
+    pub fn get_data(&self) -> &RoomRecord {
+        &self.data
+    }
+
+    pub fn get_layout(&self) -> RwLockReadGuard<'_, RoomLayout> {
+        self.layout.read().unwrap()
+    }
+
+    pub fn get_layout_mut(&self) -> RwLockWriteGuard<'_, RoomLayout> {
+        self.layout.write().unwrap()
+    }
+
+    pub fn get_user_count(&self) -> usize {
+        self.units.lock().unwrap().len()
+    }
+
+    /// The unit of a user in the room
+    pub fn get_unit_id(&self, user_id: i32) -> Option<i32> {
+        self.units.lock().unwrap().values()
+            .find(|unit| unit.get_habbo().get_id() == user_id)
+            .map(|unit| unit.get_id())
+    }
+
+    /// Runs something on the unit of a user, like holding up a sign
+    pub fn with_unit<T>(&self, user_id: i32, f: impl FnOnce(&mut RoomUnit) -> T) -> Option<T> {
+        self.units.lock().unwrap().values_mut()
+            .find(|unit| unit.get_habbo().get_id() == user_id)
+            .map(f)
+    }
+
+    /// Puts a user in the door of the room. They get everyone in the room, everyone else gets
+    /// them. Fails once the room is unloaded.
+    pub fn add_habbo(&self, habbo: Arc<Habbo>) -> Option<i32> {
+        // The layout is locked before the units everywhere else
+        let (door, rotation) = {
+            let layout = self.get_layout();
+            (*layout.get_door_tile(), Rotation::from_value(layout.get_door_dir()).unwrap_or(Rotation::East))
+        };
+
+        let mut units = self.units.lock().unwrap();
+        if self.is_unloaded() {
+            return None;
+        }
+
+        let unit_id = self.next_unit_id.fetch_add(1, Ordering::Relaxed);
+        let unit = RoomUnit::new(unit_id, habbo.clone(), &door, rotation);
+
+        let others = Self::get_clients_of(&units);
+        GameClientManager::broadcast_to(&others, RoomUsersComposer::new(&[&unit]).compose());
+        GameClientManager::broadcast_to(&others, UserUpdateComposer::new(&[&unit]).compose());
+
+        units.insert(unit_id, unit);
+
+        let all: Vec<&RoomUnit> = units.values().collect();
+        habbo.get_client().send_response(RoomUsersComposer::new(&all).compose());
+        habbo.get_client().send_response(UserUpdateComposer::new(&all).compose());
+
+        Some(unit_id)
+    }
+
+    /// Takes a user out of the room and tells everyone else
+    pub fn remove_habbo(&self, user_id: i32) -> bool {
+        let mut units = self.units.lock().unwrap();
+
+        let unit_id = match units.values().find(|unit| unit.get_habbo().get_id() == user_id) {
+            Some(unit) => unit.get_id(),
+            None => return false,
+        };
+        units.remove(&unit_id);
+
+        GameClientManager::broadcast_to(&Self::get_clients_of(&units), RoomUserRemoveComposer::new(unit_id).compose());
+        true
+    }
+
+    /// Sends a user walking to a tile, returns false when they can't get there
+    pub fn walk_to(&self, user_id: i32, x: i16, y: i16) -> bool {
+        let layout = self.get_layout();
+        let mut units = self.units.lock().unwrap();
+        let positions = Self::get_positions(&units);
+
+        let unit = match units.values_mut().find(|unit| unit.get_habbo().get_id() == user_id) {
+            Some(unit) => unit,
+            None => return false,
+        };
+
+        let unit_id = unit.get_id();
+        let occupied = |tile_x, tile_y| positions.iter().any(|(id, position)| *id != unit_id && *position == (tile_x, tile_y));
+        let walk = self.get_walk_options(unit.get_habbo());
+
+        match self.pathfinder.find_path(&layout, (unit.get_x(), unit.get_y()), (x, y), walk, occupied) {
+            Some(path) if !path.is_empty() => {
+                unit.set_path((x, y), path);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// One tick: walking units take a step, units that arrived sit or lay down and timed
+    /// statuses wear off. Every unit that changed goes out in a single `UserUpdateComposer`.
+    pub fn cycle(&self) {
+        let layout = self.get_layout();
+        let mut units = self.units.lock().unwrap();
+        let mut positions = Self::get_positions(&units);
+
+        for unit in units.values_mut() {
+            unit.cycle_statuses();
+
+            let unit_id = unit.get_id();
+            let walk = self.get_walk_options(unit.get_habbo());
+            let is_occupied = |positions: &Vec<(i32, (i16, i16))>, x, y| {
+                !walk.walk_through_users && !walk.free_walk
+                    && positions.iter().any(|(id, position)| *id != unit_id && *position == (x, y))
+            };
+
+            if let Some(next) = unit.peek_step().copied() {
+                let tile = layout.get_tile(next.x as i32, next.y as i32).copied();
+                let blocked = is_occupied(&positions, next.x, next.y)
+                    || tile.is_none_or(|tile| !tile.is_walkable() && !walk.free_walk);
+
+                // Something got in the way since the path was found, go round it
+                if blocked {
+                    let path = unit.get_goal().and_then(|goal| {
+                        self.pathfinder.find_path(&layout, (unit.get_x(), unit.get_y()), goal, walk, |x, y| is_occupied(&positions, x, y))
+                    });
+
+                    match (unit.get_goal(), path) {
+                        (Some(goal), Some(path)) if !path.is_empty() => unit.set_path(goal, path),
+                        _ => unit.stop_walking(),
+                    }
+                }
+            }
+
+            if unit.is_walking() {
+                unit.step();
+                if let Some(position) = positions.iter_mut().find(|(id, _)| *id == unit_id) {
+                    position.1 = (unit.get_x(), unit.get_y());
+                }
+            } else if unit.has_status(RoomUnitStatus::Move)
+                && let Some(tile) = layout.get_tile(unit.get_x() as i32, unit.get_y() as i32)
+            {
+                unit.arrive(tile);
+            }
+        }
+
+        let updated_ids: Vec<i32> = units.values_mut()
+            .filter_map(|unit| unit.take_update().then(|| unit.get_id()))
+            .collect();
+        let updated: Vec<&RoomUnit> = updated_ids.iter().filter_map(|id| units.get(id)).collect();
+
+        if !updated.is_empty() {
+            let message = UserUpdateComposer::new(&updated).compose();
+            GameClientManager::broadcast_to(&Self::get_clients_of(&units), message);
+        }
+    }
+
+    /// Cycles the room every `ROOM_TICK` until it is stopped, or until it was empty for
+    /// `unload_after`. Returns whether it stopped because it was idle.
+    pub async fn run(&self, unload_after: Duration) -> bool {
+        let mut interval = tokio::time::interval(ROOM_TICK);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut idle_since = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = self.stopped.cancelled() => return false,
+                _ = interval.tick() => {}
+            }
+
+            if self.get_user_count() > 0 {
+                self.cycle();
+                idle_since = Instant::now();
+            } else if idle_since.elapsed() >= unload_after {
+                return true;
+            }
+        }
+    }
+
+    /// Marks the room unloaded if nobody is inside, after that no one can enter it
+    pub fn try_unload(&self) -> bool {
+        let units = self.units.lock().unwrap();
+        if !units.is_empty() {
+            return false;
+        }
+
+        self.unload();
+        true
+    }
+
+    /// Marks the room unloaded and stops its cycle, whoever is inside stays until they leave
+    pub fn unload(&self) {
+        self.unloaded.store(true, Ordering::Relaxed);
+        self.stop();
+    }
+
+    pub fn is_unloaded(&self) -> bool {
+        self.unloaded.load(Ordering::Relaxed)
+    }
+
+    /// Stops the room cycle
+    pub fn stop(&self) {
+        self.stopped.cancel();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.is_cancelled()
+    }
+
+    /// Sends a message to everyone in the room
+    pub fn send_composer(&self, message: ServerMessage) {
+        let clients = Self::get_clients_of(&self.units.lock().unwrap());
+        GameClientManager::broadcast_to(&clients, message);
+    }
+
+    /// Staff of a high enough rank walk freely, everyone walks through others if the room allows it
+    fn get_walk_options(&self, habbo: &Habbo) -> WalkOptions {
+        WalkOptions {
+            walk_through_users: self.data.allow_walkthrough,
+            free_walk: habbo.get_info().rank >= self.pathfinder.get_options().free_walk_rank,
+        }
+    }
+
+    fn get_clients_of(units: &BTreeMap<i32, RoomUnit>) -> Vec<Arc<GameClient>> {
+        units.values().map(|unit| unit.get_habbo().get_client().clone()).collect()
+    }
+
+    fn get_positions(units: &BTreeMap<i32, RoomUnit>) -> Vec<(i32, (i16, i16))> {
+        units.values().map(|unit| (unit.get_id(), (unit.get_x(), unit.get_y()))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::habbohotel::rooms::RoomTileState;
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::util::fixtures::{self, habbo, headers, user};
+    use crate::util::pathfinding::PathfinderOptions;
+
+    fn room(heightmap: &str) -> Room {
+        let layout = RoomLayout::parse("model_test", 0, 0, 2, heightmap).unwrap();
+        Room::new(fixtures::room(1, "model_test"), layout, Pathfinder::new(PathfinderOptions { free_walk_rank: 7, ..PathfinderOptions::default() }))
+    }
+
+    #[test]
+    fn test_walk_and_sit() {
+        let room = room("000\r\n000");
+        room.get_layout_mut().get_tile_mut(2, 0).unwrap().state = RoomTileState::Sit;
+        let (first, mut first_receiver) = habbo(user(1));
+        let (second, mut second_receiver) = habbo(user(2));
+        room.add_habbo(first.clone()).unwrap();
+        room.add_habbo(second.clone()).unwrap();
+
+        // The second user got everyone in the room, the first one got the second
+        assert_eq!(headers(&mut second_receiver), [Outgoing::RoomUsersComposer.get_header(), Outgoing::UserUpdateComposer.get_header()]);
+        assert_eq!(headers(&mut first_receiver).len(), 4);
+
+        // The door is taken by the second user, but standing on it isn't walking into it
+        assert!(room.walk_to(1, 2, 0));
+        room.cycle();
+        room.with_unit(1, |unit| {
+            assert_eq!((unit.get_x(), unit.get_y()), (1, 0));
+            assert_eq!(unit.get_status_string(), "/mv 1,0,0/");
+            assert_eq!(unit.get_body_rotation(), Rotation::East);
+
+            // The client gets the tile the step starts from, after the count and the unit id
+            let update = UserUpdateComposer::new(&[unit]).compose();
+            assert_eq!(update.get_body()[8..16], [0, 0, 0, 0, 0, 0, 0, 0]);
+        });
+
+        room.cycle();
+        room.cycle();
+        room.with_unit(1, |unit| {
+            assert_eq!((unit.get_x(), unit.get_y()), (2, 0));
+            assert_eq!(unit.get_status_string(), "/sit 0/");
+        });
+
+        // One UserUpdate per tick the unit changed in, nothing when nobody did
+        room.cycle();
+        assert_eq!(headers(&mut second_receiver), [Outgoing::UserUpdateComposer.get_header(); 3]);
+        assert!(!room.walk_to(2, 2, 0));
+    }
+
+    #[test]
+    fn test_staff_free_walk() {
+        let room = room("000");
+        room.get_layout_mut().get_tile_mut(1, 0).unwrap().state = RoomTileState::Blocked;
+        let (guest, _guest_receiver) = habbo(user(1));
+        let (staff, _staff_receiver) = habbo(user(2));
+        staff.get_info_mut().rank = 7;
+        room.add_habbo(guest).unwrap();
+        room.add_habbo(staff).unwrap();
+
+        assert!(!room.walk_to(1, 2, 0));
+        assert!(room.walk_to(2, 2, 0));
+    }
+
+    #[test]
+    fn test_timed_status_and_leave() {
+        let room = room("00");
+        let (first, _first_receiver) = habbo(user(1));
+        let (second, mut second_receiver) = habbo(user(2));
+        room.add_habbo(first).unwrap();
+        room.add_habbo(second).unwrap();
+        headers(&mut second_receiver);
+
+        room.with_unit(1, |unit| unit.set_timed_status(RoomUnitStatus::Sign, "7", Some(2)));
+        room.cycle();
+        assert!(room.with_unit(1, |unit| unit.has_status(RoomUnitStatus::Sign)).unwrap());
+        room.cycle();
+        assert!(!room.with_unit(1, |unit| unit.has_status(RoomUnitStatus::Sign)).unwrap());
+
+        assert!(room.remove_habbo(1));
+        assert!(!room.try_unload());
+        assert!(room.remove_habbo(2));
+        assert!(room.try_unload() && room.is_stopped());
+        assert_eq!(headers(&mut second_receiver), [
+            Outgoing::UserUpdateComposer.get_header(),
+            Outgoing::UserUpdateComposer.get_header(),
+            Outgoing::RoomUserRemoveComposer.get_header(),
+        ]);
+
+        let (third, _third_receiver) = habbo(user(3));
+        assert!(room.add_habbo(third).is_none());
+    }
+}
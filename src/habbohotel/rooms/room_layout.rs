@@ -0,0 +1,172 @@
+use crate::database::repositories::RoomModelRecord;
+use crate::habbohotel::rooms::{RoomTile, RoomTileState};
+
+/// Tiles of a room, built from the heightmap of its model. Every character of the heightmap
+/// is a tile: `x` is no tile, `0`-`9` and `a`-`z` are heights 0 to 35.
+#[derive(Debug, Clone)]
+pub struct RoomLayout {
+    name: String,
+    door_x: i16,
+    door_y: i16,
+    door_dir: i32,
+    map_size_x: i16,
+    map_size_y: i16,
+    tiles: Vec<RoomTile>,
+}
+
+impl RoomLayout {
+    /// Parses a heightmap with rows on separate lines, `\r\n`, `\n` and `\r` all work.
+    /// Short rows are filled up with `x`.
+    pub fn parse(name: &str, door_x: i32, door_y: i32, door_dir: i32, heightmap: &str) -> Result<Self, String> {
+        let rows: Vec<&str> = heightmap
+            .split(['\r', '\n'])
+            .map(|row| row.trim())
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        let map_size_x = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        if rows.is_empty() || map_size_x == 0 {
+            return Err(format!("The heightmap of {} is empty", name));
+        }
+        if rows.len() > i16::MAX as usize || map_size_x > i16::MAX as usize {
+            return Err(format!("The heightmap of {} is too big", name));
+        }
+
+        let mut tiles = Vec::with_capacity(rows.len() * map_size_x);
+        for (y, row) in rows.iter().enumerate() {
+            let mut squares = row.chars();
+
+            for x in 0..map_size_x {
+                let square = squares.next().unwrap_or('x');
+                let tile = match square {
+                    'x' | 'X' => RoomTile::new(x as i16, y as i16, 0, RoomTileState::Invalid),
+                    _ => {
+                        let z = square.to_digit(36)
+                            .ok_or_else(|| format!("The heightmap of {} has an invalid square '{}' at {},{}", name, square, x, y))?;
+                        RoomTile::new(x as i16, y as i16, z as i16, RoomTileState::Open)
+                    }
+                };
+                tiles.push(tile);
+            }
+        }
+
+        let layout = RoomLayout {
+            name: name.to_string(),
+            door_x: door_x as i16,
+            door_y: door_y as i16,
+            door_dir,
+            map_size_x: map_size_x as i16,
+            map_size_y: rows.len() as i16,
+            tiles,
+        };
+
+        match layout.get_tile(door_x, door_y) {
+            Some(tile) if tile.state != RoomTileState::Invalid => Ok(layout),
+            _ => Err(format!("The door of {} at {},{} isn't on the heightmap", name, door_x, door_y)),
+        }
+    }
+
+    pub fn from_record(model: &RoomModelRecord) -> Result<Self, String> {
+        Self::parse(&model.name, model.door_x, model.door_y, model.door_dir, &model.heightmap)
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_door_x(&self) -> i16 {
+        self.door_x
+    }
+
+    pub fn get_door_y(&self) -> i16 {
+        self.door_y
+    }
+
+    pub fn get_door_dir(&self) -> i32 {
+        self.door_dir
+    }
+
+    pub fn get_door_tile(&self) -> &RoomTile {
+        // Checked when the layout was parsed
+        self.get_tile(self.door_x as i32, self.door_y as i32).unwrap()
+    }
+
+    pub fn get_map_size_x(&self) -> i16 {
+        self.map_size_x
+    }
+
+    pub fn get_map_size_y(&self) -> i16 {
+        self.map_size_y
+    }
+
+    /// The tile at a position, None outside the heightmap
+    pub fn get_tile(&self, x: i32, y: i32) -> Option<&RoomTile> {
+        self.get_index(x, y).map(|index| &self.tiles[index])
+    }
+
+    pub fn get_tile_mut(&mut self, x: i32, y: i32) -> Option<&mut RoomTile> {
+        self.get_index(x, y).map(|index| &mut self.tiles[index])
+    }
+
+    /// Every tile, row by row
+    pub fn get_tiles(&self) -> &[RoomTile] {
+        &self.tiles
+    }
+
+    fn get_index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.map_size_x as i32 || y >= self.map_size_y as i32 {
+            return None;
+        }
+
+        Some(y as usize * self.map_size_x as usize + x as usize)
+    }
+
+    /// The heightmap the way `FloorHeightMapComposer` sends it, rows end with `\r`
+    pub fn get_relative_map(&self) -> String {
+        let mut map = String::with_capacity(self.tiles.len() + self.map_size_y as usize);
+
+        for row in self.tiles.chunks(self.map_size_x as usize) {
+            for tile in row {
+                match tile.state {
+                    RoomTileState::Invalid => map.push('x'),
+                    _ => map.push(std::char::from_digit(tile.z as u32, 36).unwrap_or('0')),
+                }
+            }
+            map.push('\r');
+        }
+
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heightmap() {
+        let layout = RoomLayout::parse("model_test", 0, 1, 2, "xxxx\r\n09az\r\n1x\r\n").unwrap();
+
+        assert_eq!((layout.get_map_size_x(), layout.get_map_size_y()), (4, 3));
+        assert_eq!(layout.get_tile(0, 0).unwrap().state, RoomTileState::Invalid);
+        assert_eq!(layout.get_tile(1, 1).unwrap().z, 9);
+        assert_eq!(layout.get_tile(2, 1).unwrap().z, 10);
+        assert_eq!(layout.get_tile(3, 1).unwrap().z, 35);
+        assert!(layout.get_tile(4, 1).is_none());
+        assert_eq!(layout.get_door_tile().z, 0);
+
+        // The short row was filled up
+        assert_eq!(layout.get_tile(3, 2).unwrap().state, RoomTileState::Invalid);
+        assert_eq!(layout.get_relative_map(), "xxxx\r09az\r1xxx\r");
+        assert_eq!(layout.get_tile(1, 1).unwrap().get_relative_height(), 9 * 256);
+        assert_eq!(layout.get_tile(0, 0).unwrap().get_relative_height(), 1 << 14);
+    }
+
+    #[test]
+    fn test_invalid_heightmaps() {
+        assert!(RoomLayout::parse("empty", 0, 0, 2, "\n\n").is_err());
+        assert!(RoomLayout::parse("square", 0, 0, 2, "0?\n00").is_err());
+        assert!(RoomLayout::parse("door", 0, 0, 2, "x0\n00").is_err());
+        assert!(RoomLayout::parse("door", 5, 0, 2, "00\n00").is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use log::{debug, error, info, warn};
+
+use crate::core::configuration_manager::ConfigurationManager;
+use crate::database::repositories::Repositories;
+use crate::habbohotel::rooms::{Room, RoomLayout};
+use crate::habbohotel::users::Habbo;
+use crate::util::pathfinding::{Pathfinder, PathfinderOptions};
+
+type LoadedRooms = Arc<RwLock<HashMap<i32, Arc<Room>>>>;
+
+/// Keeps the room models and the rooms that are loaded. Models are parsed once at startup,
+/// every room gets its own copy of the layout since furniture changes it. A loaded room
+/// cycles on its own task until it was empty for `rooms.unload_after`.
+pub struct RoomManager {
+    repositories: Repositories,
+    models: RwLock<HashMap<String, RoomLayout>>,
+    rooms: LoadedRooms,
+    pathfinder_options: RwLock<PathfinderOptions>,
+    unload_after: RwLock<Duration>,
+}
+
+impl RoomManager {
+    pub fn new(repositories: Repositories) -> Self {
+        RoomManager {
+            repositories,
+            models: RwLock::new(HashMap::new()),
+            rooms: Arc::new(RwLock::new(HashMap::new())),
+            pathfinder_options: RwLock::new(PathfinderOptions::default()),
+            unload_after: RwLock::new(Duration::from_secs(60)),
+        }
+    }
+
+    /// Applies the `rooms.*` and `pathfinder.*` settings to rooms loaded from now on
+    pub fn configure(&self, config: &ConfigurationManager) {
+        *self.pathfinder_options.write().unwrap() = PathfinderOptions::load(config);
+        *self.unload_after.write().unwrap() = config.get_duration("rooms.unload_after").unwrap_or(Duration::from_secs(60));
+    }
+
+    pub fn set_unload_after(&self, unload_after: Duration) {
+        *self.unload_after.write().unwrap() = unload_after;
+    }
+
+    /// Loads `room_models`, models with a broken heightmap are skipped
+    pub async fn load_models(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let records = self.repositories.get_rooms().get_room_models().await?;
+        let mut models = HashMap::new();
+
+        for record in records {
+            match RoomLayout::from_record(&record) {
+                Ok(layout) => {
+                    models.insert(record.name.clone(), layout);
+                }
+                Err(e) => warn!("Skipping room model: {}", e),
+            }
+        }
+
+        info!("Loaded {} room models", models.len());
+        *self.models.write().unwrap() = models;
+
+        Ok(())
+    }
+
+    pub fn get_model(&self, name: &str) -> Option<RoomLayout> {
+        self.models.read().unwrap().get(name).cloned()
+    }
+
+    /// The room if it is loaded, otherwise loads it and starts its cycle. A custom model of the
+    /// room goes before the model it is set to. Returns None if the room doesn't exist.
+    pub async fn load_room(&self, room_id: i32) -> Result<Option<Arc<Room>>, Box<dyn Error + Send + Sync>> {
+        if let Some(room) = self.get_room(room_id) {
+            return Ok(Some(room));
+        }
+
+        let rooms = self.repositories.get_rooms();
+        let data = match rooms.get_room(room_id).await? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let layout = match rooms.get_custom_room_model(room_id).await? {
+            Some(custom) => RoomLayout::from_record(&custom)?,
+            None => self.get_model(&data.model)
+                .ok_or_else(|| format!("Room {} uses the unknown model {}", room_id, data.model))?,
+        };
+
+        let pathfinder = Pathfinder::new(*self.pathfinder_options.read().unwrap());
+
+        // Someone else may have loaded it meanwhile, theirs wins
+        let (room, loaded) = {
+            let mut loaded_rooms = self.rooms.write().unwrap();
+            match loaded_rooms.get(&room_id) {
+                Some(room) => (room.clone(), false),
+                None => {
+                    let room = Arc::new(Room::new(data, layout, pathfinder));
+                    loaded_rooms.insert(room_id, room.clone());
+                    (room, true)
+                }
+            }
+        };
+
+        if loaded {
+            self.start_cycle(room.clone());
+        }
+
+        Ok(Some(room))
+    }
+
+    /// Cycles the room until it is unloaded, unloading it once it stays empty
+    fn start_cycle(&self, room: Arc<Room>) {
+        let rooms = self.rooms.clone();
+        let unload_after = *self.unload_after.read().unwrap();
+
+        tokio::spawn(async move {
+            while room.run(unload_after).await {
+                if Self::unload_if_idle(&rooms, &room) {
+                    debug!("Unloaded idle room {}", room.get_id());
+                    break;
+                }
+            }
+        });
+    }
+
+    fn unload_if_idle(rooms: &LoadedRooms, room: &Arc<Room>) -> bool {
+        let mut rooms = rooms.write().unwrap();
+        if !room.try_unload() {
+            return false;
+        }
+
+        if rooms.get(&room.get_id()).is_some_and(|loaded| Arc::ptr_eq(loaded, room)) {
+            rooms.remove(&room.get_id());
+        }
+        true
+    }
+
+    pub fn get_room(&self, room_id: i32) -> Option<Arc<Room>> {
+        self.rooms.read().unwrap().get(&room_id).cloned()
+    }
+
+    /// Unloads a room right away and stops its cycle, no one can enter it after
+    pub fn unload_room(&self, room_id: i32) -> Option<Arc<Room>> {
+        let room = self.rooms.write().unwrap().remove(&room_id)?;
+        room.unload();
+        Some(room)
+    }
+
+    pub fn get_loaded_count(&self) -> usize {
+        self.rooms.read().unwrap().len()
+    }
+
+    /// Puts a user in the room they loaded, out of the room they were in before
+    pub fn enter_room(&self, habbo: &Arc<Habbo>, room: &Arc<Room>) -> bool {
+        self.leave_room(habbo);
+
+        if room.add_habbo(habbo.clone()).is_none() {
+            return false;
+        }
+
+        habbo.set_current_room(room.get_id());
+        habbo.set_loading_room(0);
+        true
+    }
+
+    /// Takes a user out of the room they are in
+    pub fn leave_room(&self, habbo: &Habbo) {
+        let room_id = habbo.get_current_room();
+        if room_id == 0 {
+            return;
+        }
+
+        if let Some(room) = self.get_room(room_id) {
+            room.remove_habbo(habbo.get_id());
+        }
+        habbo.set_current_room(0);
+    }
+
+    /// Stops the cycle of every room and saves them. A room that fails to save doesn't stop
+    /// the rest, the first error is returned.
+    pub async fn dispose(&self) -> Result<(), sqlx::Error> {
+        let rooms: Vec<_> = self.rooms.write().unwrap().drain().map(|(_, room)| room).collect();
+        let mut result = Ok(());
+
+        for room in rooms {
+            room.unload();
+
+            if let Err(e) = self.repositories.get_rooms().save_room(room.get_data()).await {
+                error!("Failed to save room {}: {}", room.get_id(), e);
+                result = result.and(Err(e));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::{InMemoryItemRepository, InMemoryRoomRepository, InMemorySettingsRepository, InMemoryUserRepository, RoomModelRecord};
+    use crate::util::fixtures::room;
+
+    fn model(name: &str, heightmap: &str) -> RoomModelRecord {
+        RoomModelRecord {
+            name: name.to_string(),
+            door_x: 0,
+            door_y: 0,
+            door_dir: 2,
+            heightmap: heightmap.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_rooms() {
+        let rooms = Arc::new(InMemoryRoomRepository::new());
+        rooms.insert_room_model(model("model_a", "00\r\n00"));
+        rooms.insert_room_model(model("model_broken", "x0"));
+        rooms.insert_room(room(1, "model_a"));
+        rooms.insert_room(room(2, "model_a"));
+        rooms.insert_custom_room_model(2, model("custom", "000\r\n111"));
+        rooms.insert_room(room(3, "model_unknown"));
+
+        let manager = RoomManager::new(Repositories::new(
+            Arc::new(InMemoryUserRepository::new()),
+            rooms,
+            Arc::new(InMemoryItemRepository::new()),
+            Arc::new(InMemorySettingsRepository::new()),
+        ));
+        manager.load_models().await.unwrap();
+        assert!(manager.get_model("model_broken").is_none());
+
+        let first = manager.load_room(1).await.unwrap().unwrap();
+        assert_eq!(first.get_layout().get_name(), "model_a");
+        assert!(Arc::ptr_eq(&first, &manager.load_room(1).await.unwrap().unwrap()));
+
+        let custom = manager.load_room(2).await.unwrap().unwrap();
+        assert_eq!(custom.get_layout().get_map_size_x(), 3);
+
+        assert!(manager.load_room(3).await.is_err());
+        assert!(manager.load_room(4).await.unwrap().is_none());
+        assert_eq!(manager.get_loaded_count(), 2);
+
+        manager.unload_room(1);
+        assert!(manager.get_room(1).is_none());
+        assert!(first.is_stopped() && first.is_unloaded());
+
+        // An empty room unloads itself
+        manager.set_unload_after(Duration::ZERO);
+        manager.load_room(1).await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(manager.get_room(1).is_none());
+    }
+}
@@ -0,0 +1,56 @@
+/// What a tile allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomTileState {
+    /// Can be walked on
+    Open,
+    /// Furniture stands on it that can't be walked through
+    Blocked,
+    /// Not part of the room, an `x` in the heightmap
+    Invalid,
+    /// Walking onto it sits the user down
+    Sit,
+    /// Walking onto it lays the user down
+    Lay,
+}
+
+/// A single square of a room layout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoomTile {
+    pub x: i16,
+    pub y: i16,
+    /// Height of the floor, from the heightmap
+    pub z: i16,
+    /// Height of whatever is on top, the floor when there is no furniture
+    pub stack_height: f64,
+    pub state: RoomTileState,
+    pub allow_stack: bool,
+}
+
+/// Height the client shows for a tile nothing can be placed on
+const NO_STACK_HEIGHT: i16 = 1 << 14;
+
+impl RoomTile {
+    pub fn new(x: i16, y: i16, z: i16, state: RoomTileState) -> Self {
+        RoomTile {
+            x,
+            y,
+            z,
+            stack_height: z as f64,
+            state,
+            allow_stack: state != RoomTileState::Invalid,
+        }
+    }
+
+    pub fn is_walkable(&self) -> bool {
+        matches!(self.state, RoomTileState::Open | RoomTileState::Sit | RoomTileState::Lay)
+    }
+
+    /// Stack height in the format of `HeightMapComposer`, in 256ths of a tile
+    pub fn get_relative_height(&self) -> i16 {
+        if self.state == RoomTileState::Invalid || !self.allow_stack {
+            return NO_STACK_HEIGHT;
+        }
+
+        (self.stack_height * 256.0) as i16
+    }
+}
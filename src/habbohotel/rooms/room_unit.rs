@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::habbohotel::rooms::{RoomTile, RoomTileState};
+use crate::habbohotel::users::Habbo;
+use crate::util::pathfinding::Rotation;
+
+/// Something a room unit is doing, shown above or on the avatar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomUnitStatus {
+    Move,
+    Sit,
+    Lay,
+    Sign,
+    Carry,
+}
+
+impl RoomUnitStatus {
+    /// Key in the status string of `UserUpdateComposer`
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            RoomUnitStatus::Move => "mv",
+            RoomUnitStatus::Sit => "sit",
+            RoomUnitStatus::Lay => "lay",
+            RoomUnitStatus::Sign => "sign",
+            RoomUnitStatus::Carry => "carryd",
+        }
+    }
+}
+
+struct UnitStatus {
+    status: RoomUnitStatus,
+    value: String,
+    /// Ticks until it wears off, None while it lasts
+    ticks_left: Option<u32>,
+}
+
+/// Heights the way the client reads them, `0` or `1.5`
+pub fn format_height(height: f64) -> String {
+    format!("{}", (height * 100.0).round() / 100.0)
+}
+
+/// A user inside a room, by the id the room gave it
+pub struct RoomUnit {
+    id: i32,
+    habbo: Arc<Habbo>,
+    x: i16,
+    y: i16,
+    z: f64,
+    /// The tile the unit is walking from, while it has a `mv` status
+    previous: (i16, i16, f64),
+    head_rotation: Rotation,
+    body_rotation: Rotation,
+    goal: Option<(i16, i16)>,
+    path: VecDeque<RoomTile>,
+    statuses: Vec<UnitStatus>,
+    needs_update: bool,
+}
+
+impl RoomUnit {
+    pub fn new(id: i32, habbo: Arc<Habbo>, tile: &RoomTile, rotation: Rotation) -> Self {
+        RoomUnit {
+            id,
+            habbo,
+            x: tile.x,
+            y: tile.y,
+            z: tile.stack_height,
+            previous: (tile.x, tile.y, tile.stack_height),
+            head_rotation: rotation,
+            body_rotation: rotation,
+            goal: None,
+            path: VecDeque::new(),
+            statuses: Vec::new(),
+            needs_update: true,
+        }
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_habbo(&self) -> &Arc<Habbo> {
+        &self.habbo
+    }
+
+    pub fn get_x(&self) -> i16 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> i16 {
+        self.y
+    }
+
+    pub fn get_z(&self) -> f64 {
+        self.z
+    }
+
+    /// Where the unit stood before its last step
+    pub fn get_previous_position(&self) -> (i16, i16, f64) {
+        self.previous
+    }
+
+    pub fn get_head_rotation(&self) -> Rotation {
+        self.head_rotation
+    }
+
+    pub fn get_body_rotation(&self) -> Rotation {
+        self.body_rotation
+    }
+
+    pub fn get_goal(&self) -> Option<(i16, i16)> {
+        self.goal
+    }
+
+    pub fn is_walking(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    /// Starts walking along a path, the first step is taken on the next tick
+    pub fn set_path(&mut self, goal: (i16, i16), path: Vec<RoomTile>) {
+        self.goal = Some(goal);
+        self.path = path.into();
+    }
+
+    pub fn stop_walking(&mut self) {
+        self.goal = None;
+        self.path.clear();
+    }
+
+    pub fn has_status(&self, status: RoomUnitStatus) -> bool {
+        self.statuses.iter().any(|unit_status| unit_status.status == status)
+    }
+
+    pub fn set_status(&mut self, status: RoomUnitStatus, value: &str) {
+        self.set_timed_status(status, value, None);
+    }
+
+    /// A status that wears off after a number of ticks, like holding up a sign
+    pub fn set_timed_status(&mut self, status: RoomUnitStatus, value: &str, ticks: Option<u32>) {
+        self.statuses.retain(|unit_status| unit_status.status != status);
+        self.statuses.push(UnitStatus { status, value: value.to_string(), ticks_left: ticks });
+        self.needs_update = true;
+    }
+
+    pub fn remove_status(&mut self, status: RoomUnitStatus) {
+        let count = self.statuses.len();
+        self.statuses.retain(|unit_status| unit_status.status != status);
+        self.needs_update |= self.statuses.len() != count;
+    }
+
+    /// Statuses as `UserUpdateComposer` sends them, `/mv 3,4,0/sign 7/`
+    pub fn get_status_string(&self) -> String {
+        let mut status = String::from("/");
+        for unit_status in &self.statuses {
+            status.push_str(unit_status.status.get_key());
+            if !unit_status.value.is_empty() {
+                status.push(' ');
+                status.push_str(&unit_status.value);
+            }
+            status.push('/');
+        }
+        status
+    }
+
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.head_rotation = rotation;
+        self.body_rotation = rotation;
+        self.needs_update = true;
+    }
+
+    /// Whether the unit changed since the last `UserUpdateComposer`, clears the flag
+    pub fn take_update(&mut self) -> bool {
+        std::mem::take(&mut self.needs_update)
+    }
+
+    /// The next tile of the path, without taking it
+    pub fn peek_step(&self) -> Option<&RoomTile> {
+        self.path.front()
+    }
+
+    /// Moves onto the next tile of the path. The position is already the new one, the client
+    /// is shown the previous one with a `mv` status to the new one so it animates the step.
+    pub fn step(&mut self) -> Option<RoomTile> {
+        let next = self.path.pop_front()?;
+
+        self.remove_status(RoomUnitStatus::Sit);
+        self.remove_status(RoomUnitStatus::Lay);
+        self.set_rotation(Rotation::calculate(self.x as i32, self.y as i32, next.x as i32, next.y as i32));
+        self.set_status(RoomUnitStatus::Move, &format!("{},{},{}", next.x, next.y, format_height(next.stack_height)));
+
+        self.previous = (self.x, self.y, self.z);
+        self.x = next.x;
+        self.y = next.y;
+        self.z = next.stack_height;
+
+        if self.path.is_empty() {
+            self.goal = None;
+        }
+
+        Some(next)
+    }
+
+    /// Done walking, sits or lays down if the tile is a seat or bed
+    pub fn arrive(&mut self, tile: &RoomTile) {
+        self.remove_status(RoomUnitStatus::Move);
+
+        let offset = format_height(tile.stack_height - tile.z as f64);
+        match tile.state {
+            RoomTileState::Sit => {
+                self.z = tile.z as f64;
+                self.set_status(RoomUnitStatus::Sit, &offset);
+            }
+            RoomTileState::Lay => {
+                self.z = tile.z as f64;
+                self.set_status(RoomUnitStatus::Lay, &offset);
+            }
+            _ => {}
+        }
+    }
+
+    /// Counts down timed statuses and removes the ones that wore off
+    pub fn cycle_statuses(&mut self) {
+        let count = self.statuses.len();
+
+        for unit_status in &mut self.statuses {
+            if let Some(ticks) = &mut unit_status.ticks_left {
+                *ticks = ticks.saturating_sub(1);
+            }
+        }
+        self.statuses.retain(|unit_status| unit_status.ticks_left != Some(0));
+
+        self.needs_update |= self.statuses.len() != count;
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::database::repositories::{UserRecord, UserRepository, UserSettingsRecord};
+use crate::habbohotel::gameclients::GameClient;
+use crate::habbohotel::users::HabboStats;
+
+/// Who the user is and what they have, loaded from `users` and `users_currency`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HabboInfo {
+    pub id: i32,
+    pub username: String,
+    pub motto: String,
+    pub look: String,
+    pub gender: String,
+    pub rank: i32,
+    pub credits: i32,
+    pub home_room: i32,
+    /// Balances by currency type, 0 are pixels
+    pub currencies: BTreeMap<i32, i32>,
+}
+
+impl HabboInfo {
+    pub fn new(user: UserRecord, currencies: Vec<(i32, i32)>) -> Self {
+        HabboInfo {
+            id: user.id,
+            username: user.username,
+            motto: user.motto,
+            look: user.look,
+            gender: user.gender,
+            rank: user.rank,
+            credits: user.credits,
+            home_room: user.home_room,
+            currencies: currencies.into_iter().collect(),
+        }
+    }
+
+    pub fn get_currency(&self, currency_type: i32) -> i32 {
+        self.currencies.get(&currency_type).copied().unwrap_or(0)
+    }
+}
+
+impl From<&HabboInfo> for UserRecord {
+    fn from(info: &HabboInfo) -> Self {
+        UserRecord {
+            id: info.id,
+            username: info.username.clone(),
+            motto: info.motto.clone(),
+            look: info.look.clone(),
+            gender: info.gender.clone(),
+            rank: info.rank,
+            credits: info.credits,
+            home_room: info.home_room,
+        }
+    }
+}
+
+/// A user that is logged in on a game client
+pub struct Habbo {
+    client: Arc<GameClient>,
+    info: RwLock<HabboInfo>,
+    stats: RwLock<HabboStats>,
+    /// Room the user is entering, 0 when none
+    loading_room: AtomicI32,
+    /// Room the user is in, 0 when none
+    current_room: AtomicI32,
+}
+
+impl Habbo {
+    pub fn new(client: Arc<GameClient>, info: HabboInfo, settings: UserSettingsRecord) -> Self {
+        Habbo {
+            client,
+            info: RwLock::new(info),
+            stats: RwLock::new(settings.into()),
+            loading_room: AtomicI32::new(0),
+            current_room: AtomicI32::new(0),
+        }
+    }
+
+    pub fn get_client(&self) -> &Arc<GameClient> {
+        &self.client
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.info.read().unwrap().id
+    }
+
+    pub fn get_username(&self) -> String {
+        self.info.read().unwrap().username.clone()
+    }
+
+    pub fn get_info(&self) -> RwLockReadGuard<'_, HabboInfo> {
+        self.info.read().unwrap()
+    }
+
+    pub fn get_info_mut(&self) -> RwLockWriteGuard<'_, HabboInfo> {
+        self.info.write().unwrap()
+    }
+
+    pub fn get_stats(&self) -> RwLockReadGuard<'_, HabboStats> {
+        self.stats.read().unwrap()
+    }
+
+    pub fn get_stats_mut(&self) -> RwLockWriteGuard<'_, HabboStats> {
+        self.stats.write().unwrap()
+    }
+
+    pub fn get_loading_room(&self) -> i32 {
+        self.loading_room.load(Ordering::Relaxed)
+    }
+
+    pub fn set_loading_room(&self, room_id: i32) {
+        self.loading_room.store(room_id, Ordering::Relaxed);
+    }
+
+    pub fn get_current_room(&self) -> i32 {
+        self.current_room.load(Ordering::Relaxed)
+    }
+
+    pub fn set_current_room(&self, room_id: i32) {
+        self.current_room.store(room_id, Ordering::Relaxed);
+    }
+
+    /// Writes the cached info and stats back to `users` and `users_settings`
+    pub async fn save(&self, users: &dyn UserRepository) -> Result<(), sqlx::Error> {
+        let user = UserRecord::from(&*self.get_info());
+        let settings = UserSettingsRecord::from(&*self.get_stats());
+
+        users.save_user(&user).await?;
+        users.save_user_settings(user.id, &settings).await
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use log::error;
+
+use crate::database::repositories::UserRepository;
+use crate::habbohotel::users::Habbo;
+
+/// Every user that is logged in, by user id. A user is logged in on one client at a time.
+pub struct HabboManager {
+    habbos: RwLock<HashMap<i32, Arc<Habbo>>>,
+}
+
+impl HabboManager {
+    pub fn new() -> Self {
+        HabboManager {
+            habbos: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Adds a user that logged in and returns the session it replaces, if they were logged in already
+    pub fn add_habbo(&self, habbo: Arc<Habbo>) -> Option<Arc<Habbo>> {
+        self.habbos.write().unwrap().insert(habbo.get_id(), habbo)
+    }
+
+    /// Removes a user that logged out. A session that was replaced by a newer login is left alone,
+    /// returns whether the user was removed.
+    pub fn remove_habbo(&self, habbo: &Habbo) -> bool {
+        let mut habbos = self.habbos.write().unwrap();
+
+        match habbos.get(&habbo.get_id()) {
+            Some(online) if online.get_client().get_id() == habbo.get_client().get_id() => {
+                habbos.remove(&habbo.get_id());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_habbo(&self, user_id: i32) -> Option<Arc<Habbo>> {
+        self.habbos.read().unwrap().get(&user_id).cloned()
+    }
+
+    pub fn get_habbo_by_name(&self, username: &str) -> Option<Arc<Habbo>> {
+        self.habbos.read().unwrap().values()
+            .find(|habbo| habbo.get_username().eq_ignore_ascii_case(username))
+            .cloned()
+    }
+
+    pub fn get_online_count(&self) -> usize {
+        self.habbos.read().unwrap().len()
+    }
+
+    /// Saves everyone that is logged in and marks them offline, used by the shutdown. The users
+    /// are removed first so their connections closing doesn't write them a second time.
+    /// A user that fails to save doesn't stop the rest, the first error is returned.
+    pub async fn dispose(&self, users: &dyn UserRepository) -> Result<(), sqlx::Error> {
+        let habbos: Vec<_> = self.habbos.write().unwrap().drain().map(|(_, habbo)| habbo).collect();
+        let mut result = Ok(());
+
+        for habbo in habbos {
+            let saved = match habbo.save(users).await {
+                Ok(()) => users.set_online(habbo.get_id(), false).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = saved {
+                error!("Failed to save {}: {}", habbo.get_username(), e);
+                result = result.and(Err(e));
+            }
+        }
+
+        result
+    }
+}
+
+impl Default for HabboManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::repositories::{InMemoryUserRepository, UserRecord};
+    use crate::util::fixtures::{habbo, user};
+
+    #[tokio::test]
+    async fn test_dispose_saves_and_logs_out() {
+        let user = UserRecord { credits: 100, ..user(1) };
+        let users = InMemoryUserRepository::new();
+        users.insert_user(user.clone());
+        users.set_online(1, true).await.unwrap();
+
+        let (habbo, _receiver) = habbo(user);
+        habbo.get_info_mut().motto = "Changed".to_string();
+        habbo.get_stats_mut().old_chat = true;
+
+        let manager = HabboManager::new();
+        manager.add_habbo(habbo);
+        manager.dispose(&users).await.unwrap();
+
+        assert_eq!(manager.get_online_count(), 0);
+        assert!(!users.is_online(1));
+        assert_eq!(users.get_user(1).await.unwrap().unwrap().motto, "Changed");
+        assert!(users.get_user_settings(1).await.unwrap().old_chat);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::database::repositories::UserSettingsRecord;
+
+/// Respects, achievement score and the preferences of a user
+#[derive(Debug, Clone, PartialEq)]
+pub struct HabboStats {
+    pub respects_received: i32,
+    pub daily_respect_points: i32,
+    pub daily_pet_respect_points: i32,
+    pub achievement_score: i32,
+    pub volume_system: i32,
+    pub volume_furni: i32,
+    pub volume_trax: i32,
+    pub old_chat: bool,
+    pub block_following: bool,
+    pub block_friendrequests: bool,
+}
+
+impl From<&HabboStats> for UserSettingsRecord {
+    fn from(stats: &HabboStats) -> Self {
+        UserSettingsRecord {
+            respects_received: stats.respects_received,
+            daily_respect_points: stats.daily_respect_points,
+            daily_pet_respect_points: stats.daily_pet_respect_points,
+            achievement_score: stats.achievement_score,
+            volume_system: stats.volume_system,
+            volume_furni: stats.volume_furni,
+            volume_trax: stats.volume_trax,
+            old_chat: stats.old_chat,
+            block_following: stats.block_following,
+            block_friendrequests: stats.block_friendrequests,
+        }
+    }
+}
+
+impl From<UserSettingsRecord> for HabboStats {
+    fn from(settings: UserSettingsRecord) -> Self {
+        HabboStats {
+            respects_received: settings.respects_received,
+            daily_respect_points: settings.daily_respect_points,
+            daily_pet_respect_points: settings.daily_pet_respect_points,
+            achievement_score: settings.achievement_score,
+            volume_system: settings.volume_system,
+            volume_furni: settings.volume_furni,
+            volume_trax: settings.volume_trax,
+            old_chat: settings.old_chat,
+            block_following: settings.block_following,
+            block_friendrequests: settings.block_friendrequests,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Users that are logged in, see `HabboManager`
+
+pub mod habbo;
+pub mod habbo_manager;
+pub mod habbo_stats;
+
+pub use habbo::{Habbo, HabboInfo};
+pub use habbo_manager::HabboManager;
+pub use habbo_stats::HabboStats;
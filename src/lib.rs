@@ -0,0 +1,521 @@
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+
+use log::{debug, error, info, warn};
+use tokio::runtime::Runtime;
+use num_cpus;
+use chrono::Local;
+use clap::{Parser, Subcommand};
+use once_cell::sync::OnceCell;
+
+// Module imports
+pub mod core;
+pub mod crypto;
+pub mod database;
+pub mod habbohotel;
+pub mod messages;
+pub mod networking;
+pub mod threading;
+pub mod util;
+
+use messages::outgoing::message_composer::MessageComposer;
+use networking::Server;
+
+// Constants
+const PREVIEW: &str = "";
+const VERSION: &str = "Sulove Rust Emulator";
+//rahmed is best rust dev
+
+// Logo
+const LOGO: &str = r#"
+███████╗██╗   ██╗██╗      ██████╗ ██╗   ██╗███████╗
+██╔════╝██║   ██║██║     ██╔═══██╗██║   ██║██╔════╝
+███████╗██║   ██║██║     ██║   ██║██║   ██║█████╗
+╚════██║██║   ██║██║     ██║   ██║╚██╗ ██╔╝██╔══╝
+███████║╚██████╔╝███████╗╚██████╔╝ ╚████╔╝ ███████╗
+╚══════╝ ╚═════╝ ╚══════╝ ╚═════╝   ╚═══╝  ╚══════╝
+"#;
+
+/// Command line of the emulator, without a subcommand the hotel starts
+#[derive(Parser)]
+#[command(name = "sulove", version, about = "A Habbo Hotel emulator written in Rust")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generates a new RSA key pair for the handshake, printed as enc.e, enc.n and enc.d
+    Keygen {
+        /// Size of the modulus in bits
+        #[arg(long, default_value_t = 1024)]
+        bits: u64,
+        /// Public exponent
+        #[arg(long, default_value_t = 65537)]
+        exponent: u64,
+    },
+    /// Applies the pending database migrations and exits
+    Migrate {
+        /// Prints the statements without running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+// Global statj
+static CONFIG_MANAGER: OnceCell<Arc<core::configuration_manager::ConfigurationManager>> = OnceCell::new();
+static CRYPTO_CONFIG: OnceCell<Arc<core::crypto_config::CryptoConfig>> = OnceCell::new();
+static ENCRYPTION: OnceCell<Arc<crypto::habbo_encryption::HabboEncryption>> = OnceCell::new();
+static TEXTS_MANAGER: OnceCell<Arc<core::texts_manager::TextsManager>> = OnceCell::new();
+static DATABASE: OnceCell<Arc<database::database::Database>> = OnceCell::new();
+static DATABASE_LOGGER: OnceCell<Arc<core::database_logger::DatabaseLogger>> = OnceCell::new();
+static ERROR_LOGGER: OnceCell<Arc<core::error_log::ErrorLogger>> = OnceCell::new();
+static GAME_ENVIRONMENT: OnceCell<Arc<habbohotel::game_enviroment::GameEnvironment>> = OnceCell::new();
+static GAME_SERVER: OnceCell<Arc<networking::gameserver::GameServer>> = OnceCell::new();
+static RCON_SERVER: OnceCell<Arc<networking::rconserver::RCONServer>> = OnceCell::new();
+static THREAD_POOL: std::sync::Mutex<Option<threading::thread_polling::ThreadPooling>> = std::sync::Mutex::new(None);
+static DISPOSABLES: OnceCell<Arc<core::disposable::DisposableRegistry>> = OnceCell::new();
+static SHUTDOWN_SCHEDULED: AtomicBool = AtomicBool::new(false);
+static IS_READY: AtomicBool = AtomicBool::new(false);
+static IS_SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static TIME_STARTED: OnceCell<u64> = OnceCell::new();
+
+pub fn get_config() -> Arc<core::configuration_manager::ConfigurationManager> {
+    CONFIG_MANAGER.get().expect("ConfigurationManager not initialized").clone()
+}
+
+pub fn get_crypto_config() -> Arc<core::crypto_config::CryptoConfig> {
+    CRYPTO_CONFIG.get().expect("CryptoConfig not initialized").clone()
+}
+
+/// The server's RSA key and Diffie-Hellman prime, None while encryption is disabled
+pub fn get_encryption() -> Option<Arc<crypto::habbo_encryption::HabboEncryption>> {
+    ENCRYPTION.get().cloned()
+}
+
+pub fn get_texts() -> Arc<core::texts_manager::TextsManager> {
+    TEXTS_MANAGER.get().expect("TextsManager not initialized").clone()
+}
+
+pub fn get_database() -> Arc<database::database::Database> {
+    DATABASE.get().expect("Database not initialized").clone()
+}
+
+/// Queue for log rows, see `DatabaseLoggable`
+pub fn get_database_logger() -> Arc<core::database_logger::DatabaseLogger> {
+    DATABASE_LOGGER.get().expect("DatabaseLogger not initialized").clone()
+}
+
+/// Where errors staff should look into go, None until the database is up
+pub fn get_error_logger() -> Option<Arc<core::error_log::ErrorLogger>> {
+    ERROR_LOGGER.get().cloned()
+}
+
+pub fn get_game_environment() -> Arc<habbohotel::game_enviroment::GameEnvironment> {
+    GAME_ENVIRONMENT.get().expect("GameEnvironment not initialized").clone()
+}
+
+/// Everything that gets saved and released on shutdown, see `dispose`
+pub fn get_disposables() -> Arc<core::disposable::DisposableRegistry> {
+    DISPOSABLES.get_or_init(|| Arc::new(core::disposable::DisposableRegistry::new())).clone()
+}
+
+/// Runtime of the thread pool, the database connections belong to it
+pub fn get_runtime() -> Option<Arc<Runtime>> {
+    THREAD_POOL.lock().unwrap().as_ref().map(|thread_pool| thread_pool.get_runtime())
+}
+
+pub fn is_ready() -> bool {
+    IS_READY.load(Ordering::SeqCst)
+}
+
+pub fn is_shutting_down() -> bool {
+    IS_SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Unix timestamp of the moment the hotel finished loading
+pub fn get_time_started() -> Option<u64> {
+    TIME_STARTED.get().copied()
+}
+
+pub fn get_unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+/// Runs the emulator with the command line it was started with, see `src/main.rs`
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    if let Some(Command::Keygen { bits, exponent }) = cli.command {
+        return keygen(bits, exponent);
+    }
+
+    // Initialize logging
+    env_logger::init();
+
+    if let Some(Command::Migrate { dry_run }) = cli.command {
+        return migrate(dry_run);
+    }
+
+    // Print logo and version info
+    println!("{}", LOGO);
+    info!("Version: {}", VERSION);
+    info!("This project is for educational purposes only.");
+    info!("Follow our development at https://github.com/sulove-rust/sulove");
+
+    let start_time = SystemTime::now();
+
+    // Initialize configuration
+    let config = Arc::new(core::configuration_manager::ConfigurationManager::new("config.ini")?);
+    CONFIG_MANAGER.set(config.clone()).expect("Failed to set ConfigurationManager");
+
+    // Initialize thread pool, the database connections live on its runtime
+    let thread_count = config.get_int("runtime.threads").unwrap_or_else(|_| num_cpus::get() as i32 * 2);
+    let thread_pool = threading::thread_polling::ThreadPooling::new(thread_count as usize);
+    let runtime = thread_pool.get_runtime();
+    *THREAD_POOL.lock().unwrap() = Some(thread_pool);
+
+    // Initialize database
+    let database = {
+        let _runtime = runtime.enter();
+        Arc::new(database::database::Database::new(config.clone())?)
+    };
+    DATABASE.set(database.clone()).expect("Failed to set Database");
+
+    // Bring the schema up to date, `sulove migrate` does the same for those who turn this off
+    if config.get_bool("db.migrations.auto").unwrap_or(true) {
+        let migrator = database::migrations::Migrator::new(database::migrations::MIGRATIONS);
+        runtime.block_on(migrator.run(database.get_pool(), false)).map_err(|e| e.to_string())?;
+    }
+
+    // Load configuration from database, it overrides the file and the environment
+    runtime.block_on(config.load_from_database(database.get_repositories().get_settings().as_ref())).map_err(|e| e.to_string())?;
+
+    // Report settings that are misspelled or have the wrong type, again after every reload
+    config.validate().log();
+    config.subscribe(|config| config.validate().log());
+
+    // Load texts, the file only takes over when emulator_texts can't be read
+    let texts = Arc::new(core::texts_manager::TextsManager::new(
+        &config.get_string("texts.file").unwrap_or_else(|_| "texts.ini".to_string()),
+        &config.get_string("texts.language").unwrap_or_else(|_| "en".to_string()),
+    ));
+    runtime.block_on(texts.load(Some(database.get_repositories().get_settings().as_ref()))).map_err(|e| e.to_string())?;
+    TEXTS_MANAGER.set(texts).map_err(|_| "Failed to set TextsManager")?;
+
+    // Log rows are queued and written in batches on the thread pool
+    let database_logger = Arc::new(core::database_logger::DatabaseLogger::new(
+        database.get_pool().clone(),
+        config.get_int("logging.database.batch_size").unwrap_or(100).max(1) as usize,
+    ));
+    let interval = config.get_duration("logging.database.interval").unwrap_or(std::time::Duration::from_secs(5));
+    runtime.spawn(database_logger.clone().run(interval));
+    DATABASE_LOGGER.set(database_logger.clone()).map_err(|_| "Failed to set DatabaseLogger")?;
+
+    // Panics end up in the error log as well, the default hook still prints them
+    let error_logger = core::error_log::ErrorLogger::load(&config, database_logger.clone());
+    ERROR_LOGGER.set(Arc::new(error_logger)).map_err(|_| "Failed to set ErrorLogger")?;
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(error_logger) = ERROR_LOGGER.get() {
+            error_logger.report_panic(info);
+        }
+        default_hook(info);
+    }));
+
+    // Initialize encryption settings
+    let crypto_config = Arc::new(core::crypto_config::CryptoConfig::load(&config));
+    CRYPTO_CONFIG.set(crypto_config.clone()).expect("Failed to set CryptoConfig");
+    info!("Encryption is {}", if crypto_config.is_enabled() { "enabled" } else { "disabled" });
+    if crypto_config.is_enabled() {
+        info!("Generating a {} bit Diffie-Hellman prime...", crypto_config.get_dh_bit_size());
+        let encryption = crypto::habbo_encryption::HabboEncryption::new(
+            crypto_config.get_exponent(),
+            crypto_config.get_modulus(),
+            crypto_config.get_private_exponent(),
+            crypto_config.get_dh_bit_size(),
+        );
+        ENCRYPTION.set(Arc::new(encryption)).map_err(|_| "Encryption already initialized")?;
+    }
+
+    // Initialize game server
+    let game_host = config.get_string("game.host").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let game_port = config.get_int("game.port").unwrap_or_else(|_| 30000);
+    let game_server = Arc::new(networking::gameserver::GameServer::new(game_host, game_port as u16, &config)?);
+
+    // Initialize game environment
+    let game_environment = Arc::new(habbohotel::game_enviroment::GameEnvironment::new(database.get_repositories().clone()));
+    GAME_ENVIRONMENT.set(game_environment.clone()).expect("Failed to set GameEnvironment");
+
+    // Initialize RCON server
+    let rcon_host = config.get_string("rcon.host").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let rcon_port = config.get_int("rcon.port").unwrap_or_else(|_| 30001);
+    let rcon_allowlist = messages::rcon::authentication::RconAllowlist::load(&config);
+    let rcon_server = Arc::new(networking::rconserver::RCONServer::new(
+        rcon_host,
+        rcon_port as u16,
+        rcon_allowlist,
+        game_server.get_game_client_manager(),
+        game_environment.clone(),
+    ));
+
+    // Load game environment
+    game_environment.get_room_manager().configure(&config);
+    runtime.block_on(game_environment.load()).map_err(|e| e.to_string())?;
+    get_disposables().register(game_environment.clone());
+    get_disposables().register(database_logger);
+    
+    GAME_SERVER.set(game_server.clone()).map_err(|_| "Failed to set GameServer")?;
+    RCON_SERVER.set(rcon_server.clone()).map_err(|_| "Failed to set RCONServer")?;
+
+    // Connect servers
+    game_server.initialize_pipeline()?;
+    game_server.connect()?;
+
+    rcon_server.initialize_pipeline()?;
+    rcon_server.connect()?;
+
+    // Ctrl+C and SIGTERM shut the hotel down like the shutdown command
+    listen_for_signals();
+
+    // Set up cleaner thread
+    let _cleaner = core::cleaner_thread::CleanerThread::new();
+
+    // Calculate startup time
+    let elapsed = start_time.elapsed()?.as_millis();
+
+    info!("Sulove has successfully loaded.");
+    info!("System launched in: {}ms. Using {} threads!", elapsed, thread_count);
+
+    // Set debugging mode
+    let debugging = config.get_bool("debug.mode").unwrap_or(false);
+    if debugging {
+        debug!("Debugging enabled.");
+    }
+
+    // Set ready state and record start time
+    IS_READY.store(true, Ordering::SeqCst);
+    TIME_STARTED.set(get_unix_timestamp()).expect("Failed to set start time");
+
+    // Check if console mode is enabled
+    if config.get_bool("console.mode").unwrap_or(true) {
+        let console = core::consolecommands::ConsoleCommandManager::new(core::consolecommands::ConsoleContext::new(
+            game_server.get_game_client_manager(),
+            rcon_server.get_message_handler(),
+        ));
+        let stdin = io::stdin();
+        let mut reader = stdin.lock().lines();
+
+        println!("Waiting for command: ");
+
+        while !IS_SHUTTING_DOWN.load(Ordering::SeqCst) && IS_READY.load(Ordering::SeqCst) {
+            match reader.next() {
+                Some(Ok(line)) => {
+                    console.handle(&line);
+                    if !IS_SHUTTING_DOWN.load(Ordering::SeqCst) {
+                        println!("Waiting for command: ");
+                    }
+                }
+                Some(Err(e)) => warn!("Failed to read from the console: {}", e),
+                // Stdin is closed, keep running without a console
+                None => while !IS_SHUTTING_DOWN.load(Ordering::SeqCst) {
+                    thread::sleep(std::time::Duration::from_secs(1));
+                },
+            }
+        }
+    } else {
+        // If console mode is disabled, just keep the main thread alive until the shutdown finished
+        while IS_READY.load(Ordering::SeqCst) {
+            thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+
+    Ok(())
+}
+
+// Prints a new RSA key pair in the format the config expects
+fn keygen(bits: u64, exponent: u64) -> Result<(), Box<dyn std::error::Error>> {
+    if bits < 512 {
+        return Err("The modulus needs at least 512 bits".into());
+    }
+    if exponent < 3 || exponent.is_multiple_of(2) {
+        return Err("The public exponent has to be an odd number of at least 3".into());
+    }
+
+    let (e, n, d) = crypto::habbo_rsa_crypto::HabboRSACrypto::generate_keys(bits, exponent);
+
+    println!("enc.e={}", e);
+    println!("enc.n={}", n);
+    println!("enc.d={}", d);
+
+    Ok(())
+}
+
+fn migrate(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = Arc::new(core::configuration_manager::ConfigurationManager::new("config.ini")?);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+
+    runtime.block_on(async {
+        let database = database::database::Database::new(config)?;
+        let migrator = database::migrations::Migrator::new(database::migrations::MIGRATIONS);
+        let applied = migrator.run(database.get_pool(), dry_run).await.map_err(|e| e.to_string())?;
+
+        if let (false, Some(version)) = (dry_run, applied.last()) {
+            info!("Applied {} migrations, the schema is at version {}", applied.len(), version);
+        }
+
+        database.close().await;
+        Ok(())
+    })
+}
+
+// Waits for Ctrl+C or SIGTERM on a thread of its own. The first signal starts the shutdown
+// with the configured countdown, a second one exits right away.
+fn listen_for_signals() {
+    thread::spawn(|| {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                error!("Failed to listen for shutdown signals: {}", e);
+                return;
+            }
+        };
+
+        runtime.block_on(async {
+            wait_for_signal().await;
+            info!("Received a shutdown signal");
+            shutdown(get_config().get_int("shutdown.countdown").unwrap_or(0).max(0) as u32);
+
+            wait_for_signal().await;
+            warn!("Received a second shutdown signal, exiting without saving");
+            std::process::exit(1);
+        });
+    });
+}
+
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Shuts the hotel down after warning everyone online once a minute, then exits the process
+pub fn shutdown(minutes: u32) {
+    if IS_SHUTTING_DOWN.load(Ordering::SeqCst) || SHUTDOWN_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(move || {
+        for remaining in (1..=minutes).rev() {
+            info!("The hotel shuts down in {} minute(s)", remaining);
+            if let Some(game_server) = GAME_SERVER.get() {
+                let alert = messages::outgoing::generic::HotelWillCloseInMinutesComposer::new(remaining as i32);
+                game_server.get_game_client_manager().broadcast(alert.compose());
+            }
+
+            thread::sleep(std::time::Duration::from_secs(60));
+        }
+
+        dispose();
+        std::process::exit(0);
+    });
+}
+
+// Shutdown function, runs the shutdown steps in order. Everything that needs async work gets
+// `shutdown.timeout` together, after that the rest is given up on.
+pub fn dispose() {
+    if IS_SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    info!("Shutting down Sulove...");
+
+    let timeout = CONFIG_MANAGER.get()
+        .and_then(|config| config.get_duration("shutdown.timeout").ok())
+        .unwrap_or(std::time::Duration::from_secs(30))
+        .max(std::time::Duration::from_secs(1));
+
+    // A runtime of its own, dispose can be called while another one is busy
+    let finished = thread::spawn(move || -> Result<bool, String> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(runtime.block_on(async {
+            let deadline = tokio::time::Instant::now() + timeout;
+            tokio::time::timeout_at(deadline, dispose_async(deadline)).await.is_ok()
+        }))
+    }).join();
+
+    match finished {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => error!("The shutdown took longer than {:?}, not everything was saved", timeout),
+        Ok(Err(e)) => error!("Failed to run the shutdown: {}", e),
+        Err(_) => error!("The shutdown panicked"),
+    }
+
+    // The connections are gone by now, stop what is left of the servers and the thread pool
+    if let Some(game_server) = GAME_SERVER.get() {
+        let _ = game_server.disconnect();
+    }
+
+    if let Some(thread_pool) = THREAD_POOL.lock().unwrap().take() {
+        thread_pool.shutdown(std::time::Duration::from_secs(5));
+    }
+
+    IS_READY.store(false, Ordering::SeqCst);
+    info!("Sulove has been shut down.");
+}
+
+async fn dispose_async(deadline: tokio::time::Instant) {
+    // Stop accepting connections and commands
+    if let Some(game_server) = GAME_SERVER.get() {
+        game_server.stop_listening();
+    }
+    if let Some(rcon_server) = RCON_SERVER.get() {
+        let _ = rcon_server.disconnect();
+    }
+
+    // Disconnect the users, nothing they do can change their data anymore
+    if let Some(game_server) = GAME_SERVER.get() {
+        game_server.get_game_client_manager().dispose();
+    }
+
+    // Save the online users, the loaded rooms and everything else that registered itself
+    get_disposables().dispose_all(deadline).await;
+
+    // Whatever was disposed after the logger may have logged a few more rows
+    if let Some(database_logger) = DATABASE_LOGGER.get() {
+        database_logger.flush().await;
+    }
+
+    // Close database connections last, the steps above still write to it
+    if let Some(database) = DATABASE.get() {
+        database.close().await;
+    }
+}
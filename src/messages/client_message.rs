@@ -1,5 +1,8 @@
-use bytes::BytesMut;
+use std::io;
+use bytes::{Buf, BytesMut};
 
+/// A packet received from the client.
+/// The read methods consume the body from the front, in the order the client wrote the values.
 pub struct ClientMessage {
     header: u16,
     body: BytesMut,
@@ -21,4 +24,144 @@ impl ClientMessage {
     pub fn get_body_mut(&mut self) -> &mut BytesMut {
         &mut self.body
     }
-}
\ No newline at end of file
+
+    /// Number of unread bytes left in the body
+    pub fn bytes_available(&self) -> usize {
+        self.body.remaining()
+    }
+
+    pub fn read_int(&mut self) -> io::Result<i32> {
+        self.require(4, "int")?;
+        Ok(self.body.get_i32())
+    }
+
+    pub fn read_short(&mut self) -> io::Result<i16> {
+        self.require(2, "short")?;
+        Ok(self.body.get_i16())
+    }
+
+    /// Reads a UTF-8 string prefixed with its length as a short
+    pub fn read_string(&mut self) -> io::Result<String> {
+        self.require(2, "string length")?;
+        let length = self.body.get_u16() as usize;
+        self.require(length, "string")?;
+
+        let bytes = self.body.split_to(length);
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// A boolean is a single byte, 1 for true
+    pub fn read_bool(&mut self) -> io::Result<bool> {
+        self.require(1, "bool")?;
+        Ok(self.body.get_u8() == 1)
+    }
+
+    /// Reads `length` raw bytes
+    pub fn read_bytes(&mut self, length: usize) -> io::Result<BytesMut> {
+        self.require(length, "bytes")?;
+        Ok(self.body.split_to(length))
+    }
+
+    fn require(&self, length: usize, what: &str) -> io::Result<()> {
+        if self.body.remaining() < length {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Packet {} ended while reading {}, {} of {} bytes left", self.header, what, self.body.remaining(), length),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use tokio_util::codec::Decoder;
+
+    use crate::messages::server_message::ServerMessage;
+    use crate::networking::gameserver::game_server_attributes::CryptoAttribute;
+    use crate::networking::gameserver::game_server_codec::{GameFrame, GameServerCodec};
+
+    #[derive(Debug, Clone)]
+    enum Value {
+        Int(i32),
+        Short(i16),
+        String(String),
+        Bool(bool),
+        Bytes(Vec<u8>),
+    }
+
+    fn value() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            any::<i32>().prop_map(Value::Int),
+            any::<i16>().prop_map(Value::Short),
+            any::<String>().prop_map(Value::String),
+            any::<bool>().prop_map(Value::Bool),
+            prop::collection::vec(any::<u8>(), 0..64).prop_map(Value::Bytes),
+        ]
+    }
+
+    fn write(values: &[Value], header: u16) -> ServerMessage {
+        let mut message = ServerMessage::new(header);
+        for value in values {
+            match value {
+                Value::Int(value) => message.append_int(*value),
+                Value::Short(value) => message.append_short(*value),
+                Value::String(value) => message.append_string(value),
+                Value::Bool(value) => message.append_bool(*value),
+                Value::Bytes(value) => message.append_bytes(value),
+            }
+        }
+        message
+    }
+
+    fn read(message: &mut ClientMessage, values: &[Value]) -> io::Result<()> {
+        for value in values {
+            match value {
+                Value::Int(expected) => assert_eq!(message.read_int()?, *expected),
+                Value::Short(expected) => assert_eq!(message.read_short()?, *expected),
+                Value::String(expected) => assert_eq!(&message.read_string()?, expected),
+                Value::Bool(expected) => assert_eq!(message.read_bool()?, *expected),
+                Value::Bytes(expected) => assert_eq!(&message.read_bytes(expected.len())?[..], &expected[..]),
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(header in any::<u16>(), values in prop::collection::vec(value(), 0..32)) {
+            let mut buf = BytesMut::from(&write(&values, header).get_complete()[..]);
+
+            // Through the whole inbound pipeline, like a packet the client sent
+            let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+            codec.decode(&mut BytesMut::new()).unwrap();
+            let mut message = match codec.decode(&mut buf).unwrap() {
+                Some(GameFrame::Message(message)) => message,
+                _ => panic!("expected a message"),
+            };
+
+            prop_assert_eq!(message.get_header(), header);
+            read(&mut message, &values).unwrap();
+            prop_assert_eq!(message.bytes_available(), 0);
+        }
+
+        #[test]
+        fn test_truncated_body_is_an_error(values in prop::collection::vec(value(), 1..32), cut in any::<prop::sample::Index>()) {
+            let body = write(&values, 1).get_body().clone();
+            prop_assume!(!body.is_empty());
+
+            let mut message = ClientMessage::new(1, BytesMut::from(&body[..cut.index(body.len())]));
+            prop_assert!(read(&mut message, &values).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        let mut message = ClientMessage::new(1, BytesMut::from(&[0u8, 2, 0xC3, 0x28][..]));
+        assert_eq!(message.read_string().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
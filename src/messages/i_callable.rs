@@ -0,0 +1,7 @@
+use crate::messages::incoming::message_handler::HandlerContext;
+
+/// A hook that runs before the handler of a packet, see `PacketManager::register_callable`.
+/// Returning false cancels the packet, the handler isn't called.
+pub trait ICallable: Send + Sync {
+    fn call(&self, context: &HandlerContext) -> bool;
+}
@@ -0,0 +1,168 @@
+use std::error::Error;
+use async_trait::async_trait;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::no_auth_message::NoAuthMessage;
+use crate::messages::outgoing::handshake::CompleteDiffieHandshakeComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+
+/// Last step of the key exchange, the client sends its public key and both sides
+/// switch to RC4 seeded with the shared key
+#[derive(Default)]
+pub struct CompleteDiffieHandshakeEvent;
+
+impl NoAuthMessage for CompleteDiffieHandshakeEvent {}
+
+#[async_trait]
+impl MessageHandler for CompleteDiffieHandshakeEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let diffie = context.get_client().take_diffie_hellman()
+            .ok_or("CompleteDiffieHandshake received without a pending handshake")?;
+
+        let shared_key = diffie.get_shared_key(&context.get_packet().read_string()?)?;
+        let public_key = diffie.get_public_key()?;
+
+        // The response itself still goes out in plain text, it is queued before the cipher switch
+        let response = CompleteDiffieHandshakeComposer::new(public_key, true).compose();
+        if !context.get_client().enable_encryption(&shared_key, response) {
+            return Err("Failed to enable encryption".into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::{BufMut, BytesMut};
+    use num_bigint::{BigInt, RandBigInt};
+    use rand::thread_rng;
+    use tokio::sync::mpsc;
+
+    use crate::crypto::habbo_encryption::HabboEncryption;
+    use crate::crypto::habbo_rc4::HabboRC4;
+    use crate::crypto::habbo_rsa_crypto::HabboRSACrypto;
+    use std::sync::Arc;
+
+    use crate::database::repositories::Repositories;
+    use crate::habbohotel::game_enviroment::GameEnvironment;
+    use crate::habbohotel::gameclients::GameClient;
+    use crate::messages::client_message::ClientMessage;
+    use crate::messages::incoming::handshake::InitDiffieHandshakeEvent;
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+    use crate::util::fixtures;
+
+    const E: &str = "3";
+    const N: &str = "a0cee885a9d3b3f05be35b29f252b72fa9f771ae8e0588f0ae8a5550f6b1d044e97ae1a1071c9014dc898f0e8d4307d0c392aafc90de4166e77a4fe92d7c3f8dddd8b517012f964f1fb59c95d38e9ad9743fd3b65fc6499461c3d157f2033312d9d7c31982ec4761585b4ddf64d0a05c18a17e969245f82440daa712eab625cb";
+    const D: &str = "6b349b03c68d22a03d423cc6a18c7a1fc6a4f6745eae5b4b1f06e38b4f2135834651ebc0af68600de85bb4b45e2cafe08261c7530b3ed6449a518a9b73a82a5d851b9e80686367249bb2ec7963df2b1f5eeb295df95087a490010fb047fd141cc4a3e0b17d16f1571e047dd93348c2208bf8aa05d85d358b7369443009bd53ab";
+
+    /// Takes the next packet off the client's send queue
+    fn next_packet(receiver: &mut mpsc::Receiver<OutgoingFrame>) -> ClientMessage {
+        match receiver.try_recv() {
+            Ok(OutgoingFrame::Packet(frame)) => {
+                let header = u16::from_be_bytes([frame[4], frame[5]]);
+                ClientMessage::new(header, BytesMut::from(&frame[6..]))
+            }
+            _ => panic!("expected a packet on the send queue"),
+        }
+    }
+
+    /// Checks a signature from the server with the public key, like the client does
+    fn verify_big_integer(rsa: &HabboRSACrypto, signed: &str) -> BigInt {
+        let verified = rsa.verify(&hex::decode(signed).unwrap()).unwrap();
+        std::str::from_utf8(&verified).unwrap().parse().unwrap()
+    }
+
+    fn context(client: &Arc<GameClient>, packet: ClientMessage) -> HandlerContext {
+        HandlerContext::new(Arc::clone(client), packet, Arc::new(GameEnvironment::new(Repositories::in_memory())))
+    }
+
+    #[tokio::test]
+    async fn test_handshake_as_client() {
+        crate::ENCRYPTION.get_or_init(|| Arc::new(HabboEncryption::new(E, N, D, 128)));
+        let (client, mut receiver) = fixtures::client(1);
+
+        // The client only knows the public part of the RSA key
+        let rsa = HabboRSACrypto::new(E, N);
+
+        InitDiffieHandshakeEvent
+            .handle(&mut context(&client, ClientMessage::new(3110, BytesMut::new())))
+            .await
+            .unwrap();
+
+        let mut init = next_packet(&mut receiver);
+        assert_eq!(init.get_header(), Outgoing::InitDiffieHandshakeComposer.get_header());
+        let prime = verify_big_integer(&rsa, &init.read_string().unwrap());
+        let generator = verify_big_integer(&rsa, &init.read_string().unwrap());
+        assert!(prime > BigInt::from(2));
+        assert!(generator < prime);
+
+        let client_private = BigInt::from(thread_rng().gen_biguint(128));
+        let client_public = generator.modpow(&client_private, &prime);
+
+        let mut complete = ClientMessage::new(773, BytesMut::new());
+        let encrypted_public = rsa.encrypt(client_public.to_string().as_bytes()).unwrap();
+        let encoded_public = hex::encode(encrypted_public);
+        complete.get_body_mut().put_u16(encoded_public.len() as u16);
+        complete.get_body_mut().put_slice(encoded_public.as_bytes());
+
+        CompleteDiffieHandshakeEvent.handle(&mut context(&client, complete)).await.unwrap();
+
+        let mut response = next_packet(&mut receiver);
+        assert_eq!(response.get_header(), Outgoing::CompleteDiffieHandshakeComposer.get_header());
+        let server_public = verify_big_integer(&rsa, &response.read_string().unwrap());
+        let shared_key = server_public.modpow(&client_private, &prime).to_bytes_be().1;
+
+        // The cipher switch is queued after the response and uses the same key as the client
+        let mut server_cipher = match receiver.try_recv() {
+            Ok(OutgoingFrame::EnableEncryption(crypto)) => crypto,
+            _ => panic!("expected the cipher switch after the response"),
+        };
+        assert!(client.is_encrypted());
+
+        let mut client_cipher = HabboRC4::new(&shared_key);
+        let mut data = b"SSO ticket".to_vec();
+        client_cipher.parse(&mut data);
+        assert!(client.get_crypto_client().parse(&mut data));
+        assert_eq!(&data, b"SSO ticket");
+
+        let mut data = b"SecureLoginOK".to_vec();
+        server_cipher.parse(&mut data);
+        HabboRC4::new(&shared_key).parse(&mut data);
+        assert_eq!(&data, b"SecureLoginOK");
+
+        // A second attempt has no pending handshake left
+        let again = ClientMessage::new(773, BytesMut::new());
+        assert!(CompleteDiffieHandshakeEvent.handle(&mut context(&client, again)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trivial_public_key_is_refused() {
+        crate::ENCRYPTION.get_or_init(|| Arc::new(HabboEncryption::new(E, N, D, 128)));
+        let rsa = HabboRSACrypto::new(E, N);
+
+        for public_key in ["1", "p - 1"] {
+            let (client, mut receiver) = fixtures::client(1);
+
+            InitDiffieHandshakeEvent
+                .handle(&mut context(&client, ClientMessage::new(3110, BytesMut::new())))
+                .await
+                .unwrap();
+            let prime = verify_big_integer(&rsa, &next_packet(&mut receiver).read_string().unwrap());
+            let public_key = match public_key {
+                "1" => BigInt::from(1),
+                _ => prime - 1,
+            };
+
+            let mut complete = ClientMessage::new(773, BytesMut::new());
+            let encoded_public = hex::encode(rsa.encrypt(public_key.to_string().as_bytes()).unwrap());
+            complete.get_body_mut().put_u16(encoded_public.len() as u16);
+            complete.get_body_mut().put_slice(encoded_public.as_bytes());
+
+            assert!(CompleteDiffieHandshakeEvent.handle(&mut context(&client, complete)).await.is_err());
+            assert!(!client.is_encrypted());
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::error::Error;
+use async_trait::async_trait;
+use log::warn;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::no_auth_message::NoAuthMessage;
+use crate::messages::outgoing::handshake::InitDiffieHandshakeComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+
+/// First step of the key exchange, the client asks for the Diffie-Hellman parameters
+#[derive(Default)]
+pub struct InitDiffieHandshakeEvent;
+
+impl NoAuthMessage for InitDiffieHandshakeEvent {}
+
+#[async_trait]
+impl MessageHandler for InitDiffieHandshakeEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Clients built without encryption skip the handshake, one that starts it anyway just gets no answer
+        let Some(encryption) = crate::get_encryption() else {
+            warn!("Client {} started the handshake while encryption is disabled", context.get_client().get_id());
+            return Ok(());
+        };
+
+        let diffie = encryption.create_diffie();
+        let signed_prime = diffie.get_signed_prime()?;
+        let signed_generator = diffie.get_signed_generator()?;
+
+        let client = context.get_client();
+        client.set_diffie_hellman(diffie);
+        client.send_response(InitDiffieHandshakeComposer::new(signed_prime, signed_generator).compose());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+pub mod complete_diffie_handshake_event;
+pub mod init_diffie_handshake_event;
+pub mod pong_event;
+pub mod secure_login_event;
+
+pub use complete_diffie_handshake_event::CompleteDiffieHandshakeEvent;
+pub use init_diffie_handshake_event::InitDiffieHandshakeEvent;
+pub use pong_event::PongEvent;
+pub use secure_login_event::SecureLoginEvent;
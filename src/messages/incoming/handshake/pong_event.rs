@@ -0,0 +1,19 @@
+use std::error::Error;
+use async_trait::async_trait;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::no_auth_message::NoAuthMessage;
+
+/// The client's answer to a ping. The connection's `IdleTimeoutHandler` already saw the packet
+/// before it got here, so there is nothing left to do.
+#[derive(Default)]
+pub struct PongEvent;
+
+impl NoAuthMessage for PongEvent {}
+
+#[async_trait]
+impl MessageHandler for PongEvent {
+    async fn handle(&self, _context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::sync::Arc;
+use async_trait::async_trait;
+use log::{info, warn};
+
+use crate::habbohotel::users::{Habbo, HabboInfo};
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::no_auth_message::NoAuthMessage;
+use crate::messages::outgoing::handshake::{AvailabilityStatusComposer, PingComposer, SecureLoginOKComposer};
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::users::{UserCreditsComposer, UserCurrencyComposer, UserHomeRoomComposer, UserPermissionsComposer};
+
+/// Logs the client in with the SSO ticket the website put in `users.auth_ticket`.
+/// Logging in again somewhere else kicks the older session.
+#[derive(Default)]
+pub struct SecureLoginEvent;
+
+impl NoAuthMessage for SecureLoginEvent {}
+
+#[async_trait]
+impl MessageHandler for SecureLoginEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let ticket = context.get_packet().read_string()?.replace(' ', "");
+        let client = context.get_client().clone();
+        let environment = context.get_environment().clone();
+
+        if client.is_authenticated() {
+            warn!("Client {} sent a second SSO ticket", client.get_address());
+            return Ok(());
+        }
+
+        let users = environment.get_repositories().get_users();
+        let user = match users.get_user_by_auth_ticket(&ticket).await? {
+            Some(user) => user,
+            None => {
+                warn!("Client {} tried to log in with an invalid SSO ticket", client.get_address());
+                client.close();
+                return Ok(());
+            }
+        };
+
+        // A ticket can only be used once, whoever sent it at the same time is too late
+        if !users.claim_auth_ticket(user.id, &ticket).await? {
+            warn!("Client {} tried to log in with an SSO ticket that was just used", client.get_address());
+            client.close();
+            return Ok(());
+        }
+
+        let currencies = users.get_currencies(user.id).await?;
+        let settings = users.get_user_settings(user.id).await?;
+        let habbo = Arc::new(Habbo::new(client.clone(), HabboInfo::new(user, currencies), settings));
+
+        client.set_habbo(habbo.clone());
+        client.set_user_id(habbo.get_id());
+        client.set_authenticated(true);
+
+        if let Some(previous) = environment.get_habbo_manager().add_habbo(habbo.clone()) {
+            info!("{} logged in again, disconnecting the older session", habbo.get_username());
+            previous.get_client().disconnect();
+        }
+
+        users.set_online(habbo.get_id(), true).await?;
+
+        let info = habbo.get_info().clone();
+        client.send_response(SecureLoginOKComposer.compose());
+        client.send_response(UserHomeRoomComposer::new(info.home_room, 0).compose());
+        client.send_response(UserPermissionsComposer::new(0, info.rank, false).compose());
+        client.send_response(AvailabilityStatusComposer::new(true, false, true).compose());
+        client.send_response(UserCreditsComposer::new(info.credits).compose());
+        client.send_response(UserCurrencyComposer::new(info.currencies.into_iter().collect()).compose());
+        client.send_response(PingComposer.compose());
+
+        info!("{} logged in from {}", info.username, client.get_address());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::{BufMut, BytesMut};
+
+    use crate::database::repositories::{InMemoryItemRepository, InMemoryRoomRepository, InMemorySettingsRepository, InMemoryUserRepository, Repositories, UserRecord, UserRepository};
+    use crate::habbohotel::game_enviroment::GameEnvironment;
+    use crate::messages::client_message::ClientMessage;
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+    use crate::util::fixtures::{client, headers, user};
+
+    fn login_packet(ticket: &str) -> ClientMessage {
+        let mut packet = ClientMessage::new(2419, BytesMut::new());
+        packet.get_body_mut().put_u16(ticket.len() as u16);
+        packet.get_body_mut().put_slice(ticket.as_bytes());
+        packet
+    }
+
+    #[tokio::test]
+    async fn test_login_kicks_older_session() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        users.insert_user(UserRecord { rank: 7, credits: 100, ..user(1) });
+        users.add_currency(1, 0, 25).await.unwrap();
+
+        let repositories = Repositories::new(
+            users.clone(),
+            Arc::new(InMemoryRoomRepository::new()),
+            Arc::new(InMemoryItemRepository::new()),
+            Arc::new(InMemorySettingsRepository::new()),
+        );
+        let environment = Arc::new(GameEnvironment::new(repositories));
+
+        let (first, mut first_receiver) = client(1);
+
+        // Unknown tickets don't get in
+        SecureLoginEvent.handle(&mut HandlerContext::new(first.clone(), login_packet("wrong"), environment.clone())).await.unwrap();
+        assert!(!first.is_authenticated());
+        assert!(matches!(first_receiver.try_recv(), Ok(OutgoingFrame::Close)));
+
+        users.set_auth_ticket(1, "ticket");
+        SecureLoginEvent.handle(&mut HandlerContext::new(first.clone(), login_packet("ticket"), environment.clone())).await.unwrap();
+        assert!(first.is_authenticated());
+        assert_eq!(first.get_user_id(), 1);
+        assert!(users.is_online(1));
+        assert_eq!(headers(&mut first_receiver)[..2], [Outgoing::SecureLoginOKComposer.get_header(), Outgoing::UserHomeRoomComposer.get_header()]);
+
+        // The ticket was used up, a new one logs in on a second connection and kicks the first
+        assert!(users.get_user_by_auth_ticket("ticket").await.unwrap().is_none());
+        assert!(!users.claim_auth_ticket(1, "ticket").await.unwrap());
+        users.set_auth_ticket(1, "again");
+        let (second, _second_receiver) = client(2);
+        SecureLoginEvent.handle(&mut HandlerContext::new(second.clone(), login_packet("again"), environment.clone())).await.unwrap();
+
+        assert!(first.is_disconnected());
+        let habbo = environment.get_habbo_manager().get_habbo(1).unwrap();
+        assert_eq!(habbo.get_client().get_id(), 2);
+        assert_eq!(habbo.get_info().get_currency(0), 25);
+
+        // The older session closing doesn't log out the new one
+        assert!(!environment.get_habbo_manager().remove_habbo(&first.take_habbo().unwrap()));
+        assert_eq!(environment.get_habbo_manager().get_online_count(), 1);
+    }
+}
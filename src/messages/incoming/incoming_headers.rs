@@ -0,0 +1,14 @@
+use crate::messages::incoming::handshake::{CompleteDiffieHandshakeEvent, InitDiffieHandshakeEvent, PongEvent, SecureLoginEvent};
+use crate::messages::incoming::rooms::{RequestRoomHeightmapEvent, RequestRoomLoadEvent, RoomUserWalkEvent};
+use crate::messages::packet_names::incoming_packets;
+
+// Every packet the client can send: name = header id in the default revision => handler
+incoming_packets! {
+    InitDiffieHandshakeEvent = 3110 => InitDiffieHandshakeEvent,
+    CompleteDiffieHandshakeEvent = 773 => CompleteDiffieHandshakeEvent,
+    PongEvent = 2596 => PongEvent,
+    SecureLoginEvent = 2419 => SecureLoginEvent,
+    RequestRoomLoadEvent = 2312 => RequestRoomLoadEvent,
+    RequestRoomHeightmapEvent = 2300 => RequestRoomHeightmapEvent,
+    RoomUserWalkEvent = 3320 => RoomUserWalkEvent,
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use crate::habbohotel::game_enviroment::GameEnvironment;
+use crate::habbohotel::gameclients::GameClient;
+use crate::messages::client_message::ClientMessage;
+
+/// Everything a handler gets to work with: the client that sent the packet, the packet itself
+/// and the hotel
+pub struct HandlerContext {
+    client: Arc<GameClient>,
+    packet: ClientMessage,
+    environment: Arc<GameEnvironment>,
+}
+
+impl HandlerContext {
+    pub fn new(client: Arc<GameClient>, packet: ClientMessage, environment: Arc<GameEnvironment>) -> Self {
+        Self {
+            client,
+            packet,
+            environment,
+        }
+    }
+
+    pub fn get_client(&self) -> &Arc<GameClient> {
+        &self.client
+    }
+
+    /// The packet to read from, reading consumes it from the front
+    pub fn get_packet(&mut self) -> &mut ClientMessage {
+        &mut self.packet
+    }
+
+    pub fn get_header(&self) -> u16 {
+        self.packet.get_header()
+    }
+
+    pub fn get_environment(&self) -> &Arc<GameEnvironment> {
+        &self.environment
+    }
+}
+
+/// Handles one kind of incoming packet, see the packet table in `incoming_headers.rs`.
+/// Returning an error disconnects the client that sent the packet.
+///
+/// Only logged in clients reach a handler, unless it implements `NoAuthMessage`.
+#[async_trait]
+pub trait MessageHandler {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
@@ -26,8 +26,6 @@ pub mod unknown;
 pub mod users;
 pub mod wired;
 
-// Re-export the incoming.rs file
-mod message_handler;
-
-// Export any important items from this module
-// pub use self::message_handler::*;
\ No newline at end of file
+// The header table and the shared trait
+pub mod incoming_headers;
+pub mod message_handler;
\ No newline at end of file
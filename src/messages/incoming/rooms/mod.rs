@@ -0,0 +1,7 @@
+pub mod request_room_heightmap_event;
+pub mod request_room_load_event;
+pub mod room_user_walk_event;
+
+pub use request_room_heightmap_event::RequestRoomHeightmapEvent;
+pub use request_room_load_event::RequestRoomLoadEvent;
+pub use room_user_walk_event::RoomUserWalkEvent;
@@ -0,0 +1,95 @@
+use std::error::Error;
+use async_trait::async_trait;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::rooms::{FloorHeightMapComposer, HeightMapComposer};
+
+/// Sends the heightmaps of the room the user is entering and puts them in it
+#[derive(Default)]
+pub struct RequestRoomHeightmapEvent;
+
+#[async_trait]
+impl MessageHandler for RequestRoomHeightmapEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let client = context.get_client().clone();
+        let room_manager = context.get_environment().get_room_manager();
+
+        let habbo = match client.get_habbo() {
+            Some(habbo) => habbo,
+            None => return Ok(()),
+        };
+        let room = match room_manager.get_room(habbo.get_loading_room()) {
+            Some(room) => room,
+            None => return Ok(()),
+        };
+
+        {
+            let layout = room.get_layout();
+            client.send_response(HeightMapComposer::new(&layout).compose());
+            client.send_response(FloorHeightMapComposer::new(&layout, -1).compose());
+        }
+
+        room_manager.enter_room(&habbo, &room);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use bytes::{BufMut, BytesMut};
+
+    use crate::database::repositories::{InMemoryItemRepository, InMemoryRoomRepository, InMemorySettingsRepository, InMemoryUserRepository, Repositories, RoomModelRecord};
+    use crate::habbohotel::game_enviroment::GameEnvironment;
+    use crate::messages::client_message::ClientMessage;
+    use crate::messages::incoming::rooms::RequestRoomLoadEvent;
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::util::fixtures::{habbo, headers, room, user};
+
+    #[tokio::test]
+    async fn test_enter_room() {
+        let rooms = Arc::new(InMemoryRoomRepository::new());
+        rooms.insert_room_model(RoomModelRecord {
+            name: "model_a".to_string(),
+            door_x: 0,
+            door_y: 0,
+            door_dir: 2,
+            heightmap: "00\r\n0x".to_string(),
+        });
+        rooms.insert_room(room(5, "model_a"));
+
+        let environment = Arc::new(GameEnvironment::new(Repositories::new(
+            Arc::new(InMemoryUserRepository::new()),
+            rooms,
+            Arc::new(InMemoryItemRepository::new()),
+            Arc::new(InMemorySettingsRepository::new()),
+        )));
+        environment.load().await.unwrap();
+
+        let (habbo, mut receiver) = habbo(user(1));
+        let client = habbo.get_client().clone();
+
+        let mut packet = ClientMessage::new(2312, BytesMut::new());
+        packet.get_body_mut().put_i32(5);
+        packet.get_body_mut().put_u16(0);
+        RequestRoomLoadEvent.handle(&mut HandlerContext::new(client.clone(), packet, environment.clone())).await.unwrap();
+        assert_eq!(client.get_habbo().unwrap().get_loading_room(), 5);
+
+        let packet = ClientMessage::new(2300, BytesMut::new());
+        RequestRoomHeightmapEvent.handle(&mut HandlerContext::new(client.clone(), packet, environment.clone())).await.unwrap();
+
+        assert_eq!(headers(&mut receiver), [
+            Outgoing::RoomOpenComposer.get_header(),
+            Outgoing::RoomModelComposer.get_header(),
+            Outgoing::HeightMapComposer.get_header(),
+            Outgoing::FloorHeightMapComposer.get_header(),
+            Outgoing::RoomUsersComposer.get_header(),
+            Outgoing::UserUpdateComposer.get_header(),
+        ]);
+        assert_eq!(client.get_habbo().unwrap().get_current_room(), 5);
+        assert_eq!(environment.get_room_manager().get_room(5).unwrap().get_user_count(), 1);
+    }
+}
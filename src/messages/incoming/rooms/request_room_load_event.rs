@@ -0,0 +1,41 @@
+use std::error::Error;
+use async_trait::async_trait;
+use log::warn;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::rooms::{RoomModelComposer, RoomOpenComposer};
+
+/// The user wants to enter a room. Loads it and tells the client which model to expect,
+/// the client asks for the heightmap with `RequestRoomHeightmapEvent` next.
+#[derive(Default)]
+pub struct RequestRoomLoadEvent;
+
+#[async_trait]
+impl MessageHandler for RequestRoomLoadEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let room_id = context.get_packet().read_int()?;
+        let _password = context.get_packet().read_string()?;
+        let client = context.get_client().clone();
+
+        let habbo = match client.get_habbo() {
+            Some(habbo) => habbo,
+            None => return Ok(()),
+        };
+
+        let room = match context.get_environment().get_room_manager().load_room(room_id).await? {
+            Some(room) => room,
+            None => {
+                warn!("{} tried to enter room {} which doesn't exist", habbo.get_username(), room_id);
+                return Ok(());
+            }
+        };
+
+        habbo.set_loading_room(room.get_id());
+
+        client.send_response(RoomOpenComposer.compose());
+        client.send_response(RoomModelComposer::new(room.get_layout().get_name(), room.get_id()).compose());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use std::error::Error;
+use async_trait::async_trait;
+
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+
+/// The user clicked a tile, the room cycle walks them there
+#[derive(Default)]
+pub struct RoomUserWalkEvent;
+
+#[async_trait]
+impl MessageHandler for RoomUserWalkEvent {
+    async fn handle(&self, context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let x = context.get_packet().read_int()?;
+        let y = context.get_packet().read_int()?;
+        let (Ok(x), Ok(y)) = (i16::try_from(x), i16::try_from(y)) else {
+            return Ok(());
+        };
+
+        let habbo = match context.get_client().get_habbo() {
+            Some(habbo) => habbo,
+            None => return Ok(()),
+        };
+
+        if let Some(room) = context.get_environment().get_room_manager().get_room(habbo.get_current_room()) {
+            room.walk_to(habbo.get_id(), x, y);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use std::marker::PhantomData;
+
+/// Marks a `MessageHandler` that may run before the client logged in with its SSO ticket,
+/// like the handshake and the login itself. Every other packet from a client that isn't
+/// logged in yet is dropped by the `PacketManager`.
+pub trait NoAuthMessage {}
+
+/// Lets the packet table find out whether a handler type implements `NoAuthMessage`, without
+/// declaring it a second time. Method resolution picks `NoAuthProbe::is_no_auth` when the bound
+/// holds and falls back to `AuthProbe::is_no_auth` otherwise:
+///
+/// `(&&NoAuthCheck::<Handler>::new()).is_no_auth()`
+pub struct NoAuthCheck<H>(PhantomData<H>);
+
+impl<H> NoAuthCheck<H> {
+    pub fn new() -> Self {
+        NoAuthCheck(PhantomData)
+    }
+}
+
+pub trait NoAuthProbe {
+    fn is_no_auth(&self) -> bool {
+        true
+    }
+}
+
+impl<H: NoAuthMessage> NoAuthProbe for &NoAuthCheck<H> {}
+
+pub trait AuthProbe {
+    fn is_no_auth(&self) -> bool {
+        false
+    }
+}
+
+impl<H> AuthProbe for NoAuthCheck<H> {}
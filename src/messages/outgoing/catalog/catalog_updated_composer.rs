@@ -0,0 +1,14 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Tells the client its cached catalog is outdated, it reloads the pages the next time it opens
+pub struct CatalogUpdatedComposer;
+
+impl MessageComposer for CatalogUpdatedComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::CatalogUpdatedComposer.get_header());
+        response.append_bool(false);
+        response
+    }
+}
@@ -0,0 +1,3 @@
+pub mod catalog_updated_composer;
+
+pub use catalog_updated_composer::CatalogUpdatedComposer;
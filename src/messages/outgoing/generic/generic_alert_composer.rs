@@ -0,0 +1,22 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// A plain alert window with a message
+pub struct GenericAlertComposer {
+    message: String,
+}
+
+impl GenericAlertComposer {
+    pub fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl MessageComposer for GenericAlertComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::GenericAlertComposer.get_header());
+        response.append_string(&self.message);
+        response
+    }
+}
@@ -0,0 +1,22 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Warns that the hotel is about to shut down
+pub struct HotelWillCloseInMinutesComposer {
+    minutes: i32,
+}
+
+impl HotelWillCloseInMinutesComposer {
+    pub fn new(minutes: i32) -> Self {
+        Self { minutes }
+    }
+}
+
+impl MessageComposer for HotelWillCloseInMinutesComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::HotelWillCloseInMinutesComposer.get_header());
+        response.append_int(self.minutes);
+        response
+    }
+}
@@ -0,0 +1,5 @@
+pub mod generic_alert_composer;
+pub mod hotel_will_close_in_minutes_composer;
+
+pub use generic_alert_composer::GenericAlertComposer;
+pub use hotel_will_close_in_minutes_composer::HotelWillCloseInMinutesComposer;
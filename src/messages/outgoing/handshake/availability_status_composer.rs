@@ -0,0 +1,30 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Tells the client the hotel is open
+pub struct AvailabilityStatusComposer {
+    open: bool,
+    on_shutdown: bool,
+    authentic: bool,
+}
+
+impl AvailabilityStatusComposer {
+    pub fn new(open: bool, on_shutdown: bool, authentic: bool) -> Self {
+        Self {
+            open,
+            on_shutdown,
+            authentic,
+        }
+    }
+}
+
+impl MessageComposer for AvailabilityStatusComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::AvailabilityStatusComposer.get_header());
+        response.append_bool(self.open);
+        response.append_bool(self.on_shutdown);
+        response.append_bool(self.authentic);
+        response
+    }
+}
@@ -0,0 +1,28 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Sends the server's signed Diffie-Hellman public key, the last packet before encryption starts
+pub struct CompleteDiffieHandshakeComposer {
+    public_key: String,
+    server_client_encryption: bool,
+}
+
+impl CompleteDiffieHandshakeComposer {
+    pub fn new(public_key: String, server_client_encryption: bool) -> Self {
+        Self {
+            public_key,
+            server_client_encryption,
+        }
+    }
+}
+
+impl MessageComposer for CompleteDiffieHandshakeComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::CompleteDiffieHandshakeComposer.get_header());
+        response.append_string(&self.public_key);
+        // Tells the client that packets from the server are encrypted as well
+        response.append_bool(self.server_client_encryption);
+        response
+    }
+}
@@ -0,0 +1,27 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Sends the Diffie-Hellman prime and generator, both signed with the server's RSA key
+pub struct InitDiffieHandshakeComposer {
+    signed_prime: String,
+    signed_generator: String,
+}
+
+impl InitDiffieHandshakeComposer {
+    pub fn new(signed_prime: String, signed_generator: String) -> Self {
+        Self {
+            signed_prime,
+            signed_generator,
+        }
+    }
+}
+
+impl MessageComposer for InitDiffieHandshakeComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::InitDiffieHandshakeComposer.get_header());
+        response.append_string(&self.signed_prime);
+        response.append_string(&self.signed_generator);
+        response
+    }
+}
@@ -0,0 +1,11 @@
+pub mod availability_status_composer;
+pub mod complete_diffie_handshake_composer;
+pub mod init_diffie_handshake_composer;
+pub mod ping_composer;
+pub mod secure_login_ok_composer;
+
+pub use availability_status_composer::AvailabilityStatusComposer;
+pub use complete_diffie_handshake_composer::CompleteDiffieHandshakeComposer;
+pub use init_diffie_handshake_composer::InitDiffieHandshakeComposer;
+pub use ping_composer::PingComposer;
+pub use secure_login_ok_composer::SecureLoginOKComposer;
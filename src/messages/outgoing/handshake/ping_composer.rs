@@ -0,0 +1,12 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Asks the client for a pong, see `IdleTimeoutHandler`
+pub struct PingComposer;
+
+impl MessageComposer for PingComposer {
+    fn compose(&self) -> ServerMessage {
+        ServerMessage::new(Outgoing::PingComposer.get_header())
+    }
+}
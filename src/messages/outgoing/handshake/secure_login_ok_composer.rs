@@ -0,0 +1,12 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// The SSO ticket was accepted, the client leaves the loading screen
+pub struct SecureLoginOKComposer;
+
+impl MessageComposer for SecureLoginOKComposer {
+    fn compose(&self) -> ServerMessage {
+        ServerMessage::new(Outgoing::SecureLoginOKComposer.get_header())
+    }
+}
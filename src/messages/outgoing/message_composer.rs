@@ -0,0 +1,6 @@
+use crate::messages::server_message::ServerMessage;
+
+/// Builds one kind of outgoing packet, the result goes to `GameClient::send_response`
+pub trait MessageComposer {
+    fn compose(&self) -> ServerMessage;
+}
@@ -28,8 +28,6 @@ pub mod unknown;
 pub mod users;
 pub mod wired;
 
-// Re-export the outgoing.rs file
-mod message_composer;
-
-// Export any important items from this module
-// pub use self::message_composer::*;
\ No newline at end of file
+// The header table and the shared trait
+pub mod outgoing_headers;
+pub mod message_composer;
\ No newline at end of file
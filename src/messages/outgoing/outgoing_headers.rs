@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use once_cell::sync::OnceCell;
+
+use crate::messages::packet_names::outgoing_packets;
+
+// Every packet the server can send: name = header id in the default revision
+outgoing_packets! {
+    InitDiffieHandshakeComposer = 1347,
+    CompleteDiffieHandshakeComposer = 3885,
+    PingComposer = 3928,
+    SecureLoginOKComposer = 2491,
+    AvailabilityStatusComposer = 2033,
+    GenericAlertComposer = 3801,
+    HotelWillCloseInMinutesComposer = 1050,
+    UserCreditsComposer = 3475,
+    UserPointsComposer = 2275,
+    AddUserBadgeComposer = 2493,
+    UserCurrencyComposer = 2018,
+    UserHomeRoomComposer = 2875,
+    UserPermissionsComposer = 411,
+    CatalogUpdatedComposer = 1866,
+    RoomOpenComposer = 758,
+    RoomModelComposer = 2031,
+    HeightMapComposer = 2753,
+    FloorHeightMapComposer = 1301,
+    RoomUsersComposer = 374,
+    UserUpdateComposer = 1640,
+    RoomUserRemoveComposer = 2661,
+}
+
+/// Header ids of the loaded revision, see `PacketManager::load`
+static HEADERS: OnceCell<HashMap<Outgoing, u16>> = OnceCell::new();
+
+impl Outgoing {
+    /// Header id in the loaded revision, or the default one before a revision is loaded
+    pub fn get_header(&self) -> u16 {
+        HEADERS.get()
+            .and_then(|headers| headers.get(self).copied())
+            .unwrap_or(self.get_default_header() as u16)
+    }
+
+    /// Installs the header ids composers use, only the first call has an effect
+    pub fn set_headers(headers: HashMap<Outgoing, u16>) -> bool {
+        HEADERS.set(headers).is_ok()
+    }
+}
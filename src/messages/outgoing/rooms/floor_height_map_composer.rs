@@ -0,0 +1,30 @@
+use crate::habbohotel::rooms::RoomLayout;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// The heightmap the client draws the floor from
+pub struct FloorHeightMapComposer {
+    relative_map: String,
+    wall_height: i32,
+}
+
+impl FloorHeightMapComposer {
+    /// A wall height of -1 lets the client pick it
+    pub fn new(layout: &RoomLayout, wall_height: i32) -> Self {
+        Self {
+            relative_map: layout.get_relative_map(),
+            wall_height,
+        }
+    }
+}
+
+impl MessageComposer for FloorHeightMapComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::FloorHeightMapComposer.get_header());
+        response.append_bool(true);
+        response.append_int(self.wall_height);
+        response.append_string(&self.relative_map);
+        response
+    }
+}
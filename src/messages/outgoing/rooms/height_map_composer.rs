@@ -0,0 +1,31 @@
+use crate::habbohotel::rooms::RoomLayout;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Stack heights of every tile, row by row
+pub struct HeightMapComposer {
+    map_size_x: i16,
+    heights: Vec<i16>,
+}
+
+impl HeightMapComposer {
+    pub fn new(layout: &RoomLayout) -> Self {
+        Self {
+            map_size_x: layout.get_map_size_x(),
+            heights: layout.get_tiles().iter().map(|tile| tile.get_relative_height()).collect(),
+        }
+    }
+}
+
+impl MessageComposer for HeightMapComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::HeightMapComposer.get_header());
+        response.append_int(self.map_size_x as i32);
+        response.append_int(self.heights.len() as i32);
+        for height in &self.heights {
+            response.append_short(*height);
+        }
+        response
+    }
+}
@@ -0,0 +1,10 @@
+pub mod floor_height_map_composer;
+pub mod height_map_composer;
+pub mod room_model_composer;
+pub mod room_open_composer;
+pub mod users;
+
+pub use floor_height_map_composer::FloorHeightMapComposer;
+pub use height_map_composer::HeightMapComposer;
+pub use room_model_composer::RoomModelComposer;
+pub use room_open_composer::RoomOpenComposer;
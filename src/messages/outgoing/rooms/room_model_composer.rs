@@ -0,0 +1,27 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// The model of the room being entered, the client asks for the heightmap next
+pub struct RoomModelComposer {
+    model: String,
+    room_id: i32,
+}
+
+impl RoomModelComposer {
+    pub fn new(model: &str, room_id: i32) -> Self {
+        Self {
+            model: model.to_string(),
+            room_id,
+        }
+    }
+}
+
+impl MessageComposer for RoomModelComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::RoomModelComposer.get_header());
+        response.append_string(&self.model);
+        response.append_int(self.room_id);
+        response
+    }
+}
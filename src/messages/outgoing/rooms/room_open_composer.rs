@@ -0,0 +1,12 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Tells the client it may enter the room it asked for
+pub struct RoomOpenComposer;
+
+impl MessageComposer for RoomOpenComposer {
+    fn compose(&self) -> ServerMessage {
+        ServerMessage::new(Outgoing::RoomOpenComposer.get_header())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod room_user_remove_composer;
+pub mod room_users_composer;
+pub mod user_update_composer;
+
+pub use room_user_remove_composer::RoomUserRemoveComposer;
+pub use room_users_composer::RoomUsersComposer;
+pub use user_update_composer::UserUpdateComposer;
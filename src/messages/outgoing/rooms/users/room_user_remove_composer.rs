@@ -0,0 +1,23 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// A unit left the room
+pub struct RoomUserRemoveComposer {
+    unit_id: i32,
+}
+
+impl RoomUserRemoveComposer {
+    pub fn new(unit_id: i32) -> Self {
+        Self { unit_id }
+    }
+}
+
+impl MessageComposer for RoomUserRemoveComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::RoomUserRemoveComposer.get_header());
+        // The client expects the id as a string
+        response.append_string(&self.unit_id.to_string());
+        response
+    }
+}
@@ -0,0 +1,81 @@
+use crate::habbohotel::rooms::room_unit::format_height;
+use crate::habbohotel::rooms::RoomUnit;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+struct RoomUserData {
+    user_id: i32,
+    username: String,
+    motto: String,
+    look: String,
+    unit_id: i32,
+    x: i16,
+    y: i16,
+    z: f64,
+    body_rotation: i32,
+    gender: String,
+    achievement_score: i32,
+}
+
+/// Users that appear in the room, with how they look
+pub struct RoomUsersComposer {
+    users: Vec<RoomUserData>,
+}
+
+impl RoomUsersComposer {
+    pub fn new(units: &[&RoomUnit]) -> Self {
+        let users = units.iter()
+            .map(|unit| {
+                let habbo = unit.get_habbo();
+                let info = habbo.get_info();
+
+                RoomUserData {
+                    user_id: info.id,
+                    username: info.username.clone(),
+                    motto: info.motto.clone(),
+                    look: info.look.clone(),
+                    unit_id: unit.get_id(),
+                    x: unit.get_x(),
+                    y: unit.get_y(),
+                    z: unit.get_z(),
+                    body_rotation: unit.get_body_rotation().to_value(),
+                    gender: info.gender.to_lowercase(),
+                    achievement_score: habbo.get_stats().achievement_score,
+                }
+            })
+            .collect();
+
+        Self { users }
+    }
+}
+
+impl MessageComposer for RoomUsersComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::RoomUsersComposer.get_header());
+        response.append_int(self.users.len() as i32);
+        for user in &self.users {
+            response.append_int(user.user_id);
+            response.append_string(&user.username);
+            response.append_string(&user.motto);
+            response.append_string(&user.look);
+            response.append_int(user.unit_id);
+            response.append_int(user.x as i32);
+            response.append_int(user.y as i32);
+            response.append_string(&format_height(user.z));
+            response.append_int(user.body_rotation);
+            // 1 is a user, pets and bots have their own types
+            response.append_int(1);
+            response.append_string(&user.gender);
+            // No group
+            response.append_int(-1);
+            response.append_int(-1);
+            response.append_string("");
+            // Swim figure
+            response.append_string("");
+            response.append_int(user.achievement_score);
+            response.append_bool(false);
+        }
+        response
+    }
+}
@@ -0,0 +1,67 @@
+use crate::habbohotel::rooms::room_unit::format_height;
+use crate::habbohotel::rooms::{RoomUnit, RoomUnitStatus};
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+struct UserUpdateData {
+    unit_id: i32,
+    x: i16,
+    y: i16,
+    z: f64,
+    head_rotation: i32,
+    body_rotation: i32,
+    status: String,
+}
+
+/// Positions, rotations and statuses of room units, every unit that changed in a tick at once
+pub struct UserUpdateComposer {
+    units: Vec<UserUpdateData>,
+}
+
+impl UserUpdateComposer {
+    pub fn new(units: &[&RoomUnit]) -> Self {
+        let units = units.iter()
+            .map(|unit| {
+                // A walking unit is shown on the tile it walks from, `mv` has the one it walks to
+                let (x, y, z) = match unit.has_status(RoomUnitStatus::Move) {
+                    true => unit.get_previous_position(),
+                    false => (unit.get_x(), unit.get_y(), unit.get_z()),
+                };
+
+                UserUpdateData {
+                    unit_id: unit.get_id(),
+                    x,
+                    y,
+                    z,
+                    head_rotation: unit.get_head_rotation().to_value(),
+                    body_rotation: unit.get_body_rotation().to_value(),
+                    status: unit.get_status_string(),
+                }
+            })
+            .collect();
+
+        Self { units }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+}
+
+impl MessageComposer for UserUpdateComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserUpdateComposer.get_header());
+        response.append_int(self.units.len() as i32);
+        for unit in &self.units {
+            response.append_int(unit.unit_id);
+            response.append_int(unit.x as i32);
+            response.append_int(unit.y as i32);
+            response.append_string(&format_height(unit.z));
+            response.append_int(unit.head_rotation);
+            response.append_int(unit.body_rotation);
+            response.append_string(&unit.status);
+        }
+        response
+    }
+}
@@ -0,0 +1,24 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Puts a new badge in the user's inventory
+pub struct AddUserBadgeComposer {
+    badge_id: i32,
+    code: String,
+}
+
+impl AddUserBadgeComposer {
+    pub fn new(badge_id: i32, code: String) -> Self {
+        Self { badge_id, code }
+    }
+}
+
+impl MessageComposer for AddUserBadgeComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::AddUserBadgeComposer.get_header());
+        response.append_int(self.badge_id);
+        response.append_string(&self.code);
+        response
+    }
+}
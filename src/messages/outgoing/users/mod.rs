@@ -0,0 +1,13 @@
+pub mod add_user_badge_composer;
+pub mod user_credits_composer;
+pub mod user_currency_composer;
+pub mod user_home_room_composer;
+pub mod user_permissions_composer;
+pub mod user_points_composer;
+
+pub use add_user_badge_composer::AddUserBadgeComposer;
+pub use user_credits_composer::UserCreditsComposer;
+pub use user_currency_composer::UserCurrencyComposer;
+pub use user_home_room_composer::UserHomeRoomComposer;
+pub use user_permissions_composer::UserPermissionsComposer;
+pub use user_points_composer::UserPointsComposer;
@@ -0,0 +1,23 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// The user's credit balance
+pub struct UserCreditsComposer {
+    credits: i32,
+}
+
+impl UserCreditsComposer {
+    pub fn new(credits: i32) -> Self {
+        Self { credits }
+    }
+}
+
+impl MessageComposer for UserCreditsComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserCreditsComposer.get_header());
+        // The client expects a decimal number here
+        response.append_string(&format!("{}.0", self.credits));
+        response
+    }
+}
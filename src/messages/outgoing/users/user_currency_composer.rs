@@ -0,0 +1,26 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Every currency balance besides credits, see `UserPointsComposer` for a single update
+pub struct UserCurrencyComposer {
+    currencies: Vec<(i32, i32)>,
+}
+
+impl UserCurrencyComposer {
+    pub fn new(currencies: Vec<(i32, i32)>) -> Self {
+        Self { currencies }
+    }
+}
+
+impl MessageComposer for UserCurrencyComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserCurrencyComposer.get_header());
+        response.append_int(self.currencies.len() as i32);
+        for (currency_type, amount) in &self.currencies {
+            response.append_int(*currency_type);
+            response.append_int(*amount);
+        }
+        response
+    }
+}
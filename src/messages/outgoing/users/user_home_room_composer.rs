@@ -0,0 +1,27 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// The room the user starts in, 0 for the hotel view
+pub struct UserHomeRoomComposer {
+    home_room: i32,
+    room_to_enter: i32,
+}
+
+impl UserHomeRoomComposer {
+    pub fn new(home_room: i32, room_to_enter: i32) -> Self {
+        Self {
+            home_room,
+            room_to_enter,
+        }
+    }
+}
+
+impl MessageComposer for UserHomeRoomComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserHomeRoomComposer.get_header());
+        response.append_int(self.home_room);
+        response.append_int(self.room_to_enter);
+        response
+    }
+}
@@ -0,0 +1,30 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Club level and rank, the client shows staff tools based on them
+pub struct UserPermissionsComposer {
+    club_level: i32,
+    rank: i32,
+    ambassador: bool,
+}
+
+impl UserPermissionsComposer {
+    pub fn new(club_level: i32, rank: i32, ambassador: bool) -> Self {
+        Self {
+            club_level,
+            rank,
+            ambassador,
+        }
+    }
+}
+
+impl MessageComposer for UserPermissionsComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserPermissionsComposer.get_header());
+        response.append_int(self.club_level);
+        response.append_int(self.rank);
+        response.append_bool(self.ambassador);
+        response
+    }
+}
@@ -0,0 +1,30 @@
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::server_message::ServerMessage;
+
+/// Updates a single currency, type 0 are pixels, the other types are seasonal points
+pub struct UserPointsComposer {
+    amount: i32,
+    added: i32,
+    points_type: i32,
+}
+
+impl UserPointsComposer {
+    pub fn new(amount: i32, added: i32, points_type: i32) -> Self {
+        Self {
+            amount,
+            added,
+            points_type,
+        }
+    }
+}
+
+impl MessageComposer for UserPointsComposer {
+    fn compose(&self) -> ServerMessage {
+        let mut response = ServerMessage::new(Outgoing::UserPointsComposer.get_header());
+        response.append_int(self.amount);
+        response.append_int(self.added);
+        response.append_int(self.points_type);
+        response
+    }
+}
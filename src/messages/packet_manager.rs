@@ -1,20 +1,83 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use futures::FutureExt;
+use log::{debug, warn};
+use serde::Deserialize;
 
-use crate::messages::incoming::message_handler::MessageHandler;
-use crate::messages::incoming::incoming::Incoming;
-use crate::messages::outgoing::outgoing::Outgoing;
+use crate::core::error_log::{ErrorLog, ErrorType, PACKET_CONTEXT};
+use crate::habbohotel::game_enviroment::GameEnvironment;
+use crate::habbohotel::gameclients::GameClient;
+use crate::messages::client_message::ClientMessage;
+use crate::messages::i_callable::ICallable;
+use crate::messages::incoming::message_handler::{HandlerContext, MessageHandler};
+use crate::messages::incoming::incoming_headers::Incoming;
+use crate::messages::no_auth_message::NoAuthMessage;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+
+/// Header ids of a client revision by packet name, loaded from a JSON or TOML file:
+///
+/// ```toml
+/// [incoming]
+/// InitDiffieHandshakeEvent = 3110
+///
+/// [outgoing]
+/// InitDiffieHandshakeComposer = 1347
+/// ```
+///
+/// Packets that are left out keep their default header id.
+#[derive(Debug, Default, Deserialize)]
+pub struct PacketRevision {
+    #[serde(default)]
+    incoming: HashMap<String, i32>,
+    #[serde(default)]
+    outgoing: HashMap<String, i32>,
+}
+
+impl PacketRevision {
+    /// Reads a revision file, the extension decides between JSON and TOML
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read packet revision {}: {}", path, e))?;
+
+        if path.ends_with(".json") {
+            Self::from_json(&contents)
+        } else if path.ends_with(".toml") {
+            Self::from_toml(&contents)
+        } else {
+            Err(format!("Packet revision {} has to be a .json or .toml file", path))
+        }
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| format!("Invalid packet revision: {}", e))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        toml::from_str(contents).map_err(|e| format!("Invalid packet revision: {}", e))
+    }
+}
 
 /// The PacketManager handles the registration and management of packet handlers
-/// throughout the server application. It maps incoming packet IDs to their 
+/// throughout the server application. It maps incoming packet IDs to their
 /// respective handlers and provides utility functions for packet processing.
 pub struct PacketManager {
     /// Map of packet IDs to their corresponding handlers
     handlers: HashMap<i32, Arc<dyn MessageHandler + Send + Sync>>,
+    /// Packet IDs whose handler may run before the client logged in
+    no_auth: HashSet<i32>,
+    /// Hooks that run before the handler of a packet ID and can cancel it
+    callables: HashMap<i32, Vec<Arc<dyn ICallable>>>,
     /// Mapping of incoming packet IDs to their string names for debugging
     incoming_names: HashMap<i32, String>,
     /// Mapping of outgoing packet IDs to their string names for debugging
     outgoing_names: HashMap<i32, String>,
+    /// Header ids of the loaded revision
+    incoming_headers: HashMap<Incoming, i32>,
+    outgoing_headers: HashMap<Outgoing, i32>,
 }
 
 impl PacketManager {
@@ -22,62 +85,357 @@ impl PacketManager {
     pub fn new() -> Self {
         PacketManager {
             handlers: HashMap::new(),
+            no_auth: HashSet::new(),
+            callables: HashMap::new(),
             incoming_names: HashMap::new(),
             outgoing_names: HashMap::new(),
+            incoming_headers: HashMap::new(),
+            outgoing_headers: HashMap::new(),
+        }
+    }
+
+    /// Loads every packet declared in `incoming_headers.rs` and `outgoing_headers.rs` with the header ids of the
+    /// given revision. Fails when two packets of the same direction end up on the same header id.
+    pub fn load(&mut self, revision: &PacketRevision) -> Result<(), String> {
+        self.incoming_headers = Self::resolve_headers("incoming", Incoming::ALL, Incoming::get_name, Incoming::get_default_header, &revision.incoming)?;
+        self.outgoing_headers = Self::resolve_headers("outgoing", Outgoing::ALL, Outgoing::get_name, Outgoing::get_default_header, &revision.outgoing)?;
+
+        for (packet, header) in &self.incoming_headers {
+            self.handlers.insert(*header, packet.create_handler());
+            if packet.is_no_auth() {
+                self.no_auth.insert(*header);
+            }
+            self.incoming_names.insert(*header, packet.get_name().to_string());
+        }
+
+        for (packet, header) in &self.outgoing_headers {
+            self.outgoing_names.insert(*header, packet.get_name().to_string());
+        }
+
+        Ok(())
+    }
+
+    fn resolve_headers<P: Copy + Eq + Hash>(
+        direction: &str,
+        packets: &[P],
+        get_name: fn(&P) -> &'static str,
+        get_default_header: fn(&P) -> i32,
+        overrides: &HashMap<String, i32>,
+    ) -> Result<HashMap<P, i32>, String> {
+        for name in overrides.keys() {
+            if !packets.iter().any(|packet| get_name(packet) == name) {
+                warn!("Packet revision maps unknown {} packet {}", direction, name);
+            }
         }
+
+        let mut headers = HashMap::new();
+        let mut owners: HashMap<i32, &'static str> = HashMap::new();
+
+        for packet in packets {
+            let name = get_name(packet);
+            let header = overrides.get(name).copied().unwrap_or_else(|| get_default_header(packet));
+
+            if header < 0 || header > u16::MAX as i32 {
+                return Err(format!("The {} packet {} has an invalid header id {}", direction, name, header));
+            }
+
+            if let Some(other) = owners.insert(header, name) {
+                return Err(format!("The {} header id {} is used by both {} and {}", direction, header, other, name));
+            }
+
+            headers.insert(*packet, header);
+        }
+
+        Ok(headers)
     }
-    
-    /// Registers a message handler for a specific incoming packet ID
-    pub fn register<H>(&mut self, header: Incoming, handler: H) 
-    where 
-        H: MessageHandler + Send + Sync + 'static 
+
+    /// Registers a message handler for a specific incoming packet, replacing the one from the packet table.
+    /// The handler only runs for clients that logged in.
+    pub fn register<H>(&mut self, header: Incoming, handler: H)
+    where
+        H: MessageHandler + Send + Sync + 'static
     {
-        let header_id = header as i32;
+        let header_id = self.get_incoming_header(header);
         self.handlers.insert(header_id, Arc::new(handler));
+        self.no_auth.remove(&header_id);
+    }
+
+    /// Registers a message handler that may run before the client logged in
+    pub fn register_no_auth<H>(&mut self, header: Incoming, handler: H)
+    where
+        H: MessageHandler + NoAuthMessage + Send + Sync + 'static
+    {
+        let header_id = self.get_incoming_header(header);
+        self.handlers.insert(header_id, Arc::new(handler));
+        self.no_auth.insert(header_id);
+    }
+
+    /// Registers a hook that runs before the handler of a packet, see `ICallable`
+    pub fn register_callable<C>(&mut self, header: Incoming, callable: C)
+    where
+        C: ICallable + 'static
+    {
+        let header_id = self.get_incoming_header(header);
+        self.callables.entry(header_id).or_default().push(Arc::new(callable));
+    }
+
+    /// Passes a packet to the handler registered for its header.
+    /// Packets from clients that didn't log in yet only reach `NoAuthMessage` handlers.
+    pub async fn handle(&self, message: ClientMessage, client: Arc<GameClient>) -> io::Result<()> {
+        self.handle_with(message, client, crate::get_game_environment()).await
+    }
+
+    async fn handle_with(&self, message: ClientMessage, client: Arc<GameClient>, environment: Arc<GameEnvironment>) -> io::Result<()> {
+        let header = message.get_header() as i32;
+
+        let handler = match self.get_handler(header) {
+            Some(handler) => handler,
+            None => {
+                debug!("No handler registered for packet {}", header);
+                return Ok(());
+            }
+        };
+
+        if !client.is_authenticated() && !self.no_auth.contains(&header) {
+            warn!("Dropping packet {} ({}) from client {} that isn't logged in",
+                header,
+                self.get_incoming_packet_name(header).map(|name| name.as_str()).unwrap_or("Unknown"),
+                client.get_address());
+            return Ok(());
+        }
+
+        let user_id = client.get_user_id();
+        let mut context = HandlerContext::new(client, message, environment);
+
+        if let Some(callables) = self.callables.get(&header) {
+            if !callables.iter().all(|callable| callable.call(&context)) {
+                debug!("Packet {} was cancelled", header);
+                return Ok(());
+            }
+        }
+
+        // Errors and panics of the handler are tagged with the user and the packet. A panic
+        // becomes an error as well, so the client still gets unregistered.
+        let handled = AssertUnwindSafe(PACKET_CONTEXT.scope((user_id, header), handler.handle(&mut context)))
+            .catch_unwind().await;
+
+        let (error, e) = match handled {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => {
+                let error_type = match e.downcast_ref::<sqlx::Error>() {
+                    Some(_) => ErrorType::SqlError,
+                    None => ErrorType::PacketError,
+                };
+                (ErrorLog::from_error(error_type, e.as_ref()), io::Error::other(e))
+            }
+            Err(payload) => {
+                let error = ErrorLog::from_handler_panic(payload.as_ref());
+                let e = io::Error::other(format!("Handler of packet {} panicked: {}", header, error.get_message()));
+                (error, e)
+            }
+        };
+
+        if let Some(error_logger) = crate::get_error_logger() {
+            error_logger.report(error.with_packet(user_id, header));
+        }
+        Err(e)
     }
-    
+
     /// Gets a handler for a specific packet ID, if registered
     pub fn get_handler(&self, header: i32) -> Option<Arc<dyn MessageHandler + Send + Sync>> {
         self.handlers.get(&header).cloned()
     }
-    
-    /// Initializes the packet name mappings for debugging purposes
-    pub fn initialize_packet_names(&mut self) {
-        // To populate packet name mappings, you'll need to manually map
-        // the enum variants to their names. In a real implementation,
-        // you could use a macro to generate this code automatically.
-        
-        // Example implementation - you'll need to adapt this to your actual enums
-        // For Incoming packets
-        self.register_incoming_names();
-        
-        // For Outgoing packets
-        self.register_outgoing_names();
-    }
-    
-    /// Helper method to register incoming packet names
-    fn register_incoming_names(&mut self) {
-        // This is a placeholder - you'll need to populate with your actual enum variants
-        // Example: 
-        // self.incoming_names.insert(Incoming::Login as i32, "Login".to_string());
-        // self.incoming_names.insert(Incoming::GetRooms as i32, "GetRooms".to_string());
-    }
-    
-    /// Helper method to register outgoing packet names
-    fn register_outgoing_names(&mut self) {
-        // This is a placeholder - you'll need to populate with your actual enum variants
-        // Example:
-        // self.outgoing_names.insert(Outgoing::AuthOk as i32, "AuthOk".to_string());
-        // self.outgoing_names.insert(Outgoing::RoomList as i32, "RoomList".to_string());
-    }
-    
+
+    /// Header id of an incoming packet in the loaded revision
+    pub fn get_incoming_header(&self, packet: Incoming) -> i32 {
+        self.incoming_headers.get(&packet).copied().unwrap_or_else(|| packet.get_default_header())
+    }
+
+    /// Header id of an incoming packet by its name, like `RoomUserWalkEvent`
+    pub fn get_incoming_header_by_name(&self, name: &str) -> Option<i32> {
+        self.incoming_headers.iter()
+            .find(|(packet, _)| packet.get_name() == name)
+            .map(|(_, header)| *header)
+    }
+
+    /// Header ids of all outgoing packets in the loaded revision, see `Outgoing::set_headers`
+    pub fn get_outgoing_headers(&self) -> HashMap<Outgoing, u16> {
+        self.outgoing_headers.iter()
+            .map(|(packet, header)| (*packet, *header as u16))
+            .collect()
+    }
+
     /// Gets the name of an incoming packet by ID
     pub fn get_incoming_packet_name(&self, header: i32) -> Option<&String> {
         self.incoming_names.get(&header)
     }
-    
+
     /// Gets the name of an outgoing packet by ID
     pub fn get_outgoing_packet_name(&self, header: i32) -> Option<&String> {
         self.outgoing_names.get(&header)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use async_trait::async_trait;
+    use bytes::BytesMut;
+
+    use crate::database::repositories::Repositories;
+    use crate::util::fixtures;
+
+    #[test]
+    fn test_default_revision() {
+        let mut packet_manager = PacketManager::new();
+        packet_manager.load(&PacketRevision::default()).unwrap();
+
+        for packet in Incoming::ALL {
+            let header = packet.get_default_header();
+            assert!(packet_manager.get_handler(header).is_some());
+            assert_eq!(packet_manager.get_incoming_packet_name(header).unwrap(), packet.get_name());
+        }
+
+        for packet in Outgoing::ALL {
+            let header = packet.get_default_header();
+            assert_eq!(packet_manager.get_outgoing_packet_name(header).unwrap(), packet.get_name());
+        }
+    }
+
+    #[test]
+    fn test_revision_remaps_headers() {
+        let revision = PacketRevision::from_toml(r#"
+            [incoming]
+            CompleteDiffieHandshakeEvent = 1000
+            NotAPacket = 1
+
+            [outgoing]
+            InitDiffieHandshakeComposer = 2000
+        "#).unwrap();
+
+        let mut packet_manager = PacketManager::new();
+        packet_manager.load(&revision).unwrap();
+
+        assert!(packet_manager.get_handler(1000).is_some());
+        assert!(packet_manager.get_handler(Incoming::CompleteDiffieHandshakeEvent.get_default_header()).is_none());
+        assert_eq!(packet_manager.get_incoming_header(Incoming::CompleteDiffieHandshakeEvent), 1000);
+        assert_eq!(packet_manager.get_outgoing_packet_name(2000).unwrap(), "InitDiffieHandshakeComposer");
+        assert_eq!(packet_manager.get_outgoing_headers()[&Outgoing::InitDiffieHandshakeComposer], 2000);
+    }
+
+    #[test]
+    fn test_duplicate_header_fails() {
+        let revision = PacketRevision::from_json(&format!(
+            r#"{{ "incoming": {{ "CompleteDiffieHandshakeEvent": {} }} }}"#,
+            Incoming::InitDiffieHandshakeEvent.get_default_header()
+        )).unwrap();
+
+        let error = PacketManager::new().load(&revision).unwrap_err();
+        assert!(error.contains("InitDiffieHandshakeEvent") && error.contains("CompleteDiffieHandshakeEvent"));
+    }
+
+    #[test]
+    fn test_no_auth_handlers() {
+        let mut packet_manager = PacketManager::new();
+        packet_manager.load(&PacketRevision::default()).unwrap();
+
+        assert!(packet_manager.no_auth.contains(&Incoming::InitDiffieHandshakeEvent.get_default_header()));
+        assert!(packet_manager.no_auth.contains(&Incoming::CompleteDiffieHandshakeEvent.get_default_header()));
+    }
+
+    #[derive(Default)]
+    struct CountingHandler {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl MessageHandler for CountingHandler {
+        async fn handle(&self, _context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    impl NoAuthMessage for CountingHandler {}
+
+    struct PanickingHandler;
+
+    #[async_trait]
+    impl MessageHandler for PanickingHandler {
+        async fn handle(&self, _context: &mut HandlerContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+            panic!("Room not found");
+        }
+    }
+
+    impl NoAuthMessage for PanickingHandler {}
+
+    struct Cancel;
+
+    impl ICallable for Cancel {
+        fn call(&self, _context: &HandlerContext) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_packets_are_dropped() {
+        let auth_calls = Arc::new(AtomicUsize::new(0));
+        let no_auth_calls = Arc::new(AtomicUsize::new(0));
+
+        let mut packet_manager = PacketManager::new();
+        packet_manager.register(Incoming::InitDiffieHandshakeEvent, CountingHandler { calls: auth_calls.clone() });
+        packet_manager.register_no_auth(Incoming::CompleteDiffieHandshakeEvent, CountingHandler { calls: no_auth_calls.clone() });
+
+        let (client, _receiver) = fixtures::client(1);
+        let environment = Arc::new(GameEnvironment::new(Repositories::in_memory()));
+
+        let send = |packet: Incoming| {
+            let message = ClientMessage::new(packet.get_default_header() as u16, BytesMut::new());
+            packet_manager.handle_with(message, client.clone(), environment.clone())
+        };
+
+        send(Incoming::InitDiffieHandshakeEvent).await.unwrap();
+        send(Incoming::CompleteDiffieHandshakeEvent).await.unwrap();
+        assert_eq!(auth_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(no_auth_calls.load(Ordering::SeqCst), 1);
+
+        client.set_authenticated(true);
+        send(Incoming::InitDiffieHandshakeEvent).await.unwrap();
+        assert_eq!(auth_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_callable_cancels_packet() {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut packet_manager = PacketManager::new();
+        packet_manager.register_no_auth(Incoming::InitDiffieHandshakeEvent, CountingHandler { calls: calls.clone() });
+        packet_manager.register_callable(Incoming::InitDiffieHandshakeEvent, Cancel);
+
+        let (client, _receiver) = fixtures::client(1);
+        let message = ClientMessage::new(Incoming::InitDiffieHandshakeEvent.get_default_header() as u16, BytesMut::new());
+        packet_manager.handle_with(message, client, Arc::new(GameEnvironment::new(Repositories::in_memory()))).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_handler_panic_becomes_error() {
+        let mut packet_manager = PacketManager::new();
+        packet_manager.register_no_auth(Incoming::InitDiffieHandshakeEvent, PanickingHandler);
+
+        let (client, _receiver) = fixtures::client(1);
+        let message = ClientMessage::new(Incoming::InitDiffieHandshakeEvent.get_default_header() as u16, BytesMut::new());
+        let error = packet_manager.handle_with(message, client, Arc::new(GameEnvironment::new(Repositories::in_memory()))).await.unwrap_err();
+
+        assert!(error.to_string().ends_with("panicked: Room not found"));
+    }
+
+    #[test]
+    fn test_invalid_header_fails() {
+        let revision = PacketRevision::from_json(r#"{ "outgoing": { "CompleteDiffieHandshakeComposer": 70000 } }"#).unwrap();
+        assert!(PacketManager::new().load(&revision).is_err());
+    }
+}
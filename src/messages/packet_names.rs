@@ -0,0 +1,77 @@
+//! Macros that turn the packet tables in `incoming_headers.rs` and `outgoing_headers.rs` into the
+//! `Incoming` and `Outgoing` enums. Every packet is declared exactly once, with its
+//! name, the header id of the default revision and, for incoming packets, its handler.
+
+/// Declares the incoming packets: `Name = header => HandlerType,`.
+/// Handlers are created through `Default` when the `PacketManager` loads,
+/// the ones that implement `NoAuthMessage` are allowed before the login.
+macro_rules! incoming_packets {
+    ($($name:ident = $header:literal => $handler:ty,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Incoming {
+            $($name,)*
+        }
+
+        impl Incoming {
+            pub const ALL: &'static [Incoming] = &[$(Incoming::$name,)*];
+
+            pub fn get_name(&self) -> &'static str {
+                match self {
+                    $(Incoming::$name => stringify!($name),)*
+                }
+            }
+
+            /// Header id in the default revision, a revision file can map the packet elsewhere
+            pub fn get_default_header(&self) -> i32 {
+                match self {
+                    $(Incoming::$name => $header,)*
+                }
+            }
+
+            /// Whether the handler implements `NoAuthMessage` and may run before the client logged in
+            pub fn is_no_auth(&self) -> bool {
+                use crate::messages::no_auth_message::{AuthProbe, NoAuthCheck, NoAuthProbe};
+
+                match self {
+                    $(Incoming::$name => (&&NoAuthCheck::<$handler>::new()).is_no_auth(),)*
+                }
+            }
+
+            pub fn create_handler(&self) -> std::sync::Arc<dyn crate::messages::incoming::message_handler::MessageHandler + Send + Sync> {
+                match self {
+                    $(Incoming::$name => std::sync::Arc::new(<$handler>::default()),)*
+                }
+            }
+        }
+    };
+}
+
+/// Declares the outgoing packets: `Name = header,`
+macro_rules! outgoing_packets {
+    ($($name:ident = $header:literal,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Outgoing {
+            $($name,)*
+        }
+
+        impl Outgoing {
+            pub const ALL: &'static [Outgoing] = &[$(Outgoing::$name,)*];
+
+            pub fn get_name(&self) -> &'static str {
+                match self {
+                    $(Outgoing::$name => stringify!($name),)*
+                }
+            }
+
+            /// Header id in the default revision, a revision file can map the packet elsewhere
+            pub fn get_default_header(&self) -> i32 {
+                match self {
+                    $(Outgoing::$name => $header,)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use incoming_packets;
+pub(crate) use outgoing_packets;
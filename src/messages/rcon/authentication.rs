@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use log::warn;
+
+use crate::core::configuration_manager::ConfigurationManager;
+
+/// The addresses allowed to connect to the RCON server, from `rcon.allowed`.
+/// Anything that isn't listed is disconnected before it can send a command.
+#[derive(Debug, Clone)]
+pub struct RconAllowlist {
+    addresses: HashSet<IpAddr>,
+}
+
+impl RconAllowlist {
+    pub fn new(addresses: impl IntoIterator<Item = IpAddr>) -> Self {
+        Self {
+            addresses: addresses.into_iter().map(Self::normalize).collect(),
+        }
+    }
+
+    /// Only the local machine may connect when `rcon.allowed` isn't set
+    pub fn load(config: &ConfigurationManager) -> Self {
+        let entries = config.get_list("rcon.allowed").unwrap_or_else(|_| vec!["127.0.0.1".to_string()]);
+        Self::from_entries(entries.iter().map(String::as_str))
+    }
+
+    /// Parses a list of addresses, invalid ones are skipped
+    pub fn from_entries<'a>(entries: impl Iterator<Item = &'a str>) -> Self {
+        let addresses = entries
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .filter_map(|address| match address.parse::<IpAddr>() {
+                Ok(address) => Some(address),
+                Err(_) => {
+                    warn!("Ignoring invalid address '{}' in rcon.allowed", address);
+                    None
+                }
+            });
+
+        Self::new(addresses)
+    }
+
+    pub fn is_allowed(&self, address: IpAddr) -> bool {
+        self.addresses.contains(&Self::normalize(address))
+    }
+
+    // A dual stack listener reports IPv4 clients as ::ffff:a.b.c.d
+    fn normalize(address: IpAddr) -> IpAddr {
+        match address {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(address),
+            address => address,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist = RconAllowlist::from_entries(["127.0.0.1", " 10.0.0.5", "not an address", "::1"].into_iter());
+
+        assert!(allowlist.is_allowed("127.0.0.1".parse().unwrap()));
+        assert!(allowlist.is_allowed("10.0.0.5".parse().unwrap()));
+        assert!(allowlist.is_allowed("::1".parse().unwrap()));
+        assert!(allowlist.is_allowed("::ffff:10.0.0.5".parse().unwrap()));
+        assert!(!allowlist.is_allowed("10.0.0.6".parse().unwrap()));
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::outgoing::generic::GenericAlertComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct AlertUserData {
+    user_id: i32,
+    message: String,
+}
+
+/// Shows an alert to a user that is online
+pub struct AlertUser;
+
+#[async_trait]
+impl RconCommand for AlertUser {
+    fn get_key(&self) -> &'static str {
+        "alertuser"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: AlertUserData = parse_data(data)?;
+
+        let client = context.get_game_client_manager().get_client_by_user_id(data.user_id)
+            .ok_or_else(|| RconResponse::habbo_not_found(data.user_id))?;
+        client.send_response(GenericAlertComposer::new(data.message).compose());
+
+        Ok(format!("Alerted user {}", data.user_id))
+    }
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct DisconnectUserData {
+    user_id: i32,
+}
+
+/// Kicks a user off the hotel
+pub struct DisconnectUser;
+
+#[async_trait]
+impl RconCommand for DisconnectUser {
+    fn get_key(&self) -> &'static str {
+        "disconnect"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: DisconnectUserData = parse_data(data)?;
+
+        let client = context.get_game_client_manager().get_client_by_user_id(data.user_id)
+            .ok_or_else(|| RconResponse::habbo_not_found(data.user_id))?;
+        client.disconnect();
+
+        Ok(format!("Disconnected user {}", data.user_id))
+    }
+}
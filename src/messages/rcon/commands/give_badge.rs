@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::users::AddUserBadgeComposer;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct GiveBadgeData {
+    user_id: i32,
+    badge: String,
+}
+
+/// Gives a badge to a user, online or not
+pub struct GiveBadge;
+
+#[async_trait]
+impl RconCommand for GiveBadge {
+    fn get_key(&self) -> &'static str {
+        "givebadge"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: GiveBadgeData = parse_data(data)?;
+        if data.badge.is_empty() {
+            return Err(RconResponse::error("No badge code given"));
+        }
+
+        let users = context.get_repositories().get_users();
+
+        if users.get_user(data.user_id).await?.is_none() {
+            return Err(RconResponse::habbo_not_found(data.user_id));
+        }
+
+        if users.has_badge(data.user_id, &data.badge).await? {
+            return Err(RconResponse::error(format!("User {} already has badge {}", data.user_id, data.badge)));
+        }
+
+        let badge_id = users.add_badge(data.user_id, &data.badge).await?;
+
+        if let Some(client) = context.get_game_client_manager().get_client_by_user_id(data.user_id) {
+            client.send_response(AddUserBadgeComposer::new(badge_id, data.badge.clone()).compose());
+        }
+
+        Ok(format!("Gave badge {} to user {}", data.badge, data.user_id))
+    }
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::users::UserCreditsComposer;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct GiveCreditsData {
+    user_id: i32,
+    credits: i32,
+}
+
+/// Adds credits to a user, online or not. A negative amount takes them away.
+pub struct GiveCredits;
+
+#[async_trait]
+impl RconCommand for GiveCredits {
+    fn get_key(&self) -> &'static str {
+        "givecredits"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: GiveCreditsData = parse_data(data)?;
+        let credits = context.get_repositories().get_users()
+            .add_credits(data.user_id, data.credits)
+            .await?
+            .ok_or_else(|| RconResponse::habbo_not_found(data.user_id))?;
+
+        if let Some(habbo) = context.get_habbo_manager().get_habbo(data.user_id) {
+            habbo.get_info_mut().credits = credits;
+            habbo.get_client().send_response(UserCreditsComposer::new(credits).compose());
+        }
+
+        Ok(format!("Gave {} credits to user {}", data.credits, data.user_id))
+    }
+}
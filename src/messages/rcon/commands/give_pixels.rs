@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::rcon::commands::give_points::give_points;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct GivePixelsData {
+    user_id: i32,
+    pixels: i32,
+}
+
+/// Adds pixels to a user, they are points of type 0
+pub struct GivePixels;
+
+#[async_trait]
+impl RconCommand for GivePixels {
+    fn get_key(&self) -> &'static str {
+        "givepixels"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: GivePixelsData = parse_data(data)?;
+
+        give_points(context, data.user_id, data.pixels, 0).await?;
+
+        Ok(format!("Gave {} pixels to user {}", data.pixels, data.user_id))
+    }
+}
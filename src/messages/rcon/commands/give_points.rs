@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::outgoing::users::UserPointsComposer;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct GivePointsData {
+    user_id: i32,
+    points: i32,
+    #[serde(rename = "type")]
+    points_type: Option<i32>,
+}
+
+/// Adds points of any currency type to a user, online or not.
+/// Without a type the seasonal currency from `seasonal.primary.type` is used.
+pub struct GivePoints;
+
+#[async_trait]
+impl RconCommand for GivePoints {
+    fn get_key(&self) -> &'static str {
+        "givepoints"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: GivePointsData = parse_data(data)?;
+        let points_type = data.points_type
+            .unwrap_or_else(|| crate::get_config().get_int("seasonal.primary.type").unwrap_or(5));
+
+        give_points(context, data.user_id, data.points, points_type).await?;
+
+        Ok(format!("Gave {} points of type {} to user {}", data.points, points_type, data.user_id))
+    }
+}
+
+/// Adds to a `users_currency` balance and updates the cached balance and the client if the user is online
+pub(crate) async fn give_points(context: &RconContext, user_id: i32, amount: i32, points_type: i32) -> Result<(), RconResponse> {
+    let users = context.get_repositories().get_users();
+
+    if users.get_user(user_id).await?.is_none() {
+        return Err(RconResponse::habbo_not_found(user_id));
+    }
+
+    let total = users.add_currency(user_id, points_type, amount).await?;
+
+    if let Some(habbo) = context.get_habbo_manager().get_habbo(user_id) {
+        habbo.get_info_mut().currencies.insert(points_type, total);
+        habbo.get_client().send_response(UserPointsComposer::new(total, amount, points_type).compose());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::outgoing::generic::GenericAlertComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::rcon::commands::rcon_command::{parse_data, RconCommand, RconContext, RconResponse};
+
+#[derive(Deserialize)]
+struct HotelAlertData {
+    message: String,
+}
+
+/// Shows an alert to everyone that is online
+pub struct HotelAlert;
+
+#[async_trait]
+impl RconCommand for HotelAlert {
+    fn get_key(&self) -> &'static str {
+        "hotelalert"
+    }
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse> {
+        let data: HotelAlertData = parse_data(data)?;
+
+        let game_client_manager = context.get_game_client_manager();
+        game_client_manager.broadcast(GenericAlertComposer::new(data.message).compose());
+
+        Ok(format!("Alerted {} clients", game_client_manager.get_online_count()))
+    }
+}
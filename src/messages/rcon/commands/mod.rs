@@ -0,0 +1,24 @@
+// The trait and the shared types
+pub mod rcon_command;
+
+// Commands
+pub mod alert_user;
+pub mod disconnect_user;
+pub mod give_badge;
+pub mod give_credits;
+pub mod give_pixels;
+pub mod give_points;
+pub mod hotel_alert;
+pub mod update_catalog;
+pub mod update_texts;
+
+pub use rcon_command::{RconCommand, RconContext, RconResponse};
+pub use alert_user::AlertUser;
+pub use disconnect_user::DisconnectUser;
+pub use give_badge::GiveBadge;
+pub use give_credits::GiveCredits;
+pub use give_pixels::GivePixels;
+pub use give_points::GivePoints;
+pub use hotel_alert::HotelAlert;
+pub use update_catalog::UpdateCatalog;
+pub use update_texts::UpdateTexts;
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use log::error;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::database::repositories::Repositories;
+use crate::habbohotel::game_enviroment::GameEnvironment;
+use crate::habbohotel::gameclients::GameClientManager;
+use crate::habbohotel::users::HabboManager;
+
+/// What every RCON command gets to work with
+pub struct RconContext {
+    game_client_manager: Arc<GameClientManager>,
+    game_environment: Arc<GameEnvironment>,
+}
+
+impl RconContext {
+    pub fn new(game_client_manager: Arc<GameClientManager>, game_environment: Arc<GameEnvironment>) -> Self {
+        Self { game_client_manager, game_environment }
+    }
+
+    pub fn get_game_client_manager(&self) -> &Arc<GameClientManager> {
+        &self.game_client_manager
+    }
+
+    pub fn get_repositories(&self) -> &Repositories {
+        self.game_environment.get_repositories()
+    }
+
+    /// Users that are logged in, their cached state has to follow every change made to the database
+    pub fn get_habbo_manager(&self) -> &HabboManager {
+        self.game_environment.get_habbo_manager()
+    }
+}
+
+/// The answer to every RCON request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RconResponse {
+    status: i32,
+    message: String,
+}
+
+impl RconResponse {
+    pub const STATUS_OK: i32 = 0;
+    pub const STATUS_ERROR: i32 = 1;
+    pub const HABBO_NOT_FOUND: i32 = 2;
+    pub const SYSTEM_ERROR: i32 = 4;
+
+    pub fn new(status: i32, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self::new(Self::STATUS_OK, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Self::STATUS_ERROR, message)
+    }
+
+    pub fn habbo_not_found(user_id: i32) -> Self {
+        Self::new(Self::HABBO_NOT_FOUND, format!("User {} not found", user_id))
+    }
+
+    pub fn get_status(&self) -> i32 {
+        self.status
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl From<sqlx::Error> for RconResponse {
+    fn from(error: sqlx::Error) -> Self {
+        // The CMS only needs to know it failed, the details belong in our log
+        error!("RCON command failed with a database error: {}", error);
+        Self::new(Self::SYSTEM_ERROR, "Database error")
+    }
+}
+
+/// A command the CMS can run through the RCON server.
+/// On success the command returns the message for the response, errors are returned as they are.
+#[async_trait]
+pub trait RconCommand: Send + Sync {
+    /// The `key` requests use to call this command
+    fn get_key(&self) -> &'static str;
+
+    async fn handle(&self, context: &RconContext, data: Value) -> Result<String, RconResponse>;
+}
+
+/// Parses the `data` of a request into the command's own type
+pub fn parse_data<T: DeserializeOwned>(data: Value) -> Result<T, RconResponse> {
+    serde_json::from_value(data).map_err(|e| RconResponse::error(format!("Invalid data: {}", e)))
+}
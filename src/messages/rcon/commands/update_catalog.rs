@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::messages::outgoing::catalog::CatalogUpdatedComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::rcon::commands::rcon_command::{RconCommand, RconContext, RconResponse};
+
+/// Called by the CMS after it changed the catalog, clients drop their cached pages
+pub struct UpdateCatalog;
+
+#[async_trait]
+impl RconCommand for UpdateCatalog {
+    fn get_key(&self) -> &'static str {
+        "updatecatalog"
+    }
+
+    async fn handle(&self, context: &RconContext, _data: Value) -> Result<String, RconResponse> {
+        // Pages are read from the database when they are opened, nothing is cached on our side
+        context.get_game_client_manager().broadcast(CatalogUpdatedComposer.compose());
+
+        Ok(String::from("Catalog updated"))
+    }
+}
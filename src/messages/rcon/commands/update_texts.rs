@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::messages::rcon::commands::rcon_command::{RconCommand, RconContext, RconResponse};
+
+/// Called after the texts were edited, reloads them without a restart
+pub struct UpdateTexts;
+
+#[async_trait]
+impl RconCommand for UpdateTexts {
+    fn get_key(&self) -> &'static str {
+        "updatetexts"
+    }
+
+    async fn handle(&self, context: &RconContext, _data: Value) -> Result<String, RconResponse> {
+        let texts = crate::get_texts();
+
+        texts.load(Some(context.get_repositories().get_settings().as_ref()))
+            .await
+            .map_err(|e| RconResponse::error(format!("Failed to reload the texts: {}", e)))?;
+
+        Ok(format!("Reloaded {} texts", texts.get_count()))
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rcon_message_handler;
+
+pub use rcon_message_handler::RconMessageHandler;
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::messages::rcon::commands::{
+    AlertUser, DisconnectUser, GiveBadge, GiveCredits, GivePixels, GivePoints, HotelAlert, RconCommand, RconContext,
+    RconResponse, UpdateCatalog, UpdateTexts,
+};
+
+#[derive(Deserialize)]
+struct RconRequest {
+    key: String,
+    #[serde(default)]
+    data: Value,
+}
+
+/// Looks up the command for a request and runs it
+pub struct RconMessageHandler {
+    commands: HashMap<&'static str, Arc<dyn RconCommand>>,
+    context: RconContext,
+}
+
+impl RconMessageHandler {
+    /// Creates the handler with every built-in command registered
+    pub fn new(context: RconContext) -> Self {
+        let mut handler = Self {
+            commands: HashMap::new(),
+            context,
+        };
+
+        handler.register(AlertUser);
+        handler.register(DisconnectUser);
+        handler.register(GiveBadge);
+        handler.register(GiveCredits);
+        handler.register(GivePixels);
+        handler.register(GivePoints);
+        handler.register(HotelAlert);
+        handler.register(UpdateCatalog);
+        handler.register(UpdateTexts);
+
+        handler
+    }
+
+    /// Adds a command, a command with the same key is replaced
+    pub fn register(&mut self, command: impl RconCommand + 'static) {
+        if self.commands.insert(command.get_key(), Arc::new(command)).is_some() {
+            warn!("Replaced an RCON command that was already registered");
+        }
+    }
+
+    /// The keys of every registered command, sorted
+    pub fn get_command_keys(&self) -> Vec<&'static str> {
+        let mut keys: Vec<_> = self.commands.keys().copied().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Runs a single JSON request
+    pub async fn handle(&self, request: &[u8]) -> RconResponse {
+        let request: RconRequest = match serde_json::from_slice(request) {
+            Ok(request) => request,
+            Err(e) => return RconResponse::error(format!("Invalid request: {}", e)),
+        };
+
+        let command = match self.commands.get(request.key.as_str()) {
+            Some(command) => command,
+            None => return RconResponse::error(format!("Unknown command {}", request.key)),
+        };
+
+        debug!("Running RCON command {}", request.key);
+
+        match command.handle(&self.context, request.data).await {
+            Ok(message) => RconResponse::ok(message),
+            Err(response) => response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    use crate::database::repositories::{
+        InMemoryItemRepository, InMemoryRoomRepository, InMemorySettingsRepository, InMemoryUserRepository, Repositories,
+        UserRecord, UserSettingsRecord,
+    };
+    use crate::habbohotel::game_enviroment::GameEnvironment;
+    use crate::habbohotel::gameclients::{GameClient, GameClientManager, QueueOverflowPolicy};
+    use crate::habbohotel::users::{Habbo, HabboInfo};
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+    use crate::util::fixtures;
+
+    fn handler() -> (RconMessageHandler, Arc<GameClientManager>) {
+        let game_client_manager = Arc::new(GameClientManager::new(16, QueueOverflowPolicy::Drop));
+        let game_environment = Arc::new(GameEnvironment::new(Repositories::in_memory()));
+        let context = RconContext::new(Arc::clone(&game_client_manager), game_environment);
+        (RconMessageHandler::new(context), game_client_manager)
+    }
+
+    fn user() -> UserRecord {
+        UserRecord { username: String::from("Sulake"), credits: 100, ..fixtures::user(7) }
+    }
+
+    /// A handler whose users repository already holds a user with id 7
+    fn handler_with_user() -> (RconMessageHandler, Arc<GameClientManager>, Arc<GameEnvironment>) {
+        let users = InMemoryUserRepository::new();
+        users.insert_user(user());
+
+        let repositories = Repositories::new(
+            Arc::new(users),
+            Arc::new(InMemoryRoomRepository::new()),
+            Arc::new(InMemoryItemRepository::new()),
+            Arc::new(InMemorySettingsRepository::new()),
+        );
+
+        let game_client_manager = Arc::new(GameClientManager::new(16, QueueOverflowPolicy::Drop));
+        let game_environment = Arc::new(GameEnvironment::new(repositories));
+        let context = RconContext::new(Arc::clone(&game_client_manager), Arc::clone(&game_environment));
+        (RconMessageHandler::new(context), game_client_manager, game_environment)
+    }
+
+    fn login(game_client_manager: &GameClientManager, user_id: i32) -> (Arc<GameClient>, mpsc::Receiver<OutgoingFrame>) {
+        let (client, receiver) = game_client_manager.create_client("127.0.0.1:30000".parse().unwrap());
+        client.set_user_id(user_id);
+        game_client_manager.add_client(Arc::clone(&client));
+        (client, receiver)
+    }
+
+    /// Logs user 7 in with a Habbo, like the SSO login does
+    fn login_habbo(game_client_manager: &GameClientManager, game_environment: &GameEnvironment) -> (Arc<Habbo>, mpsc::Receiver<OutgoingFrame>) {
+        let (client, receiver) = login(game_client_manager, 7);
+        let habbo = Arc::new(Habbo::new(Arc::clone(&client), HabboInfo::new(user(), vec![]), UserSettingsRecord::default()));
+        client.set_habbo(Arc::clone(&habbo));
+        game_environment.get_habbo_manager().add_habbo(Arc::clone(&habbo));
+        (habbo, receiver)
+    }
+
+    fn next_header(receiver: &mut mpsc::Receiver<OutgoingFrame>) -> u16 {
+        match receiver.try_recv() {
+            Ok(OutgoingFrame::Packet(frame)) => u16::from_be_bytes([frame[4], frame[5]]),
+            _ => panic!("expected a packet"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests() {
+        let (handler, _) = handler();
+
+        let response = handler.handle(b"not json").await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_ERROR);
+
+        let response = handler.handle(br#"{"key": "nope", "data": {}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_ERROR);
+
+        // Data that doesn't fit the command
+        let response = handler.handle(br#"{"key": "alertuser", "data": {"user_id": "one"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_alert_and_disconnect_user() {
+        let (handler, game_client_manager) = handler();
+        let (client, mut receiver) = login(&game_client_manager, 7);
+
+        let response = handler.handle(br#"{"key": "alertuser", "data": {"user_id": 7, "message": "Hello"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert_eq!(next_header(&mut receiver), Outgoing::GenericAlertComposer.get_header());
+
+        let response = handler.handle(br#"{"key": "alertuser", "data": {"user_id": 8, "message": "Hello"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::HABBO_NOT_FOUND);
+
+        let response = handler.handle(br#"{"key": "disconnect", "data": {"user_id": 7}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert!(client.is_disconnected());
+    }
+
+    #[tokio::test]
+    async fn test_give_credits_and_badge() {
+        let (handler, game_client_manager, game_environment) = handler_with_user();
+        let (habbo, mut receiver) = login_habbo(&game_client_manager, &game_environment);
+
+        let response = handler.handle(br#"{"key": "givecredits", "data": {"user_id": 7, "credits": 50}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert_eq!(next_header(&mut receiver), Outgoing::UserCreditsComposer.get_header());
+        assert_eq!(habbo.get_info().credits, 150);
+
+        let response = handler.handle(br#"{"key": "givepoints", "data": {"user_id": 7, "points": 25, "type": 5}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert_eq!(next_header(&mut receiver), Outgoing::UserPointsComposer.get_header());
+        assert_eq!(habbo.get_info().get_currency(5), 25);
+
+        let response = handler.handle(br#"{"key": "givecredits", "data": {"user_id": 8, "credits": 50}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::HABBO_NOT_FOUND);
+
+        let response = handler.handle(br#"{"key": "givebadge", "data": {"user_id": 7, "badge": "ADM"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert_eq!(next_header(&mut receiver), Outgoing::AddUserBadgeComposer.get_header());
+
+        // A badge is only given once
+        let response = handler.handle(br#"{"key": "givebadge", "data": {"user_id": 7, "badge": "ADM"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_hotel_alert() {
+        let (handler, game_client_manager) = handler();
+        let (_first, mut first_receiver) = login(&game_client_manager, 1);
+        let (_second, mut second_receiver) = login(&game_client_manager, 2);
+
+        let response = handler.handle(br#"{"key": "hotelalert", "data": {"message": "Maintenance"}}"#).await;
+        assert_eq!(response.get_status(), RconResponse::STATUS_OK);
+        assert_eq!(next_header(&mut first_receiver), Outgoing::GenericAlertComposer.get_header());
+        assert_eq!(next_header(&mut second_receiver), Outgoing::GenericAlertComposer.get_header());
+    }
+}
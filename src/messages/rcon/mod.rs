@@ -1,9 +1,9 @@
 //! Module for handling Remote Console (RCON) messages
+//!
+//! A request is a JSON object like `{ "key": "alertuser", "data": { "user_id": 1, "message": "Hi" } }`.
+//! The key picks the `RconCommand`, which parses the data itself, and every request gets a
+//! `{ "status": 0, "message": "..." }` response.
 
-// This module handles remote console commands and functionality
-// Add submodules as needed for RCON functionality
-
-// Example structure:
-pub mod commands;
 pub mod authentication;
-pub mod handlers;
\ No newline at end of file
+pub mod commands;
+pub mod handlers;
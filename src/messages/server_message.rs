@@ -0,0 +1,78 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use log::warn;
+
+pub struct ServerMessage {
+    header: u16,
+    body: BytesMut,
+}
+
+impl ServerMessage {
+    pub fn new(header: u16) -> Self {
+        Self {
+            header,
+            body: BytesMut::new(),
+        }
+    }
+
+    pub fn get_header(&self) -> u16 {
+        self.header
+    }
+
+    pub fn get_body(&self) -> &BytesMut {
+        &self.body
+    }
+
+    pub fn get_body_mut(&mut self) -> &mut BytesMut {
+        &mut self.body
+    }
+
+    pub fn append_int(&mut self, value: i32) {
+        self.body.put_i32(value);
+    }
+
+    pub fn append_short(&mut self, value: i16) {
+        self.body.put_i16(value);
+    }
+
+    /// Appends a UTF-8 string prefixed with its length as a short.
+    /// Strings that don't fit in a short are cut off at the last whole character that does.
+    pub fn append_string(&mut self, value: &str) {
+        let mut length = value.len();
+        if length > u16::MAX as usize {
+            warn!("Cutting off a {} byte string in packet {}", length, self.header);
+
+            length = u16::MAX as usize;
+            while !value.is_char_boundary(length) {
+                length -= 1;
+            }
+        }
+
+        self.body.put_u16(length as u16);
+        self.body.put_slice(&value.as_bytes()[..length]);
+    }
+
+    pub fn append_bool(&mut self, value: bool) {
+        self.body.put_u8(value as u8);
+    }
+
+    /// Appends raw bytes without a length prefix
+    pub fn append_bytes(&mut self, value: &[u8]) {
+        self.body.put_slice(value);
+    }
+
+    /// Writes the complete packet: the length, the header and the body.
+    /// The length covers the 2 byte header and the body.
+    pub fn write_to(&self, dst: &mut BytesMut) {
+        dst.reserve(4 + 2 + self.body.len());
+        dst.put_i32((2 + self.body.len()) as i32);
+        dst.put_u16(self.header);
+        dst.put_slice(&self.body);
+    }
+
+    /// The complete packet as it goes over the wire, see `write_to`
+    pub fn get_complete(&self) -> Bytes {
+        let mut packet = BytesMut::new();
+        self.write_to(&mut packet);
+        packet.freeze()
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set when the connection to the camera server should be retried
+static ATTEMPT_RECONNECT: AtomicBool = AtomicBool::new(false);
+
+/// Set while a camera client runs its connect loop
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// The connection to the camera server that renders photos
+pub struct CameraClient;
+
+impl CameraClient {
+    /// Whether there is a camera client to reconnect
+    pub fn is_running() -> bool {
+        RUNNING.load(Ordering::SeqCst)
+    }
+
+    pub fn set_running(running: bool) {
+        RUNNING.store(running, Ordering::SeqCst);
+    }
+
+    /// Asks the camera client to connect again, see `ConsoleReconnectCameraCommand`
+    pub fn request_reconnect() {
+        ATTEMPT_RECONNECT.store(true, Ordering::SeqCst);
+    }
+
+    /// Takes a pending reconnect request, true at most once per request
+    pub fn take_reconnect_request() -> bool {
+        ATTEMPT_RECONNECT.swap(false, Ordering::SeqCst)
+    }
+}
@@ -28,9 +28,8 @@ impl Decoder for GameByteDecoder {
         // Remove header bytes from buffer
         src.advance(2);
         
-        // Create a new client message with the remaining bytes
-        let body = src.clone();
-        src.clear(); // Clear the source buffer as we've consumed it
+        // The rest of the frame is the message body
+        let body = src.split();
         
         Ok(Some(ClientMessage::new(header, body)))
     }
@@ -1,44 +1,37 @@
 use bytes::BytesMut;
-use tokio_util::codec::Decoder;
-use std::io;
-use tokio::sync::Mutex;
-use std::sync::Arc;
 
-use crate::networking::gameserver::game_server_attributes::GameServerAttributes;
+use crate::networking::gameserver::game_server_attributes::CryptoAttribute;
 
-pub struct GameByteDecryption;
+pub struct GameByteDecryption {
+    crypto_client: CryptoAttribute,
+    // Number of bytes at the front of the read buffer that already went through this stage.
+    // The buffer is shared with the frame decoder, so bytes can sit here across several reads.
+    processed: usize,
+}
 
 impl GameByteDecryption {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(crypto_client: CryptoAttribute) -> Self {
+        Self {
+            crypto_client,
+            processed: 0,
+        }
     }
-}
-
-impl Decoder for GameByteDecryption {
-    type Item = BytesMut;
-    type Error = io::Error;
 
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if src.is_empty() {
-            return Ok(None);
+    /// Runs every byte that arrived since the last call through the decryption stage
+    pub fn decrypt(&mut self, src: &mut BytesMut) {
+        if self.processed >= src.len() {
+            return;
         }
-        
-        // Create a clone of the data to work with
-        let mut data = src.clone();
-        src.clear(); // Clear the source buffer as we've consumed it
-        
-        // Get the associated crypto client and decrypt the data
-        // This would be handled by the appropriate channel attribute in a complete implementation
-        // Here, we assume the GameServerAttributes would have a method to access the crypto client
-        // The actual implementation would depend on how channels and attributes are managed in the Rust version
-        
-        // As a placeholder, we'll return the data as-is
-        // In a real implementation, you would call the crypto client's parse method on the data
-        // Example (commented out as it depends on the actual implementation):
-        // if let Some(crypto_client) = channel.get_attribute(GameServerAttributes::CRYPTO_CLIENT) {
-        //     crypto_client.parse(data.as_mut());
-        // }
-        
-        Ok(Some(data))
+
+        // Decrypt the new data in place with the associated crypto client.
+        // As long as no crypto client is installed the data passes through as-is,
+        // the client only starts encrypting after the handshake finished.
+        self.crypto_client.parse(&mut src[self.processed..]);
+        self.processed = src.len();
+    }
+
+    /// Tells the stage that `length` bytes were taken off the front of the buffer
+    pub fn consume(&mut self, length: usize) {
+        self.processed = self.processed.saturating_sub(length);
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use bytes::{BytesMut, Buf, BufMut};
+use bytes::{BytesMut, Buf};
 use tokio_util::codec::Decoder;
 use std::io;
 
@@ -9,8 +9,6 @@ pub struct GameByteFrameDecoder {
     length_field_length: usize,
     length_field_adjustment: usize,
     initial_bytes_to_strip: usize,
-    // Buffer for accumulating data
-    cumulation: Option<BytesMut>,
 }
 
 impl GameByteFrameDecoder {
@@ -31,48 +29,15 @@ impl GameByteFrameDecoder {
             length_field_length,
             length_field_adjustment,
             initial_bytes_to_strip,
-            cumulation: None,
         }
     }
 
-    fn extract_frame(&mut self, buffer: &mut BytesMut, index: usize) -> Option<BytesMut> {
-        let frame_length = self.get_frame_length(buffer, index);
-        
-        // Frame length must be valid
-        if frame_length < 0 || frame_length > self.max_packet_length as i32 {
-            return None;
-        }
-        
-        // Calculate the total length of the frame including the header
-        let frame_length = frame_length as usize;
-        let total_length = self.length_field_offset + self.length_field_length + frame_length;
-        
-        // Check if we have enough bytes
-        if buffer.len() < total_length {
-            return None;
-        }
-        
-        // Extract the frame and strip initial bytes if required
-        let mut frame = buffer.split_to(total_length);
-        if self.initial_bytes_to_strip > 0 {
-            frame.advance(self.initial_bytes_to_strip);
-        }
-        
-        Some(frame)
-    }
-    
-    fn get_frame_length(&self, buffer: &BytesMut, index: usize) -> i32 {
-        if self.length_field_length == 4 {
-            // Read a 4-byte integer
-            let offset = index + self.length_field_offset;
-            let bytes = [buffer[offset], buffer[offset + 1], buffer[offset + 2], buffer[offset + 3]];
-            let length = i32::from_be_bytes(bytes);
-            length + self.length_field_adjustment as i32
-        } else {
-            // For other field lengths, provide implementation as needed
-            // This example only handles 4-byte length fields
-            panic!("Unsupported length field length: {}", self.length_field_length);
-        }
+    fn get_frame_length(&self, buffer: &BytesMut) -> i32 {
+        // Read a 4-byte integer
+        let offset = self.length_field_offset;
+        let bytes = [buffer[offset], buffer[offset + 1], buffer[offset + 2], buffer[offset + 3]];
+        let length = i32::from_be_bytes(bytes);
+        length + self.length_field_adjustment as i32
     }
 }
 
@@ -81,45 +46,35 @@ impl Decoder for GameByteFrameDecoder {
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        // If src is empty, return None
-        if src.is_empty() {
+        // Check if we have enough bytes for the length field
+        let header_length = self.length_field_offset + self.length_field_length;
+        if src.len() < header_length {
             return Ok(None);
         }
-        
-        // Accumulate the data
-        let mut buffer = match self.cumulation.take() {
-            Some(mut buf) => {
-                buf.put_slice(src.as_ref());
-                src.clear();
-                buf
-            }
-            None => {
-                let buf = src.clone();
-                src.clear();
-                buf
-            }
-        };
-        
-        // Check if we have enough bytes for the length field
-        if buffer.len() < self.length_field_offset + self.length_field_length {
-            self.cumulation = Some(buffer);
+
+        // Frame length must be valid, a frame always carries at least the 2 byte message header
+        let frame_length = self.get_frame_length(src);
+        if frame_length < 2 || frame_length as usize > self.max_packet_length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid packet length: {}", frame_length),
+            ));
+        }
+
+        // Check if the whole frame has arrived, otherwise reserve room for the rest
+        let total_length = header_length + frame_length as usize;
+        if src.len() < total_length {
+            src.reserve(total_length - src.len());
             return Ok(None);
         }
-        
-        // Extract the frame
-        match self.extract_frame(&mut buffer, 0) {
-            Some(frame) => {
-                // Keep any remaining bytes in the cumulation buffer
-                if !buffer.is_empty() {
-                    self.cumulation = Some(buffer);
-                }
-                Ok(Some(frame))
-            }
-            None => {
-                // Not enough data yet, keep accumulating
-                self.cumulation = Some(buffer);
-                Ok(None)
-            }
+
+        // Extract the frame and strip initial bytes if required,
+        // anything after it stays buffered for the next call
+        let mut frame = src.split_to(total_length);
+        if self.initial_bytes_to_strip > 0 {
+            frame.advance(self.initial_bytes_to_strip);
         }
+
+        Ok(Some(frame))
     }
-}
\ No newline at end of file
+}
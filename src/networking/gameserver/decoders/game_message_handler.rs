@@ -1,45 +1,64 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use log::{debug, error, info, warn};
+use log::{error, warn};
 use std::io;
 
+use crate::habbohotel::gameclients::{GameClient, GameClientManager};
 use crate::messages::client_message::ClientMessage;
-use crate::networking::gameserver::game_server::GameServer;
+use crate::messages::packet_manager::PacketManager;
 use crate::threading::channel_read_handler::ChannelReadHandler;
 
 pub struct GameMessageHandler {
-    game_server: Arc<GameServer>,
+    packet_manager: Arc<PacketManager>,
+    game_client_manager: Arc<GameClientManager>,
 }
 
 impl GameMessageHandler {
-    pub fn new(game_server: Arc<GameServer>) -> Self {
+    pub fn new(packet_manager: Arc<PacketManager>, game_client_manager: Arc<GameClientManager>) -> Self {
         Self {
-            game_server,
+            packet_manager,
+            game_client_manager,
         }
     }
 
     // Handle channel registration
-    pub async fn channel_registered(&self, channel: Arc<Mutex<tokio::net::TcpStream>>) -> io::Result<()> {
+    pub async fn channel_registered(&self, client: Arc<GameClient>) -> io::Result<()> {
         // Add client to the game client manager
-        if !self.game_server.get_game_client_manager().add_client(channel).await {
-            return Err(io::Error::new(io::ErrorKind::Other, "Failed to add client"));
+        if !self.game_client_manager.add_client(client) {
+            return Err(io::Error::other("Failed to add client"));
         }
         
         Ok(())
     }
 
     // Handle channel unregistration
-    pub async fn channel_unregistered(&self, channel: Arc<Mutex<tokio::net::TcpStream>>) -> io::Result<()> {
-        // Remove client from the game client manager
-        // In a real implementation, this would close the channel
+    pub async fn channel_unregistered(&self, client: Arc<GameClient>) -> io::Result<()> {
+        // Remove client from the game client manager and stop its writer task
+        self.game_client_manager.remove_client(client.get_id());
+        client.disconnect();
+
+        // Log the user out, unless they already logged in again on another connection. During the
+        // shutdown the game environment saves everyone that is left before the database closes.
+        if let Some(habbo) = client.take_habbo()
+            && !crate::is_shutting_down()
+        {
+            let environment = crate::get_game_environment();
+            environment.get_room_manager().leave_room(&habbo);
+
+            if environment.get_habbo_manager().remove_habbo(&habbo)
+                && let Err(e) = environment.get_repositories().get_users().set_online(habbo.get_id(), false).await
+            {
+                warn!("Failed to mark {} offline: {}", habbo.get_username(), e);
+            }
+        }
+
         Ok(())
     }
 
     // Handle incoming messages
-    pub async fn handle_message(&self, channel: Arc<Mutex<tokio::net::TcpStream>>, message: ClientMessage) -> io::Result<()> {
-        let multi_threaded = self.game_server.get_config().get_bool("packet_handling.multi_threaded");
+    pub async fn handle_message(&self, client: Arc<GameClient>, message: ClientMessage) -> io::Result<()> {
+        let multi_threaded = crate::get_config().get_bool("packet_handling.multi_threaded").unwrap_or(false);
         
-        let handler = ChannelReadHandler::new(channel, message, Arc::clone(&self.game_server));
+        let handler = ChannelReadHandler::new(client, message, Arc::clone(&self.packet_manager));
         
         if multi_threaded {
             // Spawn a new task to handle the message
@@ -59,24 +78,19 @@ impl GameMessageHandler {
         Ok(())
     }
 
-    // Handle channel inactive
-    pub async fn channel_inactive(&self, channel: Arc<Mutex<tokio::net::TcpStream>>) -> io::Result<()> {
-        // In a real implementation, this would close the channel
-        Ok(())
-    }
-
     // Handle exceptions
-    pub async fn exception_caught(&self, channel: Arc<Mutex<tokio::net::TcpStream>>, error: io::Error) -> io::Result<()> {
+    pub async fn exception_caught(&self, client: Arc<GameClient>, error: io::Error) -> io::Result<()> {
         // Check if it's an IO error
         if error.kind() == io::ErrorKind::ConnectionReset || 
            error.kind() == io::ErrorKind::BrokenPipe ||
            error.kind() == io::ErrorKind::ConnectionAborted {
             // Close the channel
+            client.disconnect();
             return Ok(());
         }
 
         // Log the error if in debug mode
-        if self.game_server.get_config().get_bool("debug.mode") {
+        if crate::get_config().get_bool("debug.mode").unwrap_or(false) {
             if error.kind() == io::ErrorKind::InvalidData {
                 error!("Disconnecting client, reason: \"{}\".", error);
             } else {
@@ -85,6 +99,7 @@ impl GameMessageHandler {
         }
 
         // Close the channel
+        client.disconnect();
         Ok(())
     }
 }
\ No newline at end of file
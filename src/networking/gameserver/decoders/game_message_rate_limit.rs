@@ -1,79 +1,316 @@
-use std::sync::{Arc, Mutex as StdMutex};
 use std::collections::HashMap;
-use tokio_util::codec::Decoder;
-use bytes::BytesMut;
 use std::io;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use log::{debug, warn};
 
+use crate::core::configuration_manager::ConfigurationManager;
 use crate::messages::client_message::ClientMessage;
-use crate::networking::gameserver::game_server_attributes::GameServerAttributes;
+use crate::messages::packet_manager::PacketManager;
 
-pub struct GameMessageRateLimit {
-    // Constants for rate limiting
-    reset_time: u64,
+/// Limits shared by every connection, read from the `io.ratelimit.*` settings
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    enabled: bool,
+    // Counters are cleared after this much time
+    reset_time: Duration,
+    // Maximum messages of the same type per reset period
     max_counter: u32,
+    // Per header maximum, 0 means unlimited
+    overrides: HashMap<u16, u32>,
+    // Dropped packets in a row before the client is warned
+    warn_after: u32,
+    // Dropped packets in a row before the client is disconnected
+    disconnect_after: u32,
 }
 
-impl GameMessageRateLimit {
-    pub fn new() -> Self {
+impl Default for RateLimitConfig {
+    fn default() -> Self {
         // These values match the Java implementation
-        let reset_time = 1; // Reset counter after 1 second
-        let max_counter = 10; // Maximum 10 messages of the same type per reset period
+        Self {
+            enabled: true,
+            reset_time: Duration::from_secs(1),
+            max_counter: 10,
+            overrides: HashMap::new(),
+            warn_after: 3,
+            disconnect_after: 5,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Reads the limits. `io.ratelimit.overrides` is a list like `RoomUserWalkEvent:20,2596:5`,
+    /// packets can be given by name or header id.
+    pub fn load(config: &ConfigurationManager, packet_manager: &PacketManager) -> Self {
+        let defaults = Self::default();
+        let get = |key: &str, default: u32| config.get_int(key).map(|value| value.max(0) as u32).unwrap_or(default);
+
+        let mut overrides = HashMap::new();
+        for entry in config.get_list("io.ratelimit.overrides").unwrap_or_default() {
+            let parsed = entry.split_once(':').and_then(|(packet, max)| {
+                let packet = packet.trim();
+                let header = packet.parse::<u16>().ok()
+                    .or_else(|| packet_manager.get_incoming_header_by_name(packet).map(|header| header as u16))?;
+                Some((header, max.trim().parse::<u32>().ok()?))
+            });
+
+            match parsed {
+                Some((header, max)) => {
+                    overrides.insert(header, max);
+                }
+                None => warn!("Ignoring invalid rate limit override '{}'", entry),
+            }
+        }
+
+        Self {
+            enabled: config.get_bool("io.ratelimit.enabled").unwrap_or(defaults.enabled),
+            reset_time: Duration::from_millis(get("io.ratelimit.reset_ms", defaults.reset_time.as_millis() as u32) as u64),
+            max_counter: get("io.ratelimit.max", defaults.max_counter),
+            overrides,
+            warn_after: get("io.ratelimit.warn_after", defaults.warn_after),
+            disconnect_after: get("io.ratelimit.disconnect_after", defaults.disconnect_after).max(1),
+        }
+    }
+
+    fn get_max(&self, header: u16) -> u32 {
+        self.overrides.get(&header).copied().unwrap_or(self.max_counter)
+    }
+}
+
+struct FloodRecord {
+    disconnects: u32,
+    since: Instant,
+    banned_until: Option<Instant>,
+}
+
+/// Addresses that keep getting disconnected for flooding, banned for a while once they
+/// reach `io.ratelimit.ban_after` disconnects within `io.ratelimit.ban_minutes`
+pub struct FloodBans {
+    ban_after: u32,
+    ban_time: Duration,
+    records: Mutex<HashMap<IpAddr, FloodRecord>>,
+}
+
+impl FloodBans {
+    pub fn new(ban_after: u32, ban_time: Duration) -> Self {
+        Self {
+            ban_after: ban_after.max(1),
+            ban_time,
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn load(config: &ConfigurationManager) -> Self {
+        let ban_after = config.get_int("io.ratelimit.ban_after").unwrap_or(3).max(0) as u32;
+        let ban_minutes = config.get_int("io.ratelimit.ban_minutes").unwrap_or(10).max(0) as u64;
+        Self::new(ban_after, Duration::from_secs(ban_minutes * 60))
+    }
+
+    /// Counts a flood disconnect, returns true if the address is banned now
+    pub fn record_disconnect(&self, address: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut records = self.records.lock().unwrap();
+
+        // Old records would only grow the map, nobody reads them anymore
+        records.retain(|_, record| match record.banned_until {
+            Some(until) => until > now,
+            None => now.duration_since(record.since) < self.ban_time,
+        });
+
+        let record = records.entry(address).or_insert(FloodRecord {
+            disconnects: 0,
+            since: now,
+            banned_until: None,
+        });
+
+        record.disconnects += 1;
+        if record.disconnects >= self.ban_after && record.banned_until.is_none() {
+            record.banned_until = Some(now + self.ban_time);
+            warn!("Banned {} for {} minutes for flooding", address, self.ban_time.as_secs() / 60);
+            return true;
+        }
+
+        record.banned_until.is_some()
+    }
+
+    pub fn is_banned(&self, address: IpAddr) -> bool {
+        let mut records = self.records.lock().unwrap();
+
+        match records.get(&address).and_then(|record| record.banned_until) {
+            Some(until) if until > Instant::now() => true,
+            Some(_) => {
+                records.remove(&address);
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// What the rate limiter decided for one packet
+pub enum RateLimitOutcome {
+    /// The packet may be handled
+    Allow(ClientMessage),
+    /// The packet is over the limit and dropped
+    Drop,
+    /// The packet is dropped and the client has flooded long enough to be warned about it
+    Warn,
+}
 
+/// Counts the packets of one connection per header. A client that goes over the limit has
+/// its packets dropped, keeps going and gets warned, then disconnected and finally its
+/// address gets banned for a while.
+pub struct GameMessageRateLimit {
+    config: Arc<RateLimitConfig>,
+    bans: Arc<FloodBans>,
+    address: IpAddr,
+    counters: HashMap<u16, u32>,
+    last_counter_cleared: Instant,
+    // Packets dropped since the last period without drops
+    strikes: u32,
+    dropped_this_period: bool,
+}
+
+impl GameMessageRateLimit {
+    pub fn new(config: Arc<RateLimitConfig>, bans: Arc<FloodBans>, address: IpAddr) -> Self {
         Self {
-            reset_time,
-            max_counter,
+            config,
+            bans,
+            address,
+            counters: HashMap::new(),
+            last_counter_cleared: Instant::now(),
+            strikes: 0,
+            dropped_this_period: false,
         }
     }
-    
-    // Get current Unix timestamp in seconds
-    fn get_unix_timestamp() -> u64 {
-        match SystemTime::now().duration_since(UNIX_EPOCH) {
-            Ok(duration) => duration.as_secs(),
-            Err(_) => 0,
+
+    /// Decides whether the message may be handled, the caller sends the alert on `Warn`.
+    /// Fails once the client flooded long enough to be disconnected.
+    pub fn filter(&mut self, message: ClientMessage) -> io::Result<RateLimitOutcome> {
+        if !self.config.enabled {
+            return Ok(RateLimitOutcome::Allow(message));
+        }
+
+        let now = Instant::now();
+        if now.duration_since(self.last_counter_cleared) > self.config.reset_time {
+            self.counters.clear();
+            self.last_counter_cleared = now;
+
+            // A quiet period forgives earlier drops
+            if !self.dropped_this_period {
+                self.strikes = 0;
+            }
+            self.dropped_this_period = false;
+        }
+
+        let header = message.get_header();
+        let max = self.config.get_max(header);
+        let count = self.counters.entry(header).or_insert(0);
+        *count += 1;
+
+        if max == 0 || *count <= max {
+            return Ok(RateLimitOutcome::Allow(message));
+        }
+
+        self.strikes += 1;
+        self.dropped_this_period = true;
+
+        if self.strikes >= self.config.disconnect_after {
+            self.bans.record_disconnect(self.address);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Flooding packet {}", header),
+            ));
         }
+
+        if self.strikes == self.config.warn_after {
+            warn!("{} is flooding packet {}, dropping packets", self.address, header);
+            return Ok(RateLimitOutcome::Warn);
+        }
+
+        debug!("Dropped packet {} from {}, over the limit of {}", header, self.address, max);
+        Ok(RateLimitOutcome::Drop)
     }
 }
 
-impl Decoder for GameMessageRateLimit {
-    type Item = ClientMessage;
-    type Error = io::Error;
-
-    fn decode(&mut self, _src: &mut BytesMut, item: Option<Self::Item>) -> Result<Option<Self::Item>, Self::Error> {
-        if let Some(message) = item {
-            // In a complete implementation, you would get the client from the channel's attributes
-            // and check/update its rate limit counters
-            // This is a simplified version that doesn't enforce rate limiting yet
-            
-            // Get the message ID
-            let message_id = message.get_message_id();
-            
-            // In a real implementation, you would:
-            // 1. Get the client's timestamp of last counter clear
-            // 2. Check if it's time to reset the counter
-            // 3. Check if the current message type has exceeded its counter
-            // 4. Update the counter for this message type
-            // 5. Return None if rate limited, or the message if allowed
-            
-            // Example pseudocode:
-            // let timestamp = Self::get_unix_timestamp();
-            // if timestamp - client.last_counter_cleared > self.reset_time {
-            //     // Reset counter
-            //     client.incoming_packet_counter.clear();
-            //     client.last_counter_cleared = timestamp;
-            // }
-            //
-            // let count = client.incoming_packet_counter.get(&message_id).unwrap_or(0);
-            // if count > self.max_counter {
-            //     return Ok(None); // Drop the packet
-            // }
-            //
-            // client.incoming_packet_counter.insert(message_id, count + 1);
-            
-            // For now, always pass through the message
-            return Ok(Some(message));
-        }
-        
-        Ok(None)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    fn limiter(config: RateLimitConfig, bans: &Arc<FloodBans>) -> GameMessageRateLimit {
+        GameMessageRateLimit::new(Arc::new(config), Arc::clone(bans), "10.0.0.1".parse().unwrap())
+    }
+
+    fn message(header: u16) -> ClientMessage {
+        ClientMessage::new(header, BytesMut::new())
+    }
+
+    #[test]
+    fn test_limit_per_header() {
+        let bans = Arc::new(FloodBans::new(3, Duration::from_secs(600)));
+        let config = RateLimitConfig {
+            overrides: HashMap::from([(3320, 20)]),
+            disconnect_after: 100,
+            ..RateLimitConfig::default()
+        };
+        let mut rate_limit = limiter(config, &bans);
+
+        for _ in 0..10 {
+            assert!(matches!(rate_limit.filter(message(1)).unwrap(), RateLimitOutcome::Allow(_)));
+        }
+        assert!(matches!(rate_limit.filter(message(1)).unwrap(), RateLimitOutcome::Drop));
+
+        // Other headers have their own counter, and their own limit
+        for _ in 0..20 {
+            assert!(matches!(rate_limit.filter(message(3320)).unwrap(), RateLimitOutcome::Allow(_)));
+        }
+        assert!(matches!(rate_limit.filter(message(3320)).unwrap(), RateLimitOutcome::Drop));
+    }
+
+    #[test]
+    fn test_counters_reset() {
+        let bans = Arc::new(FloodBans::new(3, Duration::from_secs(600)));
+        let config = RateLimitConfig {
+            reset_time: Duration::from_millis(20),
+            ..RateLimitConfig::default()
+        };
+        let mut rate_limit = limiter(config, &bans);
+
+        for _ in 0..10 {
+            assert!(matches!(rate_limit.filter(message(1)).unwrap(), RateLimitOutcome::Allow(_)));
+        }
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(matches!(rate_limit.filter(message(1)).unwrap(), RateLimitOutcome::Allow(_)));
+    }
+
+    #[test]
+    fn test_flooding_escalates_to_a_ban() {
+        let bans = Arc::new(FloodBans::new(2, Duration::from_secs(600)));
+        let address = "10.0.0.1".parse().unwrap();
+
+        for _ in 0..2 {
+            assert!(!bans.is_banned(address));
+
+            let mut rate_limit = limiter(RateLimitConfig::default(), &bans);
+            for _ in 0..10 {
+                rate_limit.filter(message(1)).unwrap();
+            }
+
+            // Four packets are dropped with a warning on the third, the fifth one over the limit disconnects
+            for strike in 1..=4 {
+                match rate_limit.filter(message(1)).unwrap() {
+                    RateLimitOutcome::Warn => assert_eq!(strike, 3),
+                    RateLimitOutcome::Drop => assert_ne!(strike, 3),
+                    RateLimitOutcome::Allow(_) => panic!("packet {} over the limit was let through", strike),
+                }
+            }
+            assert!(rate_limit.filter(message(1)).is_err());
+        }
+
+        assert!(bans.is_banned(address));
+        assert!(!bans.is_banned("10.0.0.2".parse().unwrap()));
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,18 @@
-use bytes::{BytesMut, Buf, BufMut};
+use bytes::{BytesMut, BufMut};
 use tokio_util::codec::Decoder;
 use std::io;
 
 pub struct GamePolicyDecoder {
-    policy_sent: bool,
+    checked: bool,
 }
 
 impl GamePolicyDecoder {
     pub fn new() -> Self {
         Self {
-            policy_sent: false,
+            checked: false,
         }
     }
-    
+
     // The policy XML string to be sent when requested
     fn get_policy_string() -> String {
         String::from("<?xml version=\"1.0\"?>\n\
@@ -21,49 +21,42 @@ impl GamePolicyDecoder {
           <allow-access-from domain=\"*\" to-ports=\"1-31111\" />\n\
           </cross-domain-policy>\u{0}")
     }
-    
+
     // Function to check if a buffer starts with a policy request
     fn is_policy_request(buffer: &BytesMut) -> bool {
         if buffer.is_empty() {
             return false;
         }
-        
+
         // Policy requests start with '<'
         buffer[0] == b'<'
     }
 }
 
 impl Decoder for GamePolicyDecoder {
-    type Item = (BytesMut, bool); // (Data, is_policy)
+    /// The policy response that has to be written back to the client
+    type Item = BytesMut;
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if src.is_empty() {
+        // Only the very first bytes of a connection can be a policy request,
+        // after that this stage stays out of the way (like removing it from the pipeline)
+        if self.checked || src.is_empty() {
             return Ok(None);
         }
-        
-        // Check if this is a policy request
-        if !self.policy_sent && Self::is_policy_request(src) {
-            // Mark that we've handled a policy request
-            self.policy_sent = true;
-            
-            // Clear the buffer as we've processed the policy request
-            src.clear();
-            
-            // Create a buffer with the policy response
-            let mut policy_buffer = BytesMut::new();
-            policy_buffer.put_slice(Self::get_policy_string().as_bytes());
-            
-            // Return the policy response with a flag indicating it's a policy response
-            return Ok(Some((policy_buffer, true)));
+
+        self.checked = true;
+
+        if !Self::is_policy_request(src) {
+            return Ok(None);
         }
-        
-        // Not a policy request, continue normal processing
-        // Clone the buffer and clear the source
-        let data = src.clone();
+
+        // Discard the request, the connection is closed after the response
         src.clear();
-        
-        // Return the data with a flag indicating it's not a policy response
-        Ok(Some((data, false)))
+
+        let mut policy_buffer = BytesMut::new();
+        policy_buffer.put_slice(Self::get_policy_string().as_bytes());
+
+        Ok(Some(policy_buffer))
     }
-}
\ No newline at end of file
+}
@@ -9,8 +9,9 @@ pub mod game_policy_decoder;
 
 // Re-export the main structs
 pub use game_byte_decoder::GameByteDecoder;
+pub use game_byte_decryption::GameByteDecryption;
 pub use game_byte_frame_decoder::GameByteFrameDecoder;
 pub use game_client_message_logger::GameClientMessageLogger;
 pub use game_message_handler::GameMessageHandler;
-pub use game_message_rate_limit::GameMessageRateLimit;
+pub use game_message_rate_limit::{FloodBans, GameMessageRateLimit, RateLimitConfig, RateLimitOutcome};
 pub use game_policy_decoder::GamePolicyDecoder;
@@ -0,0 +1,26 @@
+use crate::crypto::habbo_rc4::HabboRC4;
+
+pub struct GameByteEncryption {
+    crypto_server: Option<HabboRC4>,
+}
+
+impl GameByteEncryption {
+    pub fn new() -> Self {
+        Self {
+            crypto_server: None,
+        }
+    }
+
+    /// Installs the crypto server, every packet written after this is encrypted
+    pub fn enable(&mut self, crypto_server: HabboRC4) {
+        self.crypto_server = Some(crypto_server);
+    }
+
+    /// Encrypts an encoded packet in place right before it is written to the socket
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        // As long as no crypto server is installed the data passes through as-is
+        if let Some(ref mut crypto) = self.crypto_server {
+            crypto.parse(data);
+        }
+    }
+}
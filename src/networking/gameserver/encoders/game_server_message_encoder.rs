@@ -0,0 +1,23 @@
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Encoder;
+use std::io;
+
+use crate::messages::server_message::ServerMessage;
+
+pub struct GameServerMessageEncoder;
+
+impl GameServerMessageEncoder {
+    /// Encodes a message into a standalone frame, ready to be queued for one or many clients
+    pub fn encode_frame(message: ServerMessage) -> Bytes {
+        message.get_complete()
+    }
+}
+
+impl Encoder<ServerMessage> for GameServerMessageEncoder {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: ServerMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.write_to(dst);
+        Ok(())
+    }
+}
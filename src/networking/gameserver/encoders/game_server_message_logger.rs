@@ -0,0 +1,39 @@
+use log::debug;
+use std::sync::Arc;
+
+use crate::messages::packet_manager::PacketManager;
+use crate::util::ansi::ANSI;
+use crate::util::packet_utils::PacketUtils;
+
+pub struct GameServerMessageLogger {
+    packet_manager: Arc<PacketManager>,
+}
+
+impl GameServerMessageLogger {
+    pub fn new(packet_manager: Arc<PacketManager>) -> Self {
+        Self {
+            packet_manager,
+        }
+    }
+
+    /// Logs an encoded frame: 4 byte length, 2 byte header and the body
+    pub fn log(&self, frame: &[u8]) {
+        if frame.len() < 6 {
+            return;
+        }
+
+        // Log the message with its ID and name
+        let message_id = u16::from_be_bytes([frame[4], frame[5]]);
+        let message_name = self.packet_manager
+            .get_outgoing_packet_name(message_id as i32)
+            .map(|name| name.as_str())
+            .unwrap_or("Unknown");
+
+        debug!("[{}SERVER{}][{:<4}][{:<41}] => {}",
+            ANSI::GREEN,
+            ANSI::DEFAULT,
+            message_id,
+            message_name,
+            PacketUtils::format_packet(&frame[6..]));
+    }
+}
@@ -4,5 +4,6 @@ pub mod game_server_message_encoder;
 pub mod game_server_message_logger;
 
 // Re-export the main structs
+pub use game_byte_encryption::GameByteEncryption;
 pub use game_server_message_encoder::GameServerMessageEncoder;
 pub use game_server_message_logger::GameServerMessageLogger;
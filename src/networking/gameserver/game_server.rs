@@ -1,19 +1,27 @@
 use std::io;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use log::{error, info, debug};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::io::AsyncWriteExt;
 use tokio::runtime::Runtime;
-use tokio::sync::Mutex;
-use bytes::BytesMut;
+use tokio::sync::mpsc;
+use tokio_util::codec::{FramedRead, FramedWrite};
+use tokio_util::sync::CancellationToken;
+use futures::{SinkExt, StreamExt};
 
 use crate::core::configuration_manager::ConfigurationManager;
-use crate::messages::packet_manager::PacketManager;
-use crate::habbohotel::gameclients::GameClientManager;
+use crate::messages::incoming::incoming_headers::Incoming;
+use crate::messages::outgoing::outgoing_headers::Outgoing;
+use crate::messages::outgoing::generic::GenericAlertComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
+use crate::messages::packet_manager::{PacketManager, PacketRevision};
+use crate::habbohotel::gameclients::{GameClient, GameClientManager, QueueOverflowPolicy};
 use crate::networking::Server;
-use crate::networking::gameserver::decoders::{GamePolicyDecoder, GameByteFrameDecoder, GameByteDecoder, GameClientMessageLogger, GameMessageRateLimit, GameMessageHandler};
-use crate::networking::gameserver::encoders::{GameServerMessageEncoder, GameServerMessageLogger};
+use crate::networking::gameserver::decoders::{FloodBans, GameMessageHandler, GameMessageRateLimit, RateLimitConfig};
+use crate::networking::gameserver::game_server_codec::{GameFrame, GameServerCodec, GameServerEncoder, OutgoingFrame};
 use crate::networking::gameserver::handlers::IdleTimeoutHandler;
 
 pub struct GameServer {
@@ -22,9 +30,15 @@ pub struct GameServer {
     port: u16,
     boss_threads: usize,
     worker_threads: usize,
-    runtime: Option<Runtime>,
-    packet_manager: Arc<Mutex<PacketManager>>,
-    game_client_manager: Arc<Mutex<GameClientManager>>,
+    runtime: Mutex<Option<Runtime>>,
+    // Cancelled once the server stops accepting new connections
+    listening: CancellationToken,
+    packet_manager: Arc<PacketManager>,
+    game_client_manager: Arc<GameClientManager>,
+    rate_limit: Arc<RateLimitConfig>,
+    flood_bans: Arc<FloodBans>,
+    ping_interval: Duration,
+    pong_timeout: Duration,
 }
 
 impl Server for GameServer {
@@ -38,18 +52,16 @@ impl Server for GameServer {
         let port = self.port;
         let packet_manager = self.packet_manager.clone();
         let game_client_manager = self.game_client_manager.clone();
+        let rate_limit = self.rate_limit.clone();
+        let flood_bans = self.flood_bans.clone();
+        let ping_interval = self.ping_interval;
+        let pong_timeout = self.pong_timeout;
+        let pong_header = self.packet_manager.get_incoming_header(Incoming::PongEvent) as u16;
+        let listening = self.listening.clone();
         
         // Create a new runtime for the server
         let runtime = Runtime::new()?;
         
-        // Store the runtime in the struct
-        // We need interior mutability since we have an immutable reference to self
-        let this = unsafe { &*(self as *const _ as *mut GameServer) };
-        this.runtime = Some(runtime);
-        
-        // Get a reference to the runtime we just stored
-        let runtime = self.runtime.as_ref().unwrap();
-        
         // Spawn the server task
         runtime.spawn(async move {
             let addr = format!("{host}:{port}");
@@ -64,46 +76,57 @@ impl Server for GameServer {
             info!("Game server listening on {}", addr);
             
             loop {
-                match listener.accept().await {
+                let accepted = tokio::select! {
+                    _ = listening.cancelled() => break,
+                    accepted = listener.accept() => accepted,
+                };
+                
+                match accepted {
                     Ok((socket, addr)) => {
+                        // Addresses banned for flooding are dropped right away
+                        if flood_bans.is_banned(addr.ip()) {
+                            debug!("Refused connection from banned address: {}", addr);
+                            drop(socket);
+                            continue;
+                        }
+                        
                         // Handle new connection
                         debug!("New connection from: {}", addr);
                         
                         // Clone the managers for the connection handler
                         let pm = packet_manager.clone();
                         let gcm = game_client_manager.clone();
+                        let rate_limiter = GameMessageRateLimit::new(rate_limit.clone(), flood_bans.clone(), addr.ip());
+                        let idle_timeout = IdleTimeoutHandler::new(ping_interval, pong_timeout, pong_header);
                         
                         // Spawn a new task for each connection
                         tokio::spawn(async move {
-                            Self::handle_connection(socket, addr, pm, gcm).await;
+                            Self::handle_connection(socket, addr, pm, gcm, rate_limiter, idle_timeout).await;
                         });
                     }
                     Err(e) => {
                         error!("Failed to accept connection: {}", e);
                     }
                 }
-                
-                // Check if we should stop
-                if crate::is_shutting_down() {
-                    break;
-                }
             }
             
-            info!("Game server stopped");
+            info!("Game server stopped accepting connections");
         });
         
+        // The runtime has to outlive this call, the connections run on it
+        *self.runtime.lock().unwrap() = Some(runtime);
+        
         info!("{} started on {}:{}", self.name, self.host, self.port);
         
         Ok(())
     }
     
     fn disconnect(&self) -> io::Result<()> {
-        // Since we're using Arc/Mutex for data, we can still shut down the runtime
-        // with an immutable reference using interior mutability
-        let mut this = self.to_owned();
-        if let Some(runtime) = this.runtime.take() {
-            // Shutdown the runtime
-            drop(runtime);
+        self.stop_listening();
+        
+        // Connections that are still around get a moment to finish writing
+        if let Some(runtime) = self.runtime.lock().unwrap().take() {
+            runtime.shutdown_timeout(Duration::from_secs(5));
         }
         
         info!("{} disconnected", self.name);
@@ -112,145 +135,123 @@ impl Server for GameServer {
 }
 
 impl GameServer {
-    pub fn new(host: String, port: u16) -> io::Result<Self> {
-        // Initialize configuration manager with the proper config path
-        let config = ConfigurationManager::new("config.ini").expect("Failed to load configuration");
-        
+    pub fn new(host: String, port: u16, config: &ConfigurationManager) -> io::Result<Self> {
         // Get thread counts from config
         let boss_threads = (config.get_int("io.bossgroup.threads").unwrap_or(1)) as usize;
         let worker_threads = (config.get_int("io.workergroup.threads").unwrap_or(4)) as usize;
         
+        // Every client gets a bounded send queue, a client that can't keep up hits the overflow policy
+        let queue_size = config.get_int("io.client.queue.size").unwrap_or(512).max(1) as usize;
+        let overflow_policy = config.get_string("io.client.queue.overflow")
+            .ok()
+            .and_then(|value| QueueOverflowPolicy::from_value(&value))
+            .unwrap_or(QueueOverflowPolicy::Disconnect);
+        
+        // Header ids come from the packet tables, a revision file maps them to another client build
+        let revision = match config.get_string("packets.revision") {
+            Ok(path) if !path.is_empty() => {
+                info!("Loading packet revision {}", path);
+                PacketRevision::load(&path)
+            }
+            _ => Ok(PacketRevision::default()),
+        }.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        
+        let mut packet_manager = PacketManager::new();
+        packet_manager.load(&revision).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Outgoing::set_headers(packet_manager.get_outgoing_headers());
+        
+        // Packets over the limit are dropped, clients that keep flooding get disconnected and banned
+        let rate_limit = RateLimitConfig::load(config, &packet_manager);
+        let flood_bans = FloodBans::load(config);
+        
+        // Clients are pinged regularly and disconnected when they stop answering
+        let ping_interval = config.get_duration("io.idle.ping_interval").unwrap_or(Duration::from_secs(30));
+        let pong_timeout = config.get_duration("io.idle.pong_timeout").unwrap_or(Duration::from_secs(60));
+        
         Ok(GameServer {
             name: String::from("Game Server"),
             host,
             port,
             boss_threads,
             worker_threads,
-            runtime: None,
-            packet_manager: Arc::new(Mutex::new(PacketManager::new())),
-            game_client_manager: Arc::new(Mutex::new(GameClientManager::new())),
+            runtime: Mutex::new(None),
+            listening: CancellationToken::new(),
+            packet_manager: Arc::new(packet_manager),
+            game_client_manager: Arc::new(GameClientManager::new(queue_size, overflow_policy)),
+            rate_limit: Arc::new(rate_limit),
+            flood_bans: Arc::new(flood_bans),
+            ping_interval,
+            pong_timeout,
         })
     }
     
     async fn handle_connection(
         socket: TcpStream, 
         addr: SocketAddr,
-        packet_manager: Arc<Mutex<PacketManager>>,
-        game_client_manager: Arc<Mutex<GameClientManager>>
+        packet_manager: Arc<PacketManager>,
+        game_client_manager: Arc<GameClientManager>,
+        rate_limiter: GameMessageRateLimit,
+        mut idle_timeout: IdleTimeoutHandler,
     ) {
-        // Wrap the socket in an Arc<Mutex> for shared access
-        let socket = Arc::new(Mutex::new(socket));
-        
         // Get configuration for debug settings
-        let config = ConfigurationManager::new("config.ini").expect("Failed to load configuration");
-        let debug_enabled = config.get_bool("debug.mode").unwrap_or(false);
+        let debug_enabled = crate::get_config().get_bool("debug.mode").unwrap_or(false);
+        let packet_logging = if debug_enabled { Some(packet_manager.clone()) } else { None };
         
-        // Create a reference to self for the handlers
-        let game_server_ref = Arc::new(Self {
-            name: String::from("Game Server"),
-            host: String::new(),
-            port: 0,
-            boss_threads: 0,
-            worker_threads: 0,
-            runtime: None,
-            packet_manager: packet_manager.clone(),
-            game_client_manager: game_client_manager.clone(),
-        });
+        let (client, receiver) = game_client_manager.create_client(addr);
         
-        // Create the handler chain
-        let policy_decoder = GamePolicyDecoder::new();
-        let frame_decoder = GameByteFrameDecoder::new();
-        let byte_decoder = GameByteDecoder::new();
+        // Reading and writing happen on separate halves so a pending read never blocks a write
+        let (read_half, write_half) = socket.into_split();
+        let mut reader = FramedRead::new(read_half, GameServerCodec::new(packet_logging.clone(), client.get_crypto_client(), Some(rate_limiter)));
+        let writer = FramedWrite::new(write_half, GameServerEncoder::new(packet_logging));
         
-        // Optional message logger for debug
-        let message_logger = if debug_enabled {
-            Some(GameClientMessageLogger::new(game_server_ref.clone()))
-        } else {
-            None
-        };
+        tokio::spawn(Self::write_loop(client.clone(), writer, receiver));
         
-        // Rate limiter and message handler
-        let rate_limiter = GameMessageRateLimit::new();
-        let message_handler = GameMessageHandler::new(game_server_ref.clone());
+        let message_handler = GameMessageHandler::new(packet_manager, game_client_manager);
         
-        // Register the client channel
-        if let Err(e) = message_handler.channel_registered(socket.clone()).await {
+        // Register the client
+        if let Err(e) = message_handler.channel_registered(client.clone()).await {
             error!("Failed to register client: {}", e);
+            client.disconnect();
             return;
         }
         
+        idle_timeout.initialize(client.clone());
+        
         // Set up async processing loop for the connection
         loop {
-            // Read data from the socket
-            let mut socket_guard = match socket.lock().await {
-                Ok(guard) => guard,
-                Err(_) => {
-                    error!("Failed to lock socket mutex");
-                    break;
-                }
+            let frame = tokio::select! {
+                _ = client.disconnected() => break,
+                frame = reader.next() => frame,
             };
             
-            // Create a buffer to read into
-            let mut buf = BytesMut::with_capacity(4096);
-            
-            // Read from socket
-            match socket_guard.read_buf(&mut buf).await {
-                Ok(0) => {
+            match frame {
+                None => {
                     // Connection closed
                     debug!("Connection closed by client: {}", addr);
                     break;
                 },
-                Ok(_) => {
-                    // Process the data through the decoder chain
-                    // First, check for policy request
-                    match policy_decoder.decode(&mut buf) {
-                        Ok(Some((policy_data, is_policy))) => {
-                            if is_policy {
-                                // Send policy response and close connection
-                                if let Err(e) = socket_guard.write_all(&policy_data).await {
-                                    error!("Failed to send policy response: {}", e);
-                                }
-                                break; // Close connection after policy response
-                            } else {
-                                // Continue with frame decoding
-                                let mut framed_data = policy_data;
-                                
-                                // Apply frame decoder
-                                if let Ok(Some(frame)) = frame_decoder.decode(&mut framed_data) {
-                                    // Apply byte decoder
-                                    if let Ok(Some(message)) = byte_decoder.decode(&mut frame) {
-                                        // Apply message logger if enabled
-                                        let logged_message = if let Some(ref mut logger) = message_logger {
-                                            match logger.decode(&mut BytesMut::new()) {
-                                                Ok(Some(m)) => m,
-                                                _ => message,
-                                            }
-                                        } else {
-                                            message
-                                        };
-                                        
-                                        // Apply rate limiter
-                                        if let Ok(Some(rate_limited_message)) = rate_limiter.decode(&mut BytesMut::new(), Some(logged_message)) {
-                                            // Handle the message
-                                            if let Err(e) = message_handler.handle_message(socket.clone(), rate_limited_message).await {
-                                                error!("Error handling message: {}", e);
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        },
-                        Err(e) => {
-                            error!("Error in policy decoder: {}", e);
-                            break;
-                        },
-                        _ => {} // No data to process
+                Some(Ok(GameFrame::Policy(policy_data))) => {
+                    // Send policy response and close connection
+                    client.send_raw(policy_data);
+                    client.close();
+                    break;
+                },
+                Some(Ok(GameFrame::FloodWarning)) => {
+                    let warning = crate::get_texts().get_text_or("floodprotection.warning", "You are sending too many packets, slow down or you will be disconnected");
+                    client.send_response(GenericAlertComposer::new(warning).compose());
+                },
+                Some(Ok(GameFrame::Message(message))) => {
+                    idle_timeout.on_client_message(&message);
+                    
+                    // Handle the message
+                    if let Err(e) = message_handler.handle_message(client.clone(), message).await {
+                        error!("Error handling message: {}", e);
+                        break;
                     }
                 },
-                Err(e) => {
+                Some(Err(e)) => {
                     // Handle error
-                    if let Err(handle_err) = message_handler.exception_caught(socket.clone(), e).await {
+                    if let Err(handle_err) = message_handler.exception_caught(client.clone(), e).await {
                         error!("Error handling exception: {}", handle_err);
                     }
                     break;
@@ -263,17 +264,61 @@ impl GameServer {
             }
         }
         
-        // Connection closed, unregister the channel
-        if let Err(e) = message_handler.channel_unregistered(socket.clone()).await {
+        // Connection closed, unregister the client
+        idle_timeout.destroy();
+        if let Err(e) = message_handler.channel_unregistered(client.clone()).await {
             error!("Failed to unregister client: {}", e);
         }
     }
     
-    pub fn get_packet_manager(&self) -> Arc<Mutex<PacketManager>> {
-        self.packet_manager.clone()
+    /// Drains a client's send queue into the socket until the client disconnects
+    async fn write_loop(
+        client: Arc<GameClient>,
+        mut writer: FramedWrite<OwnedWriteHalf, GameServerEncoder>,
+        mut receiver: mpsc::Receiver<OutgoingFrame>,
+    ) {
+        loop {
+            let frame = tokio::select! {
+                _ = client.disconnected() => break,
+                frame = receiver.recv() => frame,
+            };
+            
+            let frame = match frame {
+                Some(OutgoingFrame::Close) | None => break,
+                Some(frame) => frame,
+            };
+            
+            // Queue everything that is already waiting before flushing, this keeps
+            // the amount of syscalls down when a room is busy
+            let mut result = writer.feed(frame).await;
+            while result.is_ok() {
+                match receiver.try_recv() {
+                    Ok(OutgoingFrame::Close) => {
+                        let _ = writer.flush().await;
+                        client.disconnect();
+                        break;
+                    }
+                    Ok(frame) => result = writer.feed(frame).await,
+                    Err(_) => break,
+                }
+            }
+            
+            if let Err(e) = result.and(writer.flush().await) {
+                debug!("Failed to write to client {}: {}", client.get_address(), e);
+                break;
+            }
+        }
+        
+        client.disconnect();
+        let _ = writer.get_mut().shutdown().await;
+    }
+    
+    /// Closes the listener, connected clients stay connected
+    pub fn stop_listening(&self) {
+        self.listening.cancel();
     }
     
-    pub fn get_game_client_manager(&self) -> Arc<Mutex<GameClientManager>> {
+    pub fn get_game_client_manager(&self) -> Arc<GameClientManager> {
         self.game_client_manager.clone()
     }
 }
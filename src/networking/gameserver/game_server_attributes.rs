@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 use crate::habbohotel::gameclients::GameClient;
 use crate::crypto::habbo_rc4::HabboRC4;
 
@@ -19,10 +21,34 @@ pub struct GameClientAttribute {
     pub client: GameClient,
 }
 
-/// Manages crypto objects, designed to be stored in a connection's attributes
+/// Holds a connection's RC4 cipher for one direction once the handshake installed it.
+/// Clones share the same cipher, so the codec and the client see the same state.
+#[derive(Clone, Default)]
 pub struct CryptoAttribute {
-    /// Connection ID
-    pub connection_id: u64,
-    /// Crypto reference
-    pub crypto: HabboRC4,
+    crypto: Arc<Mutex<Option<HabboRC4>>>,
+}
+
+impl CryptoAttribute {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, crypto: HabboRC4) {
+        *self.crypto.lock().unwrap() = Some(crypto);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.crypto.lock().unwrap().is_some()
+    }
+
+    /// Runs the bytes through the cipher, returns false if no cipher is installed
+    pub fn parse(&self, bytes: &mut [u8]) -> bool {
+        match self.crypto.lock().unwrap().as_mut() {
+            Some(crypto) => {
+                crypto.parse(bytes);
+                true
+            }
+            None => false,
+        }
+    }
 }
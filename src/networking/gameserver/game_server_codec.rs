@@ -0,0 +1,309 @@
+use std::io;
+use std::sync::Arc;
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::crypto::habbo_rc4::HabboRC4;
+use crate::messages::client_message::ClientMessage;
+use crate::messages::packet_manager::PacketManager;
+use crate::networking::gameserver::decoders::{GamePolicyDecoder, GameByteDecryption, GameByteFrameDecoder, GameByteDecoder, GameClientMessageLogger, GameMessageRateLimit, RateLimitOutcome};
+use crate::networking::gameserver::encoders::{GameByteEncryption, GameServerMessageLogger};
+use crate::networking::gameserver::game_server_attributes::CryptoAttribute;
+
+/// Everything the inbound side of a game connection can produce
+pub enum GameFrame {
+    /// The client asked for the flash policy file, the response has to be written
+    /// back as-is and the connection closed afterwards
+    Policy(Bytes),
+    /// A decoded packet that passed the whole inbound chain
+    Message(ClientMessage),
+    /// The client is flooding and has to be warned before it gets disconnected
+    FloodWarning,
+}
+
+/// Everything that can be queued on the outbound side of a game connection
+pub enum OutgoingFrame {
+    /// An encoded packet, see `GameServerMessageEncoder::encode_frame`.
+    /// Broadcasts share the same bytes between every receiving client.
+    Packet(Bytes),
+    /// Bytes that go out untouched, like the policy response
+    Raw(Bytes),
+    /// Encrypt every packet queued after this frame, see `GameClient::enable_encryption`
+    EnableEncryption(Box<HabboRC4>),
+    /// Flush everything queued before this frame and close the connection
+    Close,
+}
+
+/// The inbound game server pipeline for a single connection, meant to be driven by `FramedRead`.
+///
+/// GamePolicyDecoder -> GameByteDecryption -> GameByteFrameDecoder -> GameByteDecoder
+/// -> GameClientMessageLogger -> GameMessageRateLimit.
+///
+/// `FramedRead` keeps the read buffer between calls, so partial packets wait for the rest of their
+/// bytes and several packets arriving in one read come out one by one.
+pub struct GameServerCodec {
+    policy_decoder: GamePolicyDecoder,
+    decryption: GameByteDecryption,
+    frame_decoder: GameByteFrameDecoder,
+    byte_decoder: GameByteDecoder,
+    message_logger: Option<GameClientMessageLogger>,
+    rate_limiter: Option<GameMessageRateLimit>,
+}
+
+impl GameServerCodec {
+    /// Creates the pipeline, packets are logged when a packet manager is given.
+    /// Incoming data is decrypted as soon as a cipher is installed in `crypto_client`.
+    /// Without a rate limiter every packet is let through.
+    pub fn new(packet_logging: Option<Arc<PacketManager>>, crypto_client: CryptoAttribute, rate_limiter: Option<GameMessageRateLimit>) -> Self {
+        Self {
+            policy_decoder: GamePolicyDecoder::new(),
+            decryption: GameByteDecryption::new(crypto_client),
+            frame_decoder: GameByteFrameDecoder::new(),
+            byte_decoder: GameByteDecoder::new(),
+            message_logger: packet_logging.map(GameClientMessageLogger::new),
+            rate_limiter,
+        }
+    }
+}
+
+/// The outbound game server pipeline for a single connection, meant to be driven by `FramedWrite`
+/// from the connection's writer task.
+///
+/// GameServerMessageEncoder -> GameServerMessageLogger -> GameByteEncryption.
+/// Packets are encoded with `GameServerMessageEncoder` before they are queued, so a broadcast
+/// is only encoded once and just logging and encryption happen on the writer task.
+pub struct GameServerEncoder {
+    message_logger: Option<GameServerMessageLogger>,
+    encryption: GameByteEncryption,
+}
+
+impl GameServerEncoder {
+    /// Creates the pipeline, packets are logged when a packet manager is given
+    pub fn new(packet_logging: Option<Arc<PacketManager>>) -> Self {
+        Self {
+            message_logger: packet_logging.map(GameServerMessageLogger::new),
+            encryption: GameByteEncryption::new(),
+        }
+    }
+}
+
+impl Decoder for GameServerCodec {
+    type Item = GameFrame;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if let Some(policy) = self.policy_decoder.decode(src)? {
+            return Ok(Some(GameFrame::Policy(policy.freeze())));
+        }
+
+        loop {
+            self.decryption.decrypt(src);
+
+            let buffered = src.len();
+            let mut frame = match self.frame_decoder.decode(src)? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+            self.decryption.consume(buffered - src.len());
+
+            let message = match self.byte_decoder.decode(&mut frame)? {
+                Some(message) => message,
+                None => continue,
+            };
+
+            if let Some(ref logger) = self.message_logger {
+                logger.log(&message);
+            }
+
+            let outcome = match self.rate_limiter {
+                Some(ref mut rate_limiter) => rate_limiter.filter(message)?,
+                None => RateLimitOutcome::Allow(message),
+            };
+
+            // A packet dropped by the rate limiter must not stall the ones buffered behind it
+            match outcome {
+                RateLimitOutcome::Allow(message) => return Ok(Some(GameFrame::Message(message))),
+                RateLimitOutcome::Warn => return Ok(Some(GameFrame::FloodWarning)),
+                RateLimitOutcome::Drop => continue,
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(buf)? {
+            Some(frame) => Ok(Some(frame)),
+            None => {
+                // The client went away halfway through a packet, nothing left to handle
+                buf.clear();
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl Encoder<OutgoingFrame> for GameServerEncoder {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: OutgoingFrame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            OutgoingFrame::Packet(frame) => {
+                if let Some(ref logger) = self.message_logger {
+                    logger.log(&frame);
+                }
+
+                let start = dst.len();
+                dst.extend_from_slice(&frame);
+                self.encryption.encrypt(&mut dst[start..]);
+            }
+            OutgoingFrame::Raw(data) => dst.extend_from_slice(&data),
+            OutgoingFrame::EnableEncryption(crypto) => self.encryption.enable(*crypto),
+            OutgoingFrame::Close => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+    use crate::messages::server_message::ServerMessage;
+    use crate::networking::gameserver::encoders::GameServerMessageEncoder;
+
+    fn packet(header: u16, body: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&((2 + body.len()) as i32).to_be_bytes());
+        data.extend_from_slice(&header.to_be_bytes());
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn expect_message(frame: Option<GameFrame>, header: u16, body: &[u8]) {
+        match frame {
+            Some(GameFrame::Message(message)) => {
+                assert_eq!(message.get_header(), header);
+                assert_eq!(&message.get_body()[..], body);
+            }
+            Some(GameFrame::Policy(_)) => panic!("expected a message, got a policy request"),
+            Some(GameFrame::FloodWarning) => panic!("expected a message, got a flood warning"),
+            None => panic!("expected a message, got nothing"),
+        }
+    }
+
+    #[test]
+    fn test_pipelined_packets_in_one_read() {
+        let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+        let mut buf = BytesMut::new();
+
+        for header in 0..50u16 {
+            buf.put_slice(&packet(header, &[header as u8; 3]));
+        }
+
+        for header in 0..50u16 {
+            expect_message(codec.decode(&mut buf).unwrap(), header, &[header as u8; 3]);
+        }
+
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_packets_split_across_reads() {
+        let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+        let mut stream = packet(4000, b"PRODUCTION");
+        stream.extend_from_slice(&packet(2419, b"ticket"));
+
+        // Feed the stream one byte at a time, like a very slow connection would
+        let mut buf = BytesMut::new();
+        let mut messages = Vec::new();
+        for byte in stream {
+            buf.put_u8(byte);
+            while let Some(frame) = codec.decode(&mut buf).unwrap() {
+                messages.push(frame);
+            }
+        }
+
+        assert_eq!(messages.len(), 2);
+        let mut messages = messages.into_iter();
+        expect_message(messages.next(), 4000, b"PRODUCTION");
+        expect_message(messages.next(), 2419, b"ticket");
+    }
+
+    #[test]
+    fn test_policy_request() {
+        let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+        let mut buf = BytesMut::from(&b"<policy-file-request/>\0"[..]);
+
+        match codec.decode(&mut buf).unwrap() {
+            Some(GameFrame::Policy(policy)) => assert!(policy.starts_with(b"<?xml")),
+            _ => panic!("expected a policy response"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_length_is_an_error() {
+        let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+        let mut buf = BytesMut::new();
+        buf.put_i32(i32::MAX);
+
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let mut encoder = GameServerEncoder::new(None);
+        let mut codec = GameServerCodec::new(None, CryptoAttribute::new(), None);
+        let mut message = ServerMessage::new(2491);
+        message.get_body_mut().put_slice(b"ok");
+
+        let mut buf = BytesMut::new();
+        encoder.encode(OutgoingFrame::Packet(GameServerMessageEncoder::encode_frame(message)), &mut buf).unwrap();
+        assert_eq!(&buf[..], &packet(2491, b"ok")[..]);
+
+        // The policy check only looks at the first bytes of a connection
+        codec.decode(&mut BytesMut::new()).unwrap();
+        expect_message(codec.decode(&mut buf).unwrap(), 2491, b"ok");
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let key = b"shared key from the handshake";
+        let crypto_client = CryptoAttribute::new();
+        let mut codec = GameServerCodec::new(None, crypto_client.clone(), None);
+        let mut buf = BytesMut::new();
+
+        // A plain packet before the handshake finished
+        buf.put_slice(&packet(773, b"handshake"));
+        expect_message(codec.decode(&mut buf).unwrap(), 773, b"handshake");
+
+        crypto_client.set(HabboRC4::new(key));
+
+        // The client encrypts its whole stream with its own cipher from here on
+        let mut client_cipher = HabboRC4::new(key);
+        let mut stream = packet(2419, b"ticket");
+        stream.extend_from_slice(&packet(2490, b"machine"));
+        client_cipher.parse(&mut stream);
+
+        // Deliver it in two uneven reads
+        let (first, second) = stream.split_at(5);
+        buf.put_slice(first);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.put_slice(second);
+        expect_message(codec.decode(&mut buf).unwrap(), 2419, b"ticket");
+        expect_message(codec.decode(&mut buf).unwrap(), 2490, b"machine");
+
+        // And the other direction
+        let mut encoder = GameServerEncoder::new(None);
+        let mut out = BytesMut::new();
+        encoder.encode(OutgoingFrame::Packet(Bytes::from(packet(1, b"plain"))), &mut out).unwrap();
+        encoder.encode(OutgoingFrame::EnableEncryption(Box::new(HabboRC4::new(key))), &mut out).unwrap();
+        encoder.encode(OutgoingFrame::Packet(Bytes::from(packet(2, b"secret"))), &mut out).unwrap();
+
+        let mut server_cipher = HabboRC4::new(key);
+        let (plain, encrypted) = out.split_at_mut(packet(1, b"plain").len());
+        assert_eq!(plain, &packet(1, b"plain")[..]);
+        server_cipher.parse(encrypted);
+        assert_eq!(encrypted, &packet(2, b"secret")[..]);
+    }
+}
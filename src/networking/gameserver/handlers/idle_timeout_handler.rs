@@ -1,87 +1,79 @@
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex as AsyncMutex;
 use tokio::time::sleep;
-use tokio::net::TcpStream;
 use tokio::task::JoinHandle;
-use log::{debug, warn};
+use log::debug;
 
+use crate::habbohotel::gameclients::GameClient;
 use crate::messages::client_message::ClientMessage;
-
-/// The header value for Pong packets. Replace with the real value.
-const PONG_EVENT_HEADER: u16 = 1234; // TODO: Replace with actual value
-
-/// Sends a ping to the client. Replace with actual implementation.
-pub async fn send_ping(_stream: &Arc<AsyncMutex<TcpStream>>) {
-    // TODO: Implement actual ping sending logic
-    debug!("Sending ping to client");
-}
+use crate::messages::outgoing::handshake::PingComposer;
+use crate::messages::outgoing::message_composer::MessageComposer;
 
 /// IdleTimeoutHandler for managing ping/pong and idle timeouts.
+///
+/// A client that doesn't answer the pings is disconnected, which ends its connection loop in
+/// `GameServer` and unregisters it like any other client that goes away.
 pub struct IdleTimeoutHandler {
-    /// How often to send a ping (nanoseconds)
+    /// How often to send a ping
     ping_schedule: Duration,
-    /// How long to wait for a pong (nanoseconds)
+    /// How long to wait for a pong
     pong_timeout: Duration,
+    /// Header of the pong packet in the loaded revision
+    pong_header: u16,
     /// Last time a pong was received
     last_pong: Arc<Mutex<Instant>>,
     /// Handle to the ping task
     ping_task: Option<JoinHandle<()>>,
-    /// State: 0 = none, 1 = initialized, 2 = destroyed
-    state: Arc<Mutex<u8>>,
+    /// Set once destroyed, a destroyed handler can't be started again
+    destroyed: bool,
 }
 
 impl IdleTimeoutHandler {
-    pub fn new(ping_schedule_secs: u64, pong_timeout_secs: u64) -> Self {
+    pub fn new(ping_schedule: Duration, pong_timeout: Duration, pong_header: u16) -> Self {
         let min_timeout = Duration::from_millis(1);
         Self {
-            ping_schedule: std::cmp::max(min_timeout, Duration::from_secs(ping_schedule_secs)),
-            pong_timeout: std::cmp::max(min_timeout, Duration::from_secs(pong_timeout_secs)),
+            ping_schedule: std::cmp::max(min_timeout, ping_schedule),
+            pong_timeout: std::cmp::max(min_timeout, pong_timeout),
+            pong_header,
             last_pong: Arc::new(Mutex::new(Instant::now())),
             ping_task: None,
-            state: Arc::new(Mutex::new(0)),
+            destroyed: false,
         }
     }
 
-    pub fn initialize(&mut self, stream: Arc<AsyncMutex<TcpStream>>) {
-        let mut state = self.state.lock().unwrap();
-        if *state == 1 || *state == 2 {
+    /// Starts pinging the client, the task stops by itself once the client disconnects
+    pub fn initialize(&mut self, client: Arc<GameClient>) {
+        if self.ping_task.is_some() || self.destroyed {
             return;
         }
-        *state = 1;
-        drop(state);
-        {
-            let mut last_pong = self.last_pong.lock().unwrap();
-            *last_pong = Instant::now();
-        }
+
+        *self.last_pong.lock().unwrap() = Instant::now();
+
         let ping_schedule = self.ping_schedule;
         let pong_timeout = self.pong_timeout;
         let last_pong = Arc::clone(&self.last_pong);
-        let state = Arc::clone(&self.state);
-        // Spawn ping task
+
         self.ping_task = Some(tokio::spawn(async move {
             loop {
-                sleep(ping_schedule).await;
-                if *state.lock().unwrap() != 1 {
-                    break;
+                tokio::select! {
+                    _ = client.disconnected() => break,
+                    _ = sleep(ping_schedule) => {}
                 }
-                let now = Instant::now();
+
                 let last = *last_pong.lock().unwrap();
-                if now.duration_since(last) > pong_timeout {
-                    warn!("Pong timeout, closing connection");
-                    // Close the connection
-                    let _ = stream.lock().await.shutdown().await;
+                if last.elapsed() > pong_timeout {
+                    debug!("Client {} ({}) didn't answer a ping in time, disconnecting", client.get_id(), client.get_address());
+                    client.disconnect();
                     break;
                 }
-                send_ping(&stream).await;
+
+                client.send_response(PingComposer.compose());
             }
         }));
     }
 
     pub fn destroy(&mut self) {
-        let mut state = self.state.lock().unwrap();
-        *state = 2;
-        drop(state);
+        self.destroyed = true;
         if let Some(handle) = self.ping_task.take() {
             handle.abort();
         }
@@ -89,15 +81,65 @@ impl IdleTimeoutHandler {
 
     /// Call this when a message is received from the client.
     pub fn on_client_message(&self, msg: &ClientMessage) {
-        if msg.get_header() == PONG_EVENT_HEADER {
-            let mut last_pong = self.last_pong.lock().unwrap();
-            *last_pong = Instant::now();
+        if msg.get_header() == self.pong_header {
+            *self.last_pong.lock().unwrap() = Instant::now();
         }
     }
 }
 
-// Usage example (integration required):
-// let mut handler = IdleTimeoutHandler::new(30, 60);
-// handler.initialize(client_stream.clone());
-// ... on message: handler.on_client_message(&msg);
-// ... on disconnect: handler.destroy();
+impl Drop for IdleTimeoutHandler {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    use crate::habbohotel::gameclients::QueueOverflowPolicy;
+    use crate::messages::outgoing::outgoing_headers::Outgoing;
+    use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+
+    const PONG: u16 = 2596;
+
+    fn client() -> (Arc<GameClient>, tokio::sync::mpsc::Receiver<OutgoingFrame>) {
+        let (client, receiver) = GameClient::new(1, "127.0.0.1:30000".parse().unwrap(), 64, QueueOverflowPolicy::Drop);
+        (Arc::new(client), receiver)
+    }
+
+    #[tokio::test]
+    async fn test_pongs_keep_the_client_connected() {
+        let (client, mut receiver) = client();
+        let mut handler = IdleTimeoutHandler::new(Duration::from_millis(20), Duration::from_millis(60), PONG);
+        handler.initialize(Arc::clone(&client));
+
+        for _ in 0..5 {
+            match receiver.recv().await {
+                Some(OutgoingFrame::Packet(frame)) => {
+                    assert_eq!(u16::from_be_bytes([frame[4], frame[5]]), Outgoing::PingComposer.get_header());
+                }
+                _ => panic!("expected a ping"),
+            }
+            handler.on_client_message(&ClientMessage::new(PONG, BytesMut::new()));
+        }
+
+        assert!(!client.is_disconnected());
+        handler.destroy();
+    }
+
+    #[tokio::test]
+    async fn test_missing_pong_disconnects() {
+        let (client, _receiver) = client();
+        let mut handler = IdleTimeoutHandler::new(Duration::from_millis(10), Duration::from_millis(30), PONG);
+        handler.initialize(Arc::clone(&client));
+
+        // Anything that isn't a pong doesn't count
+        handler.on_client_message(&ClientMessage::new(PONG + 1, BytesMut::new()));
+
+        tokio::time::timeout(Duration::from_secs(5), client.disconnected())
+            .await
+            .expect("the client should have been disconnected");
+    }
+}
@@ -3,6 +3,7 @@ use std::io;
 // Re-export main structs
 pub mod game_server;
 pub mod game_server_attributes;
+pub mod game_server_codec;
 
 // These modules should exist if they're needed
 pub mod decoders;
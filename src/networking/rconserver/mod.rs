@@ -1,85 +1,7 @@
-use std::io;
-use std::net::SocketAddr;
-use std::sync::Arc;
-use log::{error, info};
-use tokio::net::TcpListener;
-use tokio::runtime::Runtime;
+// Export the RCON server
+pub mod rcon_server;
+pub mod rcon_server_handler;
 
-pub struct RCONServer {
-    host: String,
-    port: u16,
-    runtime: Option<Runtime>,
-}
-
-impl RCONServer {
-    pub fn new(host: String, port: u16) -> Self {
-        RCONServer {
-            host,
-            port,
-            runtime: None,
-        }
-    }
-    
-    pub fn initialize_pipeline(&self) -> io::Result<()> {
-        // Initialize the network pipeline for RCON
-        // This would set up the command handlers and authentication
-        info!("RCON server pipeline initialized");
-        Ok(())
-    }
-    
-    pub fn connect(&self) -> io::Result<()> {
-        let host = self.host.clone();
-        let port = self.port;
-        
-        // Create a new runtime for the server
-        let runtime = Runtime::new()?;
-        
-        // Spawn the server task
-        runtime.spawn(async move {
-            let addr = format!("{host}:{port}");
-            let listener = match TcpListener::bind(&addr).await {
-                Ok(listener) => listener,
-                Err(e) => {
-                    error!("Failed to bind RCON server to {}: {}", addr, e);
-                    return;
-                }
-            };
-            
-            info!("RCON server listening on {}", addr);
-            
-            loop {
-                match listener.accept().await {
-                    Ok((socket, addr)) => {
-                        // Handle new RCON connection
-                        info!("New RCON connection from: {}", addr);
-                        
-                        // In a real implementation, we would:
-                        // 1. Authenticate the connection
-                        // 2. Set up the command handler
-                        // 3. Start processing commands
-                    }
-                    Err(e) => {
-                        error!("Failed to accept RCON connection: {}", e);
-                    }
-                }
-                
-                // Check if we should stop
-                if crate::is_shutting_down() {
-                    break;
-                }
-            }
-            
-            info!("RCON server stopped");
-        });
-        
-        info!("RCON server started on {}:{}", self.host, self.port);
-        
-        Ok(())
-    }
-    
-    pub fn disconnect(&self) -> io::Result<()> {
-        // Stop the server
-        info!("RCON server disconnected");
-        Ok(())
-    }
-}
\ No newline at end of file
+// Re-export the main structs
+pub use rcon_server::RCONServer;
+pub use rcon_server_handler::{RconCodec, RconServerHandler};
@@ -0,0 +1,117 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use log::{error, info, warn};
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+
+use crate::habbohotel::game_enviroment::GameEnvironment;
+use crate::habbohotel::gameclients::GameClientManager;
+use crate::messages::rcon::authentication::RconAllowlist;
+use crate::messages::rcon::commands::RconContext;
+use crate::messages::rcon::handlers::RconMessageHandler;
+use crate::networking::rconserver::rcon_server_handler::RconServerHandler;
+
+/// Lets the CMS control the hotel with JSON commands, see `messages::rcon`
+pub struct RCONServer {
+    host: String,
+    port: u16,
+    runtime: Mutex<Option<Runtime>>,
+    allowlist: Arc<RconAllowlist>,
+    message_handler: Arc<RconMessageHandler>,
+}
+
+impl RCONServer {
+    pub fn new(
+        host: String,
+        port: u16,
+        allowlist: RconAllowlist,
+        game_client_manager: Arc<GameClientManager>,
+        game_environment: Arc<GameEnvironment>,
+    ) -> Self {
+        RCONServer {
+            host,
+            port,
+            runtime: Mutex::new(None),
+            allowlist: Arc::new(allowlist),
+            message_handler: Arc::new(RconMessageHandler::new(RconContext::new(game_client_manager, game_environment))),
+        }
+    }
+    
+    pub fn initialize_pipeline(&self) -> io::Result<()> {
+        info!("RCON server pipeline initialized with {} commands", self.message_handler.get_command_keys().len());
+        Ok(())
+    }
+    
+    pub fn connect(&self) -> io::Result<()> {
+        let host = self.host.clone();
+        let port = self.port;
+        let allowlist = self.allowlist.clone();
+        let message_handler = self.message_handler.clone();
+        
+        // Create a new runtime for the server
+        let runtime = Runtime::new()?;
+        
+        // Spawn the server task
+        runtime.spawn(async move {
+            let addr = format!("{host}:{port}");
+            let listener = match TcpListener::bind(&addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to bind RCON server to {}: {}", addr, e);
+                    return;
+                }
+            };
+            
+            info!("RCON server listening on {}", addr);
+            
+            loop {
+                match listener.accept().await {
+                    Ok((socket, addr)) => {
+                        if !allowlist.is_allowed(addr.ip()) {
+                            warn!("Refused RCON connection from {}, it is not in rcon.allowed", addr);
+                            drop(socket);
+                            continue;
+                        }
+                        
+                        info!("New RCON connection from: {}", addr);
+                        
+                        let message_handler = message_handler.clone();
+                        tokio::spawn(async move {
+                            RconServerHandler::handle_connection(socket, addr, message_handler).await;
+                        });
+                    }
+                    Err(e) => {
+                        error!("Failed to accept RCON connection: {}", e);
+                    }
+                }
+                
+                // Check if we should stop
+                if crate::is_shutting_down() {
+                    break;
+                }
+            }
+            
+            info!("RCON server stopped");
+        });
+        
+        // The runtime has to outlive this call or the listener is dropped with it
+        *self.runtime.lock().unwrap() = Some(runtime);
+        
+        info!("RCON server started on {}:{}", self.host, self.port);
+        
+        Ok(())
+    }
+    
+    pub fn disconnect(&self) -> io::Result<()> {
+        if let Some(runtime) = self.runtime.lock().unwrap().take() {
+            runtime.shutdown_background();
+        }
+        
+        info!("RCON server disconnected");
+        Ok(())
+    }
+    
+    pub fn get_message_handler(&self) -> Arc<RconMessageHandler> {
+        self.message_handler.clone()
+    }
+}
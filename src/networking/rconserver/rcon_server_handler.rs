@@ -0,0 +1,218 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::{SinkExt, StreamExt};
+use log::debug;
+use serde::de::IgnoredAny;
+use tokio::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+use crate::messages::rcon::handlers::RconMessageHandler;
+
+/// Biggest request we accept, anything above that is not a command
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// How requests on a connection are framed, picked from the first byte the CMS sends.
+/// Responses are framed the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RconFraming {
+    /// JSON documents one after the other, usually one per line
+    Json,
+    /// Every document is preceded by its length as a big-endian int
+    LengthPrefixed,
+}
+
+/// Splits an RCON connection into single JSON requests
+pub struct RconCodec {
+    framing: Option<RconFraming>,
+}
+
+impl RconCodec {
+    pub fn new() -> Self {
+        Self { framing: None }
+    }
+
+    pub fn get_framing(&self) -> Option<RconFraming> {
+        self.framing
+    }
+
+    fn decode_json(src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        let mut documents = serde_json::Deserializer::from_slice(src).into_iter::<IgnoredAny>();
+
+        match documents.next() {
+            Some(Ok(_)) => {
+                let end = documents.byte_offset();
+                Ok(Some(src.split_to(end)))
+            }
+            Some(Err(e)) if e.is_eof() => {
+                if src.len() > MAX_MESSAGE_SIZE {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "RCON request too large"));
+                }
+                Ok(None)
+            }
+            Some(Err(e)) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            None => Ok(None),
+        }
+    }
+
+    fn decode_length_prefixed(src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        if src.len() < 4 {
+            return Ok(None);
+        }
+
+        let length = i32::from_be_bytes([src[0], src[1], src[2], src[3]]);
+        if length < 0 || length as usize > MAX_MESSAGE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid RCON request length {}", length)));
+        }
+
+        let length = length as usize;
+        if src.len() < 4 + length {
+            src.reserve(4 + length - src.len());
+            return Ok(None);
+        }
+
+        src.advance(4);
+        Ok(Some(src.split_to(length)))
+    }
+}
+
+impl Default for RconCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for RconCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let framing = match self.framing {
+            Some(framing) => framing,
+            None => {
+                // Newlines or spaces in front of the first document don't tell us anything yet
+                let skip = src.iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+                src.advance(skip);
+
+                let framing = match src.first() {
+                    Some(b'{') => RconFraming::Json,
+                    Some(_) => RconFraming::LengthPrefixed,
+                    None => return Ok(None),
+                };
+                self.framing = Some(framing);
+                framing
+            }
+        };
+
+        match framing {
+            RconFraming::Json => {
+                // The whitespace between documents
+                let skip = src.iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+                src.advance(skip);
+                Self::decode_json(src)
+            }
+            RconFraming::LengthPrefixed => Self::decode_length_prefixed(src),
+        }
+    }
+}
+
+impl Encoder<Bytes> for RconCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match self.framing {
+            Some(RconFraming::LengthPrefixed) => {
+                dst.put_i32(item.len() as i32);
+                dst.extend_from_slice(&item);
+            }
+            _ => {
+                dst.extend_from_slice(&item);
+                dst.put_u8(b'\n');
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Serves the requests of a single RCON connection until the CMS hangs up
+pub struct RconServerHandler;
+
+impl RconServerHandler {
+    pub async fn handle_connection(socket: TcpStream, addr: SocketAddr, message_handler: Arc<RconMessageHandler>) {
+        let mut framed = Framed::new(socket, RconCodec::new());
+
+        while let Some(request) = framed.next().await {
+            let request = match request {
+                Ok(request) => request,
+                Err(e) => {
+                    debug!("Closing RCON connection from {}: {}", addr, e);
+                    break;
+                }
+            };
+
+            let response = message_handler.handle(&request).await;
+            let response = match serde_json::to_vec(&response) {
+                Ok(response) => response,
+                Err(e) => {
+                    debug!("Failed to serialize RCON response: {}", e);
+                    break;
+                }
+            };
+
+            if let Err(e) = framed.send(Bytes::from(response)).await {
+                debug!("Failed to write to RCON connection from {}: {}", addr, e);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_documents() {
+        let mut codec = RconCodec::new();
+        let mut buf = BytesMut::from(&b"\n{\"key\": \"a\", \"data\": {\"message\": \"}\"}}\n{\"key\": \"b\"}{\"key\""[..]);
+
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], br#"{"key": "a", "data": {"message": "}"}}"#);
+        assert_eq!(codec.get_framing(), Some(RconFraming::Json));
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], br#"{"key": "b"}"#);
+
+        // The rest of the last document is still on its way
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b": \"c\"}");
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], br#"{"key": "c"}"#);
+
+        let mut out = BytesMut::new();
+        codec.encode(Bytes::from_static(b"{}"), &mut out).unwrap();
+        assert_eq!(&out[..], b"{}\n");
+
+        buf.extend_from_slice(b"{\"key\": ]");
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_length_prefixed_documents() {
+        let mut codec = RconCodec::new();
+        let request = br#"{"key": "updatecatalog"}"#;
+        let mut buf = BytesMut::new();
+        buf.put_i32(request.len() as i32);
+        buf.extend_from_slice(&request[..10]);
+
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert_eq!(codec.get_framing(), Some(RconFraming::LengthPrefixed));
+        buf.extend_from_slice(&request[10..]);
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], &request[..]);
+
+        let mut out = BytesMut::new();
+        codec.encode(Bytes::from_static(b"{}"), &mut out).unwrap();
+        assert_eq!(&out[..], b"\0\0\0\x02{}");
+
+        buf.put_i32(-1);
+        assert!(codec.decode(&mut buf).is_err());
+    }
+}
@@ -1,31 +1,31 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use std::io;
 use log::{debug, error, info, warn};
 
+use crate::habbohotel::gameclients::GameClient;
 use crate::messages::client_message::ClientMessage;
-use crate::networking::gameserver::game_server::GameServer;
+use crate::messages::packet_manager::PacketManager;
 
 pub struct ChannelReadHandler {
-    channel: Arc<Mutex<tokio::net::TcpStream>>,
+    client: Arc<GameClient>,
     message: ClientMessage,
-    game_server: Arc<GameServer>,
+    packet_manager: Arc<PacketManager>,
 }
 
 impl ChannelReadHandler {
     pub fn new(
-        channel: Arc<Mutex<tokio::net::TcpStream>>,
+        client: Arc<GameClient>,
         message: ClientMessage,
-        game_server: Arc<GameServer>,
+        packet_manager: Arc<PacketManager>,
     ) -> Self {
         Self {
-            channel,
+            client,
             message,
-            game_server,
+            packet_manager,
         }
     }
 
-    pub async fn run(&self) -> io::Result<()> {
+    pub async fn run(self) -> io::Result<()> {
         // Get the packet from the message
         let header = self.message.get_header();
         
@@ -33,20 +33,15 @@ impl ChannelReadHandler {
         debug!("Processing packet: {}", header);
 
         // Handle the packet through the packet manager
-        if let Some(packet_manager) = self.game_server.get_packet_manager() {
-            match packet_manager.handle(&self.message, Arc::clone(&self.channel)).await {
-                Ok(_) => {
-                    debug!("Successfully handled packet: {}", header);
-                    Ok(())
-                }
-                Err(e) => {
-                    error!("Error handling packet {}: {:?}", header, e);
-                    Err(e)
-                }
+        match self.packet_manager.handle(self.message, self.client).await {
+            Ok(_) => {
+                debug!("Successfully handled packet: {}", header);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Error handling packet {}: {:?}", header, e);
+                Err(e)
             }
-        } else {
-            error!("No packet manager available to handle message: {}", header);
-            Err(io::Error::new(io::ErrorKind::Other, "No packet manager available"))
         }
     }
 }
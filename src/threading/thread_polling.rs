@@ -73,6 +73,17 @@ impl ThreadPooling {
         })
     }
     
+    /// Stops the pool, running tasks get up to `timeout` to finish
+    pub fn shutdown(self, timeout: Duration) {
+        match Arc::try_unwrap(self.runtime) {
+            Ok(runtime) => runtime.shutdown_timeout(timeout),
+            // Someone still holds the runtime, it stops once they let go of it
+            Err(_) => error!("Thread pool is still in use, it can't be shut down"),
+        }
+        
+        info!("Thread pool shut down");
+    }
+    
     pub fn get_thread_count(&self) -> usize {
         self.thread_count
     }
@@ -20,4 +20,9 @@ impl DebugUtils {
         
         None
     }
-}
\ No newline at end of file
+
+    // Get the full stack trace of the current thread, whether or not RUST_BACKTRACE is set
+    pub fn get_stacktrace() -> String {
+        Backtrace::force_capture().to_string()
+    }
+}
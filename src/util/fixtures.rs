@@ -0,0 +1,64 @@
+//! Users, rooms and clients the tests build on
+
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::database::repositories::{RoomRecord, UserRecord, UserSettingsRecord};
+use crate::habbohotel::gameclients::{GameClient, QueueOverflowPolicy};
+use crate::habbohotel::users::{Habbo, HabboInfo};
+use crate::networking::gameserver::game_server_codec::OutgoingFrame;
+
+/// A user of rank 1 without credits, named after its id
+pub fn user(id: i32) -> UserRecord {
+    UserRecord {
+        id,
+        username: format!("user{}", id),
+        motto: String::new(),
+        look: String::new(),
+        gender: "M".to_string(),
+        rank: 1,
+        credits: 0,
+        home_room: 0,
+    }
+}
+
+/// An open room of user 1 built on a model
+pub fn room(id: i32, model: &str) -> RoomRecord {
+    RoomRecord {
+        id,
+        owner_id: 1,
+        owner_name: "user1".to_string(),
+        name: "Test".to_string(),
+        description: String::new(),
+        model: model.to_string(),
+        state: "open".to_string(),
+        users_max: 25,
+        category: 1,
+        score: 0,
+        allow_walkthrough: false,
+    }
+}
+
+/// A client that didn't log in, each id connects from its own port
+pub fn client(id: u64) -> (Arc<GameClient>, mpsc::Receiver<OutgoingFrame>) {
+    let address = format!("127.0.0.1:{}", 30000 + id).parse().unwrap();
+    let (client, receiver) = GameClient::new(id, address, 64, QueueOverflowPolicy::Disconnect);
+    (Arc::new(client), receiver)
+}
+
+/// A client logged in as a user
+pub fn habbo(user: UserRecord) -> (Arc<Habbo>, mpsc::Receiver<OutgoingFrame>) {
+    let (client, receiver) = client(user.id as u64);
+    let habbo = Arc::new(Habbo::new(client.clone(), HabboInfo::new(user, Vec::new()), UserSettingsRecord::default()));
+    client.set_habbo(habbo.clone());
+    (habbo, receiver)
+}
+
+/// Headers of the packets queued for a client, they are taken off the queue
+pub fn headers(receiver: &mut mpsc::Receiver<OutgoingFrame>) -> Vec<u16> {
+    let mut headers = Vec::new();
+    while let Ok(OutgoingFrame::Packet(frame)) = receiver.try_recv() {
+        headers.push(u16::from_be_bytes([frame[4], frame[5]]));
+    }
+    headers
+}
@@ -1,14 +1,3 @@
-pub struct SqlExceptionFilter;
+pub mod sql_exception_filter;
 
-impl SqlExceptionFilter {
-    pub fn new() -> Self {
-        SqlExceptionFilter {}
-    }
-    
-    // Filter SQL exceptions for logging
-    pub fn filter(&self, message: &str) -> bool {
-        // This would contain logic to filter SQL exceptions
-        // For now, just return true for all messages
-        true
-    }
-}
\ No newline at end of file
+pub use sql_exception_filter::SqlExceptionFilter;
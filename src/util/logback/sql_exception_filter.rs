@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::warn;
+
+/// Errors remembered at most, older ones are forgotten once their window passed
+/// or, while all of them are still within it, the oldest one is
+const MAX_REMEMBERED: usize = 1000;
+
+struct SeenError {
+    since: Instant,
+    repeats: u32,
+}
+
+/// Keeps one failing query from flooding the error log. The first error is let through,
+/// the same error again within the window is only counted.
+pub struct SqlExceptionFilter {
+    window: Duration,
+    seen: Mutex<HashMap<String, SeenError>>,
+}
+
+impl SqlExceptionFilter {
+    pub fn new(window: Duration) -> Self {
+        SqlExceptionFilter {
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the error should be logged
+    pub fn filter(&self, message: &str) -> bool {
+        self.filter_at(message, Instant::now())
+    }
+
+    fn filter_at(&self, message: &str, now: Instant) -> bool {
+        let key = Self::normalize(message);
+        let mut seen = self.seen.lock().unwrap();
+
+        if seen.len() >= MAX_REMEMBERED && !seen.contains_key(&key) {
+            seen.retain(|_, error| now.duration_since(error.since) < self.window);
+
+            if seen.len() >= MAX_REMEMBERED
+                && let Some(oldest) = seen.iter().min_by_key(|(_, error)| error.since).map(|(key, _)| key.clone()) {
+                seen.remove(&oldest);
+            }
+        }
+
+        match seen.get_mut(&key) {
+            Some(error) if now.duration_since(error.since) < self.window => {
+                error.repeats += 1;
+                false
+            }
+            Some(error) => {
+                if error.repeats > 0 {
+                    warn!("SQL error repeated {} times in the last {:?}: {}", error.repeats, self.window, message);
+                }
+                error.since = now;
+                error.repeats = 0;
+                true
+            }
+            None => {
+                seen.insert(key, SeenError { since: now, repeats: 0 });
+                true
+            }
+        }
+    }
+
+    // The same query failing for another id is the same error
+    fn normalize(message: &str) -> String {
+        let mut key = String::with_capacity(message.len());
+        for c in message.chars() {
+            if !c.is_ascii_digit() {
+                key.push(c);
+            } else if !key.ends_with('#') {
+                key.push('#');
+            }
+        }
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_repeats_within_window() {
+        let filter = SqlExceptionFilter::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert!(filter.filter_at("Duplicate entry '12' for key 'PRIMARY'", now));
+        assert!(!filter.filter_at("Duplicate entry '13' for key 'PRIMARY'", now + Duration::from_secs(10)));
+        assert!(filter.filter_at("Table 'rooms' doesn't exist", now + Duration::from_secs(10)));
+        assert!(filter.filter_at("Duplicate entry '14' for key 'PRIMARY'", now + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn test_forget_oldest_when_full() {
+        let filter = SqlExceptionFilter::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        for i in 0..MAX_REMEMBERED {
+            assert!(filter.filter_at(&format!("Unknown column 'c{}'", "x".repeat(i)), now + Duration::from_millis(i as u64)));
+        }
+        assert!(filter.filter_at("Table 'rooms' doesn't exist", now + Duration::from_secs(1)));
+
+        assert_eq!(filter.seen.lock().unwrap().len(), MAX_REMEMBERED);
+        assert!(filter.filter_at("Unknown column 'c'", now + Duration::from_secs(2)));
+        assert!(!filter.filter_at("Unknown column 'cxx'", now + Duration::from_secs(2)));
+    }
+}
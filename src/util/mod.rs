@@ -1,4 +1,10 @@
 pub mod ansi;
+pub mod debug_utils;
+#[cfg(test)]
+pub mod fixtures;
+pub mod logback;
+pub mod packet_utils;
+pub mod pathfinding;
 
 // Re-export commonly used utilities
 pub use ansi::*;
@@ -0,0 +1,281 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::core::configuration_manager::ConfigurationManager;
+use crate::habbohotel::rooms::{RoomLayout, RoomTile, RoomTileState};
+use crate::util::pathfinding::Rotation;
+
+/// Cost of a straight step, a diagonal one costs `DIAGONAL_COST`. Close to 1 and √2.
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NO_PARENT: u32 = u32::MAX;
+
+/// How users may move through a room
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathfinderOptions {
+    /// Highest step up onto an open tile, falling down is always allowed
+    pub max_step_height: f64,
+    pub allow_diagonals: bool,
+    /// Lowest rank that walks freely, see `WalkOptions::free_walk`
+    pub free_walk_rank: i32,
+}
+
+impl Default for PathfinderOptions {
+    fn default() -> Self {
+        PathfinderOptions {
+            max_step_height: 1.1,
+            allow_diagonals: true,
+            free_walk_rank: i32::MAX,
+        }
+    }
+}
+
+impl PathfinderOptions {
+    /// Options as configured by the `pathfinder.*` settings
+    pub fn load(config: &ConfigurationManager) -> Self {
+        let defaults = Self::default();
+
+        PathfinderOptions {
+            max_step_height: config.get_f64("pathfinder.step.maximum.height").unwrap_or(defaults.max_step_height),
+            allow_diagonals: config.get_bool("pathfinder.diagonals").unwrap_or(defaults.allow_diagonals),
+            free_walk_rank: config.get_int("pathfinder.free_walk.rank").unwrap_or(defaults.free_walk_rank),
+        }
+    }
+}
+
+/// How one user walks, it depends on who they are and the room they are in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Users may walk over tiles other users stand on, the room setting
+    pub walk_through_users: bool,
+    /// Staff walking anywhere on the heightmap, over furniture, users and heights
+    pub free_walk: bool,
+}
+
+/// A* over the tiles of a room layout. Open, sit and lay tiles can be walked on, a diagonal
+/// step can't cut the corner of a blocked tile and stepping up is capped by `max_step_height`,
+/// except onto seats and beds whose height is that of the furniture.
+pub struct Pathfinder {
+    options: PathfinderOptions,
+}
+
+impl Pathfinder {
+    pub fn new(options: PathfinderOptions) -> Self {
+        Pathfinder { options }
+    }
+
+    pub fn get_options(&self) -> &PathfinderOptions {
+        &self.options
+    }
+
+    /// Tiles to walk from one tile to another, without the start tile. `is_occupied` tells
+    /// whether a user stands on a tile. None when there is no way to get there, an empty path
+    /// when already there.
+    pub fn find_path<F>(&self, layout: &RoomLayout, from: (i16, i16), to: (i16, i16), walk: WalkOptions, is_occupied: F) -> Option<Vec<RoomTile>>
+    where
+        F: Fn(i16, i16) -> bool,
+    {
+        let start = layout.get_tile(from.0 as i32, from.1 as i32)?;
+        let goal = layout.get_tile(to.0 as i32, to.1 as i32)?;
+
+        if from == to {
+            return Some(Vec::new());
+        }
+        if !Self::can_stand_on(goal, walk, &is_occupied) {
+            return None;
+        }
+
+        let width = layout.get_map_size_x() as usize;
+        let index = |tile: &RoomTile| tile.y as usize * width + tile.x as usize;
+        let tiles = layout.get_tiles();
+
+        let mut costs = vec![u32::MAX; tiles.len()];
+        let mut parents = vec![NO_PARENT; tiles.len()];
+        let mut closed = vec![false; tiles.len()];
+        let mut open = BinaryHeap::new();
+
+        costs[index(start)] = 0;
+        open.push(Reverse((self.estimate(start, goal), 0, index(start) as u32)));
+
+        let directions: &[Rotation] = match self.options.allow_diagonals {
+            true => &[
+                Rotation::North, Rotation::East, Rotation::South, Rotation::West,
+                Rotation::NorthEast, Rotation::SouthEast, Rotation::SouthWest, Rotation::NorthWest,
+            ],
+            false => &[Rotation::North, Rotation::East, Rotation::South, Rotation::West],
+        };
+
+        while let Some(Reverse((_, cost, current))) = open.pop() {
+            let current = current as usize;
+            if closed[current] {
+                continue;
+            }
+            closed[current] = true;
+
+            let tile = &tiles[current];
+            if tile.x == goal.x && tile.y == goal.y {
+                return Some(Self::build_path(tiles, &parents, current));
+            }
+
+            for rotation in directions {
+                let (dx, dy) = rotation.get_delta();
+                let next = match layout.get_tile(tile.x as i32 + dx, tile.y as i32 + dy) {
+                    Some(next) => next,
+                    None => continue,
+                };
+
+                let next_index = index(next);
+                if closed[next_index] || !self.can_step(layout, tile, next, *rotation, walk, &is_occupied) {
+                    continue;
+                }
+
+                let next_cost = cost + if rotation.is_diagonal() { DIAGONAL_COST } else { STRAIGHT_COST };
+                if next_cost < costs[next_index] {
+                    costs[next_index] = next_cost;
+                    parents[next_index] = current as u32;
+                    open.push(Reverse((next_cost + self.estimate(next, goal), next_cost, next_index as u32)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Whether a step from one tile to its neighbour in the given direction is allowed
+    fn can_step<F>(&self, layout: &RoomLayout, from: &RoomTile, to: &RoomTile, rotation: Rotation, walk: WalkOptions, is_occupied: &F) -> bool
+    where
+        F: Fn(i16, i16) -> bool,
+    {
+        if !Self::can_stand_on(to, walk, is_occupied) {
+            return false;
+        }
+
+        if walk.free_walk {
+            return true;
+        }
+
+        if to.state == RoomTileState::Open && from.state == RoomTileState::Open
+            && to.stack_height - from.stack_height > self.options.max_step_height
+        {
+            return false;
+        }
+
+        // No squeezing past the corner of a wall or blocking furniture
+        if rotation.is_diagonal() {
+            let (dx, dy) = rotation.get_delta();
+            let blocks = |x: i32, y: i32| layout.get_tile(x, y).is_none_or(|tile| !tile.is_walkable());
+
+            if blocks(from.x as i32 + dx, from.y as i32) || blocks(from.x as i32, from.y as i32 + dy) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn can_stand_on<F>(tile: &RoomTile, walk: WalkOptions, is_occupied: &F) -> bool
+    where
+        F: Fn(i16, i16) -> bool,
+    {
+        if tile.state == RoomTileState::Invalid {
+            return false;
+        }
+
+        if walk.free_walk {
+            return true;
+        }
+
+        tile.is_walkable() && (walk.walk_through_users || !is_occupied(tile.x, tile.y))
+    }
+
+    /// Octile distance, exact when nothing is in the way
+    fn estimate(&self, from: &RoomTile, to: &RoomTile) -> u32 {
+        let dx = (from.x - to.x).unsigned_abs() as u32;
+        let dy = (from.y - to.y).unsigned_abs() as u32;
+
+        match self.options.allow_diagonals {
+            true => STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy),
+            false => STRAIGHT_COST * (dx + dy),
+        }
+    }
+
+    fn build_path(tiles: &[RoomTile], parents: &[u32], goal: usize) -> Vec<RoomTile> {
+        let mut path = Vec::new();
+        let mut current = goal;
+
+        while parents[current] != NO_PARENT {
+            path.push(tiles[current]);
+            current = parents[current] as usize;
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(heightmap: &str) -> RoomLayout {
+        RoomLayout::parse("model_test", 0, 0, 2, heightmap).unwrap()
+    }
+
+    fn positions(path: &[RoomTile]) -> Vec<(i16, i16)> {
+        path.iter().map(|tile| (tile.x, tile.y)).collect()
+    }
+
+    #[test]
+    fn test_diagonals_and_corners() {
+        let pathfinder = Pathfinder::new(PathfinderOptions::default());
+        let open = layout("000\r\n000\r\n000");
+        let path = pathfinder.find_path(&open, (0, 0), (2, 2), WalkOptions::default(), |_, _| false).unwrap();
+        assert_eq!(positions(&path), [(1, 1), (2, 2)]);
+        assert!(pathfinder.find_path(&open, (1, 1), (1, 1), WalkOptions::default(), |_, _| false).unwrap().is_empty());
+
+        // The wall at 1,0 keeps the path from cutting its corner
+        let corner = layout("0x\r\n00");
+        let path = pathfinder.find_path(&corner, (0, 0), (1, 1), WalkOptions::default(), |_, _| false).unwrap();
+        assert_eq!(positions(&path), [(0, 1), (1, 1)]);
+
+        let mut blocked = layout("000\r\n000\r\n000");
+        for x in 0..3 {
+            blocked.get_tile_mut(x, 1).unwrap().state = RoomTileState::Blocked;
+        }
+        assert!(pathfinder.find_path(&blocked, (0, 0), (2, 2), WalkOptions::default(), |_, _| false).is_none());
+        let free_walk = WalkOptions { free_walk: true, ..WalkOptions::default() };
+        assert!(pathfinder.find_path(&blocked, (0, 0), (2, 2), free_walk, |_, _| false).is_some());
+    }
+
+    #[test]
+    fn test_heights_and_seats() {
+        let pathfinder = Pathfinder::new(PathfinderOptions::default());
+
+        // Two steps up is too high, going round over the 1 works and falling down is fine
+        let stairs = layout("020\r\n010");
+        let path = pathfinder.find_path(&stairs, (0, 0), (2, 0), WalkOptions::default(), |_, _| false).unwrap();
+        assert_eq!(positions(&path), [(1, 1), (2, 0)]);
+        assert!(pathfinder.find_path(&stairs, (1, 0), (0, 0), WalkOptions::default(), |_, _| false).is_some());
+
+        // A chair is higher than a step but can still be sat on
+        let mut chair = layout("00");
+        let seat = chair.get_tile_mut(1, 0).unwrap();
+        seat.state = RoomTileState::Sit;
+        seat.stack_height = 3.0;
+        assert_eq!(positions(&pathfinder.find_path(&chair, (0, 0), (1, 0), WalkOptions::default(), |_, _| false).unwrap()), [(1, 0)]);
+    }
+
+    #[test]
+    fn test_walk_through_users() {
+        let pathfinder = Pathfinder::new(PathfinderOptions::default());
+        let corridor = layout("000");
+        let occupied = |x, y| (x, y) == (1, 0);
+
+        assert!(pathfinder.find_path(&corridor, (0, 0), (2, 0), WalkOptions::default(), occupied).is_none());
+        assert!(pathfinder.find_path(&corridor, (0, 0), (1, 0), WalkOptions::default(), occupied).is_none());
+        let walk_through = WalkOptions { walk_through_users: true, ..WalkOptions::default() };
+        let path = pathfinder.find_path(&corridor, (0, 0), (2, 0), walk_through, occupied).unwrap();
+        assert_eq!(positions(&path), [(1, 0), (2, 0)]);
+    }
+}
@@ -0,0 +1,86 @@
+/// Direction a user or item faces, clockwise from north. North is towards the top of the
+/// heightmap (y - 1), east towards its right (x + 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    North = 0,
+    NorthEast = 1,
+    East = 2,
+    SouthEast = 3,
+    South = 4,
+    SouthWest = 5,
+    West = 6,
+    NorthWest = 7,
+}
+
+impl Rotation {
+    pub fn from_value(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Rotation::North),
+            1 => Some(Rotation::NorthEast),
+            2 => Some(Rotation::East),
+            3 => Some(Rotation::SouthEast),
+            4 => Some(Rotation::South),
+            5 => Some(Rotation::SouthWest),
+            6 => Some(Rotation::West),
+            7 => Some(Rotation::NorthWest),
+            _ => None,
+        }
+    }
+    
+    pub fn to_value(&self) -> i32 {
+        *self as i32
+    }
+
+    /// Direction to face when looking from one tile at another, North when they are the same
+    pub fn calculate(from_x: i32, from_y: i32, to_x: i32, to_y: i32) -> Self {
+        match ((to_x - from_x).signum(), (to_y - from_y).signum()) {
+            (0, -1) => Rotation::North,
+            (1, -1) => Rotation::NorthEast,
+            (1, 0) => Rotation::East,
+            (1, 1) => Rotation::SouthEast,
+            (0, 1) => Rotation::South,
+            (-1, 1) => Rotation::SouthWest,
+            (-1, 0) => Rotation::West,
+            (-1, -1) => Rotation::NorthWest,
+            _ => Rotation::North,
+        }
+    }
+
+    /// Tile offset of one step in this direction
+    pub fn get_delta(&self) -> (i32, i32) {
+        match self {
+            Rotation::North => (0, -1),
+            Rotation::NorthEast => (1, -1),
+            Rotation::East => (1, 0),
+            Rotation::SouthEast => (1, 1),
+            Rotation::South => (0, 1),
+            Rotation::SouthWest => (-1, 1),
+            Rotation::West => (-1, 0),
+            Rotation::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.to_value() % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate() {
+        assert_eq!(Rotation::calculate(5, 5, 5, 2), Rotation::North);
+        assert_eq!(Rotation::calculate(5, 5, 9, 1), Rotation::NorthEast);
+        assert_eq!(Rotation::calculate(5, 5, 6, 5), Rotation::East);
+        assert_eq!(Rotation::calculate(5, 5, 3, 8), Rotation::SouthWest);
+        assert_eq!(Rotation::calculate(5, 5, 5, 5), Rotation::North);
+
+        for value in 0..8 {
+            let rotation = Rotation::from_value(value).unwrap();
+            let (dx, dy) = rotation.get_delta();
+            assert_eq!(Rotation::calculate(0, 0, dx, dy), rotation);
+        }
+    }
+}